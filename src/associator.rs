@@ -0,0 +1,166 @@
+use std::collections::HashMap;
+
+use crate::{nodes::Begin, Comment, Node};
+
+/// Maps comments to the nodes they document.
+///
+/// A comment that ends the line of some node (`foo # comment`) belongs to it,
+/// any other comment belongs to the next node (or to the previous one at the end of the file).
+/// Among nodes that start (or end) at the same place the outermost one is picked.
+///
+/// Comments are associated once on construction, and nodes are identified by their address,
+/// so lookups only work for nodes that are borrowed from the same tree.
+#[derive(Debug)]
+pub struct Associator<'a> {
+    // every comment with its owner, in the order of comments
+    associations: Vec<(Comment, &'a Node)>,
+    by_node: HashMap<*const Node, Vec<Comment>>,
+}
+
+impl<'a> Associator<'a> {
+    /// Constructs an associator, `input` must be the source code of the `ast`
+    pub fn new(ast: &'a Node, comments: &'a [Comment], input: &'a [u8]) -> Self {
+        let mut nodes = vec![];
+        collect_nodes(ast, &mut nodes);
+
+        let associations: Vec<(Comment, &'a Node)> = comments
+            .iter()
+            .filter_map(|comment| owner_of(comment, &nodes, input).map(|node| (*comment, node)))
+            .collect();
+
+        let mut by_node: HashMap<*const Node, Vec<Comment>> = HashMap::new();
+        for (comment, node) in associations.iter() {
+            by_node
+                .entry(*node as *const Node)
+                .or_default()
+                .push(*comment);
+        }
+
+        Self {
+            associations,
+            by_node,
+        }
+    }
+
+    /// Returns every comment with the node it belongs to, in the order of comments
+    pub fn associate(&self) -> Vec<(Comment, &'a Node)> {
+        self.associations.clone()
+    }
+
+    /// Returns comments that belong to the given node of the `ast`
+    pub fn comments_for(&self, node: &Node) -> Vec<Comment> {
+        self.by_node
+            .get(&(node as *const Node))
+            .cloned()
+            .unwrap_or_default()
+    }
+}
+
+fn owner_of<'a>(comment: &Comment, nodes: &[&'a Node], input: &[u8]) -> Option<&'a Node> {
+    let mut preceding: Option<&'a Node> = None;
+    for node in nodes.iter().copied() {
+        let end = node.expression().end;
+        if end <= comment.loc.start
+            && preceding.is_none_or(|preceding| end > preceding.expression().end)
+        {
+            preceding = Some(node);
+        }
+    }
+
+    if let Some(preceding) = preceding {
+        if !has_newline_between(input, preceding.expression().end, comment.loc.start) {
+            return Some(preceding);
+        }
+    }
+
+    nodes
+        .iter()
+        .copied()
+        .find(|node| node.expression().start >= comment.loc.end)
+        .or(preceding)
+}
+
+fn has_newline_between(input: &[u8], start: usize, end: usize) -> bool {
+    input
+        .get(start..end)
+        .is_none_or(|bytes| bytes.contains(&b'\n'))
+}
+
+// Collects nodes in pre-order (i.e. outer nodes go first),
+// implicit `begin` (a list of statements without parens) is not a meaningful owner
+fn collect_nodes<'a>(node: &'a Node, nodes: &mut Vec<&'a Node>) {
+    if !matches!(node, Node::Begin(Begin { begin_l: None, .. })) {
+        nodes.push(node);
+    }
+    for child in node.child_nodes() {
+        collect_nodes(child, nodes);
+    }
+}
+
+#[cfg(test)]
+fn associated(
+    rule_parse: fn(&mut crate::Parser) -> crate::error::ParseResult<Box<Node>>,
+    input: &[u8],
+) -> Vec<(String, String)> {
+    let mut parser = crate::Parser::new(input);
+    let ast = rule_parse(&mut parser).unwrap();
+    let comments = parser.take_comments();
+
+    Associator::new(&ast, &comments, input)
+        .associate()
+        .into_iter()
+        .map(|(comment, node)| {
+            let comment = String::from_utf8_lossy(&input[comment.loc.to_range()]).into_owned();
+            (comment, node.str_type().to_string())
+        })
+        .collect()
+}
+
+#[test]
+fn test_leading_comment() {
+    use crate::parser::{base::Rule, Array};
+
+    assert_eq!(
+        associated(
+            Array::parse,
+            b"# outer doc\n[\n  # inner doc\n  # second line\n  foo: 1\n]"
+        ),
+        vec![
+            ("# outer doc".to_string(), "array".to_string()),
+            ("# inner doc".to_string(), "pair".to_string()),
+            ("# second line".to_string(), "pair".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn test_trailing_comment() {
+    use crate::parser::{base::Rule, Array};
+
+    assert_eq!(
+        associated(Array::parse, b"[\n  1, # one\n  2 # two\n  # end\n]"),
+        vec![
+            ("# one".to_string(), "int".to_string()),
+            ("# two".to_string(), "int".to_string()),
+            ("# end".to_string(), "int".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn test_comments_for() {
+    use crate::parser::{base::Rule, Array};
+
+    let input = b"[\n  1,\n  # doc\n  2\n]";
+    let mut parser = crate::Parser::new(input);
+    let ast = Array::parse(&mut parser).unwrap();
+    let comments = parser.take_comments();
+    let item = match &*ast {
+        Node::Array(array) => &array.elements[1],
+        _ => unreachable!(),
+    };
+
+    let associator = Associator::new(&ast, &comments, input);
+    assert_eq!(associator.comments_for(item), comments);
+    assert!(associator.comments_for(&ast).is_empty());
+}
@@ -1,16 +1,22 @@
-use crate::buffer::Pattern;
+use std::sync::OnceLock;
 
-#[derive(Debug)]
+use crate::buffer::{LineIndex, Pattern};
+
+/// Source code that is (or has been) parsed
+#[derive(Debug, Default)]
 pub struct Buffer {
     bytes: Vec<u8>,
     pub(crate) unescaped_bytes: Vec<u8>,
+    pub(crate) line_index: OnceLock<LineIndex>,
 }
 
 impl Buffer {
-    pub(crate) fn new(bytes: &[u8]) -> Self {
+    /// Constructs a buffer from the given source code
+    pub fn new(bytes: &[u8]) -> Self {
         Self {
             bytes: bytes.to_vec(),
             unescaped_bytes: vec![],
+            line_index: OnceLock::new(),
         }
     }
 
@@ -31,6 +31,11 @@ impl BufferWithCursor {
         &mut self.buffer
     }
 
+    // Takes the buffer out once it's fully processed
+    pub(crate) fn take_buffer(&mut self) -> Buffer {
+        std::mem::take(&mut self.buffer)
+    }
+
     pub(crate) fn skip_byte(&mut self) {
         self.pos += 1;
     }
@@ -0,0 +1,160 @@
+use crate::{
+    buffer::{Buffer, PositionEncoding},
+    loc::loc,
+    Loc,
+};
+
+// Locations of all lines of the buffer (without trailing `\n` or `\r\n`),
+// built once per `Buffer` on the first line/column lookup.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct LineIndex {
+    lines: Vec<Loc>,
+}
+
+impl LineIndex {
+    pub(crate) fn new(bytes: &[u8]) -> Self {
+        let mut lines = vec![];
+        let mut start = 0;
+        for (idx, byte) in bytes.iter().enumerate() {
+            if *byte == b'\n' {
+                let end = if idx > start && bytes[idx - 1] == b'\r' {
+                    idx - 1
+                } else {
+                    idx
+                };
+                lines.push(loc!(start, end));
+                start = idx + 1;
+            }
+        }
+        lines.push(loc!(start, bytes.len()));
+        Self { lines }
+    }
+
+    // Returns 0-based index of the line that contains given byte offset,
+    // offsets of `\n` and of the end of input belong to the line that they finish
+    pub(crate) fn line_idx_for_pos(&self, pos: usize) -> Option<usize> {
+        let last = self.lines.last()?;
+        if pos > last.end {
+            return None;
+        }
+        // first line that starts after `pos`, `pos` is on the line before it
+        let next_line_idx = self.lines.partition_point(|line| line.start <= pos);
+        Some(next_line_idx - 1)
+    }
+
+    pub(crate) fn line(&self, line_idx: usize) -> Option<Loc> {
+        self.lines.get(line_idx).copied()
+    }
+}
+
+/// Human-readable position in the source code
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LineCol {
+    /// 1-based line number
+    pub line: usize,
+
+    /// 0-based offset from the beginning of the line in bytes
+    pub column: usize,
+
+    /// 0-based offset from the beginning of the line in characters,
+    /// each byte of invalid UTF-8 counts as a separate character
+    pub char_column: usize,
+}
+
+impl Buffer {
+    pub(crate) fn line_index(&self) -> &LineIndex {
+        self.line_index.get_or_init(|| LineIndex::new(self.bytes()))
+    }
+
+    /// Returns line and column of the given byte offset,
+    /// `None` if it's outside of the buffer
+    pub fn line_col_for_pos(&self, pos: usize) -> Option<LineCol> {
+        let position = self.encode_pos(pos, PositionEncoding::Char)?;
+        let line = self.line_at(position.line)?;
+        Some(LineCol {
+            line: position.line,
+            column: pos - line.start,
+            char_column: position.column,
+        })
+    }
+
+    /// Returns location of the line with the given 1-based number,
+    /// it doesn't include the trailing newline
+    pub fn line_at(&self, line: usize) -> Option<Loc> {
+        self.line_index().line(line.checked_sub(1)?)
+    }
+
+    /// Returns total number of lines, an empty buffer has one empty line
+    pub fn lines_count(&self) -> usize {
+        self.line_index().lines.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LineCol;
+    use crate::{buffer::Buffer, loc::loc};
+
+    fn line_col(line: usize, column: usize, char_column: usize) -> Option<LineCol> {
+        Some(LineCol {
+            line,
+            column,
+            char_column,
+        })
+    }
+
+    #[test]
+    fn test_line_col_for_pos() {
+        let buffer = Buffer::new(b"foo\nbar\n\nbaz");
+        assert_eq!(buffer.line_col_for_pos(0), line_col(1, 0, 0));
+        assert_eq!(buffer.line_col_for_pos(3), line_col(1, 3, 3));
+        assert_eq!(buffer.line_col_for_pos(4), line_col(2, 0, 0));
+        assert_eq!(buffer.line_col_for_pos(8), line_col(3, 0, 0));
+        assert_eq!(buffer.line_col_for_pos(11), line_col(4, 2, 2));
+        assert_eq!(buffer.line_col_for_pos(12), line_col(4, 3, 3));
+        assert_eq!(buffer.line_col_for_pos(13), None);
+    }
+
+    #[test]
+    fn test_line_col_for_pos_multibyte() {
+        let buffer = Buffer::new("# 日本\nx = \"😀\" + y".as_bytes());
+        // `y` on the second line
+        let pos = buffer
+            .bytes()
+            .iter()
+            .position(|byte| *byte == b'y')
+            .unwrap();
+        assert_eq!(buffer.line_col_for_pos(pos), line_col(2, 13, 10));
+    }
+
+    #[test]
+    fn test_line_col_for_pos_invalid_utf8() {
+        let buffer = Buffer::new(b"\xFF\xFEa");
+        assert_eq!(buffer.line_col_for_pos(2), line_col(1, 2, 2));
+    }
+
+    #[test]
+    fn test_line_at() {
+        let buffer = Buffer::new(b"foo\r\nbar\n");
+        assert_eq!(buffer.lines_count(), 3);
+        assert_eq!(buffer.line_at(0), None);
+        assert_eq!(buffer.line_at(1), Some(loc!(0, 3)));
+        assert_eq!(buffer.line_at(2), Some(loc!(5, 8)));
+        assert_eq!(buffer.line_at(3), Some(loc!(9, 9)));
+        assert_eq!(buffer.line_at(4), None);
+    }
+
+    #[test]
+    fn test_empty_buffer() {
+        let buffer = Buffer::new(b"");
+        assert_eq!(buffer.lines_count(), 1);
+        assert_eq!(buffer.line_col_for_pos(0), line_col(1, 0, 0));
+    }
+
+    #[test]
+    fn test_buffer_is_shareable_between_threads() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<Buffer>();
+        assert_send_sync::<crate::ParserResult>();
+    }
+}
@@ -1,5 +1,12 @@
 mod buffer;
-pub(crate) use buffer::Buffer;
+pub use buffer::Buffer;
+
+mod line_index;
+pub use line_index::LineCol;
+
+mod position_encoding;
+pub(crate) use line_index::LineIndex;
+pub use position_encoding::{Position, PositionEncoding};
 
 mod buffer_with_cursor;
 pub(crate) use buffer_with_cursor::BufferWithCursor;
@@ -0,0 +1,183 @@
+use crate::buffer::{utf8::Utf8Char, Buffer};
+
+/// Units that are used to measure columns
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PositionEncoding {
+    /// Bytes, the same units as `Loc` uses
+    Utf8,
+
+    /// UTF-16 code units, the default encoding of LSP
+    Utf16,
+
+    /// Unicode scalar values
+    Char,
+}
+
+impl PositionEncoding {
+    // Returns the number of units taken by a char of `length` bytes,
+    // each byte of invalid UTF-8 is a separate unit
+    fn units(self, length: usize) -> usize {
+        match self {
+            Self::Utf8 => length,
+            Self::Utf16 if length == 4 => 2,
+            Self::Utf16 | Self::Char => 1,
+        }
+    }
+}
+
+/// Position as editors see it: a line and a column in some `PositionEncoding`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Position {
+    /// 1-based line number
+    pub line: usize,
+
+    /// 0-based offset from the beginning of the line
+    pub column: usize,
+}
+
+impl Buffer {
+    /// Converts a byte offset into a `Position` with columns measured in `encoding`,
+    /// `None` if the offset is outside of the buffer
+    pub fn encode_pos(&self, pos: usize, encoding: PositionEncoding) -> Option<Position> {
+        let line_idx = self.line_index().line_idx_for_pos(pos)?;
+        let line = self.line_index().line(line_idx)?;
+        let mut column = 0;
+        self.each_char(line.start, pos, |_, length| {
+            column += encoding.units(length);
+        });
+        Some(Position {
+            line: line_idx + 1,
+            column,
+        })
+    }
+
+    /// Converts a `Position` with columns measured in `encoding` back into a byte offset.
+    ///
+    /// Like in LSP a column that is past the end of the line points to the end of the line,
+    /// a column in the middle of a character points to the start of the character.
+    pub fn decode_pos(&self, position: Position, encoding: PositionEncoding) -> Option<usize> {
+        let line = self.line_at(position.line)?;
+        let mut column = 0;
+        let mut result = line.end;
+        self.each_char(line.start, line.end, |pos, length| {
+            if result == line.end && column + encoding.units(length) > position.column {
+                result = pos;
+            }
+            column += encoding.units(length);
+        });
+        Some(result)
+    }
+
+    // Calls `f` with an offset and a length of every char that starts in `start..end`,
+    // each byte of invalid UTF-8 is a separate char of length 1
+    fn each_char(&self, start: usize, end: usize, mut f: impl FnMut(usize, usize)) {
+        let mut pos = start;
+        while pos < end {
+            let length = match self.utf8_char_at(pos) {
+                Utf8Char::Valid { length } => length,
+                Utf8Char::Invalid | Utf8Char::EOF => 1,
+            };
+            f(pos, length);
+            pos += length;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Position, PositionEncoding};
+    use crate::buffer::Buffer;
+
+    fn pos(line: usize, column: usize) -> Position {
+        Position { line, column }
+    }
+
+    #[test]
+    fn test_ascii() {
+        let buffer = Buffer::new(b"foo\nbar");
+        for encoding in [
+            PositionEncoding::Utf8,
+            PositionEncoding::Utf16,
+            PositionEncoding::Char,
+        ] {
+            assert_eq!(buffer.encode_pos(5, encoding), Some(pos(2, 1)));
+            assert_eq!(buffer.decode_pos(pos(2, 1), encoding), Some(5));
+        }
+    }
+
+    #[test]
+    fn test_multibyte() {
+        // "日" is 3 bytes and 1 UTF-16 unit, "😀" is 4 bytes and 2 UTF-16 units
+        let buffer = Buffer::new("# 日😀\nx = 1 # 😀 y".as_bytes());
+        let y = buffer.bytes().len() - 1;
+
+        assert_eq!(
+            buffer.encode_pos(y, PositionEncoding::Utf8),
+            Some(pos(2, 13))
+        );
+        assert_eq!(
+            buffer.encode_pos(y, PositionEncoding::Utf16),
+            Some(pos(2, 11))
+        );
+        assert_eq!(
+            buffer.encode_pos(y, PositionEncoding::Char),
+            Some(pos(2, 10))
+        );
+
+        assert_eq!(
+            buffer.decode_pos(pos(2, 11), PositionEncoding::Utf16),
+            Some(y)
+        );
+        assert_eq!(
+            buffer.decode_pos(pos(2, 10), PositionEncoding::Char),
+            Some(y)
+        );
+
+        // end of the first line
+        assert_eq!(
+            buffer.encode_pos(9, PositionEncoding::Utf16),
+            Some(pos(1, 5))
+        );
+        assert_eq!(
+            buffer.decode_pos(pos(1, 5), PositionEncoding::Utf16),
+            Some(9)
+        );
+    }
+
+    #[test]
+    fn test_decode_inside_of_char() {
+        let buffer = Buffer::new("😀x".as_bytes());
+        // between surrogates of the emoji
+        assert_eq!(
+            buffer.decode_pos(pos(1, 1), PositionEncoding::Utf16),
+            Some(0)
+        );
+        assert_eq!(
+            buffer.decode_pos(pos(1, 2), PositionEncoding::Utf16),
+            Some(4)
+        );
+    }
+
+    #[test]
+    fn test_decode_past_the_end_of_line() {
+        let buffer = Buffer::new(b"foo\nbar");
+        assert_eq!(
+            buffer.decode_pos(pos(1, 10), PositionEncoding::Utf16),
+            Some(3)
+        );
+        assert_eq!(buffer.decode_pos(pos(3, 0), PositionEncoding::Utf16), None);
+    }
+
+    #[test]
+    fn test_invalid_utf8() {
+        let buffer = Buffer::new(b"\xFF\xE6\x97a");
+        assert_eq!(
+            buffer.encode_pos(3, PositionEncoding::Utf16),
+            Some(pos(1, 3))
+        );
+        assert_eq!(
+            buffer.decode_pos(pos(1, 3), PositionEncoding::Char),
+            Some(3)
+        );
+    }
+}
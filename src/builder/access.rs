@@ -1,10 +1,14 @@
 use crate::{
     buffer::Buffer,
     builder::{helpers::string_value, Builder},
-    nodes::{BackRef, Cbase, Const, Cvar, Gvar, Ivar, Lvar, NthRef, Self_},
+    current_arg_stack::CurrentArgStack,
+    diagnostic::{DiagnosticMessage, Diagnostics},
+    max_numparam_stack::MaxNumparamStack,
+    nodes::{BackRef, Cbase, Const, Cvar, Gvar, Ivar, Lvar, NthRef, Self_, Send},
+    static_environment::StaticEnvironment,
     string_content::StringContent,
     token::Token,
-    Node,
+    Loc, Node,
 };
 
 impl Builder {
@@ -47,7 +51,11 @@ impl Builder {
             expression_l: loc,
         }))
     }
-    pub(crate) fn nth_ref(nth_ref_t: Token, buffer: &Buffer) -> Box<Node> {
+    pub(crate) fn nth_ref(
+        nth_ref_t: Token,
+        buffer: &Buffer,
+        diagnostics: &Diagnostics,
+    ) -> Box<Node> {
         let expression_l = nth_ref_t.loc;
         let name = string_value(expression_l, buffer).to_string_lossy();
         let name = &name[1..];
@@ -59,18 +67,24 @@ impl Builder {
             Ok(n) if n <= MAX_NTH_REF => {
                 // ok
             }
-            _ => {
-                // TODO: warn
-                // DiagnosticMessage::NthRefIsTooBig {
-                //      nth_ref: name.clone(),
-                // },
-            }
+            _ => diagnostics.warn(
+                DiagnosticMessage::NthRefIsTooBig {
+                    nth_ref: name.to_string_lossy(),
+                },
+                expression_l,
+            ),
         }
 
         Box::new(Node::NthRef(NthRef { name, expression_l }))
     }
 
-    pub(crate) fn accessible(node: Box<Node>) -> Box<Node> {
+    pub(crate) fn accessible(
+        node: Box<Node>,
+        static_env: &StaticEnvironment,
+        current_arg_stack: &CurrentArgStack,
+        max_numparam_stack: &MaxNumparamStack,
+        diagnostics: &Diagnostics,
+    ) -> Box<Node> {
         if matches!(&*node, Node::Lvar(_)) {
             match *node {
                 Node::Lvar(Lvar { name, expression_l }) => {
@@ -82,35 +96,44 @@ impl Builder {
 
                     // Numbered parameters are not declared anywhere,
                     // so they take precedence over method calls in numblock contexts
-                    // TODO: code below must be uncommented and adapted
-                    // if try_declare_numparam(name_s, &expression_l) {
-                    //     return Box::new(Node::Lvar(Lvar { name, expression_l }));
-                    // }
-
-                    // if !self.static_env.is_declared(name_s) {
-                    //     return Box::new(Node::Send(Send {
-                    //         recv: None,
-                    //         method_name: name,
-                    //         args: vec![],
-                    //         dot_l: None,
-                    //         selector_l: Some(expression_l),
-                    //         begin_l: None,
-                    //         end_l: None,
-                    //         operator_l: None,
-                    //         expression_l,
-                    //     }));
-                    // }
-
-                    // if let Some(current_arg) = self.current_arg_stack.top() {
-                    //     if current_arg == name_s {
-                    //         self.error(
-                    //             DiagnosticMessage::CircularArgumentReference {
-                    //                 arg_name: name.clone(),
-                    //             },
-                    //             &expression_l,
-                    //         );
-                    //     }
-                    // }
+                    if Self::try_declare_numparam(
+                        name_s,
+                        expression_l,
+                        static_env,
+                        max_numparam_stack,
+                        diagnostics,
+                    ) || Self::try_declare_it(
+                        name_s,
+                        expression_l,
+                        static_env,
+                        max_numparam_stack,
+                        diagnostics,
+                    ) {
+                        return Box::new(Node::Lvar(Lvar { name, expression_l }));
+                    }
+
+                    if !static_env.is_declared(name.as_bytes()) {
+                        return Box::new(Node::Send(Send {
+                            recv: None,
+                            method_name: name,
+                            args: vec![],
+                            dot_l: None,
+                            selector_l: Some(expression_l),
+                            begin_l: None,
+                            end_l: None,
+                            operator_l: None,
+                            expression_l,
+                        }));
+                    }
+
+                    if current_arg_stack.is_current(name.as_bytes()) {
+                        diagnostics.error(
+                            DiagnosticMessage::CircularArgumentReference {
+                                arg_name: name.to_string_lossy(),
+                            },
+                            expression_l,
+                        );
+                    }
 
                     Box::new(Node::Lvar(Lvar { name, expression_l }))
                 }
@@ -121,6 +144,63 @@ impl Builder {
         }
     }
 
+    // `_1`..`_9` in a block without ordinary parameters
+    fn try_declare_numparam(
+        name: &str,
+        loc: Loc,
+        static_env: &StaticEnvironment,
+        max_numparam_stack: &MaxNumparamStack,
+        diagnostics: &Diagnostics,
+    ) -> bool {
+        let numparam = match name.as_bytes() {
+            [b'_', n @ b'1'..=b'9'] => n - b'0',
+            _ => return false,
+        };
+        if static_env.is_declared(name.as_bytes()) || !max_numparam_stack.in_dynamic_block() {
+            return false;
+        }
+
+        if max_numparam_stack.has_ordinary_params() {
+            diagnostics.error(DiagnosticMessage::OrdinaryParamDefined, loc);
+        }
+        if max_numparam_stack.outer_block_has_numparams() {
+            diagnostics.error(DiagnosticMessage::NumparamUsedInOuterScope, loc);
+        }
+        if max_numparam_stack.uses_it() {
+            diagnostics.error(DiagnosticMessage::NumparamIsNotAllowedWithIt, loc);
+        }
+
+        // not declared in the static environment,
+        // so `_1` of a nested block is checked against the outer one again
+        max_numparam_stack.register_numparam(numparam);
+        true
+    }
+
+    // `it` in a block without ordinary parameters (since Ruby 3.4),
+    // unlike numbered parameters it's not declared, so nested blocks get their own `it`
+    fn try_declare_it(
+        name: &str,
+        loc: Loc,
+        static_env: &StaticEnvironment,
+        max_numparam_stack: &MaxNumparamStack,
+        diagnostics: &Diagnostics,
+    ) -> bool {
+        if name != "it"
+            || static_env.is_declared(b"it")
+            || !max_numparam_stack.in_dynamic_block()
+            || max_numparam_stack.has_ordinary_params()
+        {
+            return false;
+        }
+
+        if max_numparam_stack.has_numparams() {
+            diagnostics.error(DiagnosticMessage::ItIsNotAllowedWithNumparam, loc);
+        }
+
+        max_numparam_stack.register_it();
+        true
+    }
+
     pub(crate) fn const_(const_t: Token, buffer: &Buffer) -> Box<Node> {
         let name_l = const_t.loc;
         let expression_l = name_l;
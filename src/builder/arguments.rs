@@ -5,8 +5,8 @@ use crate::{
         Builder,
     },
     nodes::{
-        Arg, Args, Blockarg, ForwardArg, ForwardedArgs, Kwarg, Kwnilarg, Kwoptarg, Kwrestarg, Mlhs,
-        Optarg, Procarg0, Restarg, Shadowarg,
+        Arg, Args, Blockarg, ForwardArg, ForwardedArgs, ForwardedKwrestarg, Kwarg, Kwnilarg,
+        Kwoptarg, Kwrestarg, Mlhs, Optarg, Procarg0, Restarg, Shadowarg,
     },
     Node, Token,
 };
@@ -40,6 +40,12 @@ impl Builder {
         }))
     }
 
+    pub(crate) fn forwarded_kwrestarg(dstar_t: Token) -> Box<Node> {
+        Box::new(Node::ForwardedKwrestarg(ForwardedKwrestarg {
+            expression_l: dstar_t.loc,
+        }))
+    }
+
     pub(crate) fn forward_arg(dots_t: Token) -> Box<Node> {
         Box::new(Node::ForwardArg(ForwardArg {
             expression_l: dots_t.loc,
@@ -1,18 +1,28 @@
 use crate::{
     buffer::Buffer,
-    builder::{helpers::string_value, Builder},
+    builder::{
+        helpers::{collection_map, string_value},
+        Builder,
+    },
+    diagnostic::{DiagnosticMessage, Diagnostics},
+    error::{ParseError, ParseErrorKind, ParseResult},
     nodes::{
         AndAsgn, BackRef, CSend, Casgn, Const, Cvar, Cvasgn, Encoding, False, File, Gvar, Gvasgn,
-        Index, IndexAsgn, Ivar, Ivasgn, Line, Lvar, Lvasgn, MatchVar, Nil, NthRef, OpAsgn, OrAsgn,
-        Self_, Send, True,
+        Index, IndexAsgn, Ivar, Ivasgn, Line, Lvar, Lvasgn, Masgn, MatchVar, Mlhs, Nil, NthRef,
+        OpAsgn, OrAsgn, Self_, Send, True,
     },
+    static_environment::StaticEnvironment,
     token::Token,
     Node,
 };
 
 impl Builder {
-    pub(crate) fn assignable(node: Box<Node>) -> Box<Node> {
-        let node = match *node {
+    pub(crate) fn assignable(
+        node: Node,
+        static_env: &StaticEnvironment,
+        diagnostics: &Diagnostics,
+    ) -> ParseResult<Box<Node>> {
+        let node = match node {
             Node::Cvar(Cvar { name, expression_l }) => Node::Cvasgn(Cvasgn {
                 name,
                 value: None,
@@ -56,7 +66,7 @@ impl Builder {
                 // TODO: check assignment to numparam
                 // TODO: check if name is reserved for numparam
 
-                // TODO: save `name` as local variable
+                static_env.declare(name.as_bytes());
 
                 Node::Lvasgn(Lvasgn {
                     name,
@@ -74,52 +84,50 @@ impl Builder {
                 // TODO: check assignment to numparam
                 // TODO: check if name is reserved for numparam
 
+                static_env.declare(name.as_bytes());
+
                 Node::MatchVar(MatchVar {
                     name,
                     name_l,
                     expression_l,
                 })
             }
-            node @ Node::Self_(Self_ { .. }) => {
-                // TODO: report assignment to `self`
-                node
-            }
-            node @ Node::Nil(Nil { .. }) => {
-                // TODO: report assignment to `nil`
+            node @ Node::Self_(Self_ { expression_l }) => {
+                diagnostics.error(DiagnosticMessage::CantAssignToSelf, expression_l);
                 node
             }
-            node @ Node::True(True { .. }) => {
-                // TODO: report assignment to `true`
+            node @ Node::Nil(Nil { expression_l }) => {
+                diagnostics.error(DiagnosticMessage::CantAssignToNil, expression_l);
                 node
             }
-            node @ Node::False(False { .. }) => {
-                // TODO: report assignment to `false`
+            node @ Node::True(True { expression_l }) => {
+                diagnostics.error(DiagnosticMessage::CantAssignToTrue, expression_l);
                 node
             }
-            node @ Node::File(File { .. }) => {
-                // TODO: report assignment to `__FILE__`
+            node @ Node::False(False { expression_l }) => {
+                diagnostics.error(DiagnosticMessage::CantAssignToFalse, expression_l);
                 node
             }
-            node @ Node::Line(Line { .. }) => {
-                // TODO: report assignment to `__LINE__`
+            node @ Node::File(File { expression_l }) => {
+                diagnostics.error(DiagnosticMessage::CantAssignToFile, expression_l);
                 node
             }
-            node @ Node::Encoding(Encoding { .. }) => {
-                // TODO: report assignment to `__ENCODING__`
+            node @ Node::Line(Line { expression_l }) => {
+                diagnostics.error(DiagnosticMessage::CantAssignToLine, expression_l);
                 node
             }
-            node @ Node::BackRef(BackRef { .. }) => {
-                // TODO: report assignment to back ref
+            node @ Node::Encoding(Encoding { expression_l }) => {
+                diagnostics.error(DiagnosticMessage::CantAssignToEncoding, expression_l);
                 node
             }
-            node @ Node::NthRef(NthRef { .. }) => {
-                // TODO: report assignment to nth ref
+            node @ Node::BackRef(BackRef { .. }) | node @ Node::NthRef(NthRef { .. }) => {
+                Self::cant_set_variable(&node, diagnostics);
                 node
             }
-            other => unreachable!("{:?} can't be used in assignment", other),
+            other => return Err(Self::invalid_assignment_target(&other)),
         };
 
-        Box::new(node)
+        Ok(Box::new(node))
     }
 
     pub(crate) fn const_op_assignable(node: Box<Node>) -> Box<Node> {
@@ -145,7 +153,11 @@ impl Builder {
         }
     }
 
-    pub(crate) fn assign(mut lhs: Box<Node>, eql_t: Token, rhs: Box<Node>) -> Box<Node> {
+    pub(crate) fn assign(
+        mut lhs: Box<Node>,
+        eql_t: Token,
+        rhs: Box<Node>,
+    ) -> ParseResult<Box<Node>> {
         let op_l = Some(eql_t.loc);
         let expr_l = lhs.expression().join(rhs.expression());
 
@@ -202,18 +214,93 @@ impl Builder {
                 args,
                 ..
             }) => {
+                if !args.is_empty() {
+                    // `foo.bar(1) = 2`
+                    return Err(ParseError::new(
+                        ParseErrorKind::InvalidAssignmentTarget,
+                        *expression_l,
+                    ));
+                }
                 *expression_l = expr_l;
                 *operator_l = op_l;
-                if args.is_empty() {
-                    *args = vec![*rhs];
-                } else {
-                    unreachable!("can't assign to method call with args")
-                }
+                *args = vec![*rhs];
+            }
+            Node::Self_(_)
+            | Node::Nil(_)
+            | Node::True(_)
+            | Node::False(_)
+            | Node::File(_)
+            | Node::Line(_)
+            | Node::Encoding(_)
+            | Node::BackRef(_)
+            | Node::NthRef(_) => {
+                // error has been reported by `assignable`, nothing to assign
+            }
+            other => return Err(Self::invalid_assignment_target(other)),
+        }
+
+        Ok(lhs)
+    }
+
+    // `a = 1, 2`, the value of `asgn` becomes the first element of the array
+    pub(crate) fn assign_mrhs(mut asgn: Box<Node>, rest: Vec<Node>) -> Box<Node> {
+        match &mut *asgn {
+            Node::Cvasgn(Cvasgn {
+                value,
+                expression_l,
+                ..
+            })
+            | Node::Ivasgn(Ivasgn {
+                value,
+                expression_l,
+                ..
+            })
+            | Node::Gvasgn(Gvasgn {
+                value,
+                expression_l,
+                ..
+            })
+            | Node::Lvasgn(Lvasgn {
+                value,
+                expression_l,
+                ..
+            })
+            | Node::Casgn(Casgn {
+                value,
+                expression_l,
+                ..
+            })
+            | Node::IndexAsgn(IndexAsgn {
+                value,
+                expression_l,
+                ..
+            }) => {
+                let first = value.take().expect("bug: assignment has a value");
+                let array = Self::mrhs_array(*first, rest);
+                *expression_l = expression_l.join(array.expression());
+                *value = Some(array);
             }
-            other => unreachable!("{:?} can't be used in assignment", other),
+            // a.b = 1, 2
+            Node::Send(Send {
+                args, expression_l, ..
+            })
+            | Node::CSend(CSend {
+                args, expression_l, ..
+            }) => {
+                let first = args.pop().expect("bug: attribute assignment has a value");
+                let array = Self::mrhs_array(first, rest);
+                *expression_l = expression_l.join(array.expression());
+                args.push(*array);
+            }
+            other => unreachable!("{:?} is not an assignment with a value", other),
         }
+        asgn
+    }
 
-        lhs
+    fn mrhs_array(first: Node, rest: Vec<Node>) -> Box<Node> {
+        let mut elements = vec![first];
+        elements.extend(rest);
+        Builder::array(None, elements, None)
     }
 
     pub(crate) fn op_assign(
@@ -221,13 +308,20 @@ impl Builder {
         op_t: Token,
         rhs: Box<Node>,
         buffer: &Buffer,
-    ) -> Box<Node> {
+        diagnostics: &Diagnostics,
+    ) -> ParseResult<Box<Node>> {
         let operator_l = op_t.loc;
         let mut operator = string_value(operator_l, buffer);
         operator.pop();
         let expression_l = lhs.expression().join(rhs.expression());
 
-        match &*lhs {
+        match *lhs {
+            // `foo.bar(1) += 2`
+            Node::Send(Send { ref args, .. }) | Node::CSend(CSend { ref args, .. })
+                if !args.is_empty() =>
+            {
+                return Err(Self::invalid_assignment_target(&lhs));
+            }
             Node::Gvasgn(_)
             | Node::Ivasgn(_)
             | Node::Lvasgn(_)
@@ -237,55 +331,37 @@ impl Builder {
             | Node::CSend(_) => {
                 // ignore
             }
-            Node::Index(_) => match *lhs {
-                Node::Index(Index {
+            Node::Index(Index {
+                recv,
+                indexes,
+                begin_l,
+                end_l,
+                expression_l,
+            }) => {
+                lhs = Box::new(Node::IndexAsgn(IndexAsgn {
                     recv,
                     indexes,
+                    value: None,
                     begin_l,
                     end_l,
+                    operator_l: None,
                     expression_l,
-                }) => {
-                    lhs = Box::new(Node::IndexAsgn(IndexAsgn {
-                        recv,
-                        indexes,
-                        value: None,
-                        begin_l,
-                        end_l,
-                        operator_l: None,
-                        expression_l,
-                    }));
-                }
-                _ => unreachable!(),
-            },
-            Node::BackRef(BackRef {
-                name: _name,
-                expression_l: _expression_l,
-            }) => {
-                // TODO: report CantSetVariable
-                // self.error(
-                //     DiagnosticMessage::CantSetVariable {
-                //         var_name: name.clone(),
-                //     },
-                //     expression_l,
-                // );
-
-                // and ignore
+                }));
             }
-            Node::NthRef(NthRef {
-                name: _name,
-                expression_l: _expression_l,
-            }) => {
-                // TODO: report CantSetVariable
-                // self.error(
-                //     DiagnosticMessage::CantSetVariable {
-                //         var_name: format!("${}", name),
-                //     },
-                //     expression_l,
-                // );
-
+            Node::BackRef(_) | Node::NthRef(_) => {
+                Self::cant_set_variable(&lhs, diagnostics);
                 // and ignore
             }
-            _ => unreachable!("unsupported op_assign lhs {:?}", lhs),
+            Node::Self_(_)
+            | Node::Nil(_)
+            | Node::True(_)
+            | Node::False(_)
+            | Node::File(_)
+            | Node::Line(_)
+            | Node::Encoding(_) => {
+                // error has been reported by `assignable`, ignore
+            }
+            ref other => return Err(Self::invalid_assignment_target(other)),
         }
 
         let recv: Box<Node> = lhs;
@@ -313,6 +389,121 @@ impl Builder {
             }),
         };
 
-        Box::new(result)
+        Ok(Box::new(result))
+    }
+
+    // `foo.bar` and `foo[1]` that have been parsed as values become targets of the assignment
+    pub(crate) fn call_assignable(node: Node) -> ParseResult<Box<Node>> {
+        let node = match node {
+            Node::Send(Send {
+                recv: Some(recv),
+                mut method_name,
+                args,
+                dot_l: Some(dot_l),
+                selector_l,
+                expression_l,
+                ..
+            }) if args.is_empty() => {
+                method_name.push(b'=');
+                Node::Send(Send {
+                    recv: Some(recv),
+                    method_name,
+                    args,
+                    dot_l: Some(dot_l),
+                    selector_l,
+                    begin_l: None,
+                    end_l: None,
+                    operator_l: None,
+                    expression_l,
+                })
+            }
+            Node::CSend(CSend {
+                recv,
+                mut method_name,
+                args,
+                dot_l,
+                selector_l,
+                expression_l,
+                ..
+            }) if args.is_empty() => {
+                method_name.push(b'=');
+                Node::CSend(CSend {
+                    recv,
+                    method_name,
+                    args,
+                    dot_l,
+                    selector_l,
+                    begin_l: None,
+                    end_l: None,
+                    operator_l: None,
+                    expression_l,
+                })
+            }
+            Node::Index(Index {
+                recv,
+                indexes,
+                begin_l,
+                end_l,
+                expression_l,
+            }) => Node::IndexAsgn(IndexAsgn {
+                recv,
+                indexes,
+                value: None,
+                begin_l,
+                end_l,
+                operator_l: None,
+                expression_l,
+            }),
+            other => return Err(Self::invalid_assignment_target(&other)),
+        };
+
+        Ok(Box::new(node))
+    }
+
+    pub(crate) fn multi_lhs(
+        begin_t: Option<Token>,
+        items: Vec<Node>,
+        end_t: Option<Token>,
+    ) -> Box<Node> {
+        let (begin_l, end_l, expression_l) = collection_map(&begin_t, &items, &end_t);
+
+        Box::new(Node::Mlhs(Mlhs {
+            items,
+            begin_l,
+            end_l,
+            expression_l,
+        }))
+    }
+
+    pub(crate) fn multi_assign(lhs: Box<Node>, eql_t: Token, rhs: Box<Node>) -> Box<Node> {
+        let operator_l = eql_t.loc;
+        let expression_l = lhs.expression().join(rhs.expression());
+
+        Box::new(Node::Masgn(Masgn {
+            lhs,
+            rhs,
+            operator_l,
+            expression_l,
+        }))
+    }
+
+    fn invalid_assignment_target(node: &Node) -> ParseError {
+        ParseError::new(ParseErrorKind::InvalidAssignmentTarget, *node.expression())
+    }
+
+    fn cant_set_variable(node: &Node, diagnostics: &Diagnostics) {
+        let (var_name, expression_l) = match node {
+            Node::BackRef(BackRef { name, expression_l }) => {
+                (name.to_string_lossy(), *expression_l)
+            }
+            Node::NthRef(NthRef { name, expression_l }) => {
+                (format!("${}", name.to_string_lossy()), *expression_l)
+            }
+            _ => unreachable!(),
+        };
+        diagnostics.error(
+            DiagnosticMessage::CantSetVariable { var_name },
+            expression_l,
+        );
     }
 }
@@ -4,10 +4,14 @@ use crate::{
         helpers::{collection_map, string_value},
         Builder,
     },
+    current_arg_stack::CurrentArgStack,
+    diagnostic::{DiagnosticMessage, Diagnostics},
+    max_numparam_stack::MaxNumparamStack,
     nodes::{
         Complex, Const, Dstr, Dsym, Float, Hash, Int, Kwsplat, Lvar, Pair, Rational, Regexp, Str,
         Sym,
     },
+    static_environment::StaticEnvironment,
     Node, Token,
 };
 
@@ -88,7 +92,14 @@ impl Builder {
         }))
     }
 
-    pub(crate) fn pair_label(key_t: Token, buffer: &Buffer) -> Box<Node> {
+    pub(crate) fn pair_label(
+        key_t: Token,
+        buffer: &Buffer,
+        static_env: &StaticEnvironment,
+        current_arg_stack: &CurrentArgStack,
+        max_numparam_stack: &MaxNumparamStack,
+        diagnostics: &Diagnostics,
+    ) -> Box<Node> {
         let key_l = key_t.loc;
         let value_l = key_l.adjust_end(-1);
 
@@ -114,7 +125,14 @@ impl Builder {
             }))
         };
 
-        Self::pair_keyword(key_t, Self::accessible(value), buffer)
+        let value = Self::accessible(
+            value,
+            static_env,
+            current_arg_stack,
+            max_numparam_stack,
+            diagnostics,
+        );
+        Self::pair_keyword(key_t, value, buffer)
     }
 
     pub(crate) fn kwsplat(dstar_t: Token, value: Box<Node>) -> Box<Node> {
@@ -132,6 +150,7 @@ impl Builder {
         begin_t: Option<Token>,
         pairs: Vec<Node>,
         end_t: Option<Token>,
+        diagnostics: &Diagnostics,
     ) -> Box<Node> {
         for i in 0..pairs.len() {
             for j in i + 1..pairs.len() {
@@ -213,7 +232,7 @@ impl Builder {
                 let do_warn = keys_are_equal(key1, key2);
 
                 if do_warn {
-                    // self.warn(DiagnosticMessage::DuplicateHashKey {}, key2.expression());
+                    diagnostics.warn(DiagnosticMessage::DuplicateHashKey, *key2.expression());
                 }
             }
         }
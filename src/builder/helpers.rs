@@ -1,6 +1,7 @@
 use crate::{
     buffer::Buffer,
     loc::{loc, Loc},
+    nodes::{Hash, Kwargs, Regexp, Str},
     string_content::StringContent,
     token::{Token, TokenKind},
     Node,
@@ -18,14 +19,15 @@ pub(crate) fn maybe_loc(token: &Option<Token>) -> Option<Loc> {
 }
 
 pub(crate) fn nodes_locs(nodes: &[Node]) -> (Loc, Loc, Loc) {
-    debug_assert!(nodes.len() > 0);
+    debug_assert!(!nodes.is_empty());
 
     let begin = nodes.first().unwrap().expression().start;
     let end = nodes.last().unwrap().expression().end;
 
+    // first and last bytes of the list
     let begin_l = loc!(begin, begin + 1);
-    let end_l = loc!(end, end + 1);
-    let expression_l = begin_l.join(&end_l);
+    let end_l = loc!(end.saturating_sub(1), end);
+    let expression_l = loc!(begin, end);
 
     (begin_l, end_l, expression_l)
 }
@@ -93,7 +95,217 @@ pub(crate) fn heredoc_map(
     (heredoc_body_l, heredoc_end_l, expression_l)
 }
 
+// Heredoc helpers
+const TAB_WIDTH: usize = 8;
+
+// Tracks whether each part of the heredoc starts a new line,
+// only such parts can be dedented
+fn heredoc_line_starts(parts: &[Node]) -> Vec<bool> {
+    let mut at_line_begin = true;
+    parts
+        .iter()
+        .map(|part| {
+            let is_line_start = at_line_begin;
+            at_line_begin = match part {
+                Node::Str(Str { value, .. }) => value.as_bytes().ends_with(b"\n"),
+                _ => false,
+            };
+            is_line_start
+        })
+        .collect()
+}
+
+// Computes indentation of the `<<~` heredoc the same way MRI does:
+// the smallest indentation of all lines, lines consisting only of whitespaces are ignored
+pub(crate) fn heredoc_indent(parts: &[Node]) -> usize {
+    let mut indent = None;
+
+    for (part, is_line_start) in parts.iter().zip(heredoc_line_starts(parts)) {
+        if !is_line_start {
+            continue;
+        }
+
+        let line_indent = match part {
+            Node::Str(Str { value, .. }) => {
+                let bytes = value.as_bytes();
+                let mut col = 0;
+                let mut idx = 0;
+                while let Some(byte) = bytes.get(idx) {
+                    match byte {
+                        b' ' => col += 1,
+                        b'\t' => col = TAB_WIDTH * (col / TAB_WIDTH + 1),
+                        _ => break,
+                    }
+                    idx += 1;
+                }
+                match &bytes[idx..] {
+                    b"\n" | b"\r\n" => continue,
+                    _ => col,
+                }
+            }
+            // interpolation at the beginning of the line
+            _ => 0,
+        };
+
+        indent = Some(indent.map_or(line_indent, |indent: usize| indent.min(line_indent)));
+    }
+
+    indent.unwrap_or(0)
+}
+
+// Removes up to `width` columns of leading whitespaces
+pub(crate) fn dedent_string(bytes: &[u8], width: usize) -> &[u8] {
+    let mut col = 0;
+    let mut idx = 0;
+    while col < width {
+        match bytes.get(idx) {
+            Some(b' ') => col += 1,
+            Some(b'\t') => {
+                let next_col = TAB_WIDTH * (col / TAB_WIDTH + 1);
+                if next_col > width {
+                    break;
+                }
+                col = next_col;
+            }
+            _ => break,
+        }
+        idx += 1;
+    }
+    &bytes[idx..]
+}
+
+pub(crate) fn dedent_heredoc_parts(parts: Vec<Node>) -> Vec<Node> {
+    let indent = heredoc_indent(&parts);
+    let line_starts = heredoc_line_starts(&parts);
+
+    parts
+        .into_iter()
+        .zip(line_starts)
+        .filter_map(|(mut part, is_line_start)| {
+            if let (Node::Str(Str { value, .. }), true) = (&mut part, is_line_start) {
+                *value = StringContent::from(dedent_string(value.as_bytes(), indent).to_vec());
+                if value.as_bytes().is_empty() {
+                    return None;
+                }
+            }
+            Some(part)
+        })
+        .collect()
+}
+
 // Regexp heleprs
-pub(crate) fn static_regexp_captures(_node: &Node) -> Option<Vec<String>> {
-    None
+
+// Returns names of named captures of the regexp literal without interpolation,
+// `/(?<foo>\d+)/ =~ bar` declares local variable `foo`
+pub(crate) fn static_regexp_captures(node: &Node) -> Option<Vec<String>> {
+    let parts = match node {
+        Node::Regexp(Regexp { parts, .. }) => parts,
+        _ => return None,
+    };
+
+    let mut source = vec![];
+    for part in parts {
+        match part {
+            Node::Str(Str { value, .. }) => source.extend_from_slice(value.as_bytes()),
+            _ => return None,
+        }
+    }
+
+    Some(regexp_named_captures(&source))
+}
+
+fn regexp_named_captures(source: &[u8]) -> Vec<String> {
+    let mut captures = vec![];
+    let mut in_char_class = false;
+    let mut idx = 0;
+
+    while idx < source.len() {
+        match (source[idx], in_char_class) {
+            (b'\\', _) => idx += 1,
+            (b'[', false) => in_char_class = true,
+            (b']', true) => in_char_class = false,
+            (b'(', false) => {
+                // `(?<name>...)` or `(?'name'...)`, but not `(?<=...)`/`(?<!...)`
+                let terminator = match (source.get(idx + 1), source.get(idx + 2)) {
+                    (Some(b'?'), Some(b'<')) => b'>',
+                    (Some(b'?'), Some(b'\'')) => b'\'',
+                    _ => {
+                        idx += 1;
+                        continue;
+                    }
+                };
+                let name_start = idx + 3;
+                if let Some(len) = source[name_start..]
+                    .iter()
+                    .position(|byte| *byte == terminator)
+                {
+                    let name = &source[name_start..name_start + len];
+                    if is_local_variable_name(name) {
+                        captures.push(String::from_utf8_lossy(name).into_owned());
+                    }
+                }
+            }
+            _ => {}
+        }
+        idx += 1;
+    }
+
+    captures
+}
+
+// Trailing braceless hash in method call args is passed as keyword arguments:
+// `foo(a, k: 1)` and `foo(k: 1, &blk)`, but not `foo({ k: 1 })`
+pub(crate) fn rewrite_hash_args_to_kwargs(args: &mut Vec<Node>) {
+    let len = args.len();
+    let kwargs_idx = if len > 0 && is_kwargs(&args[len - 1]) {
+        len - 1
+    } else if len > 1 && matches!(args[len - 1], Node::BlockPass(_)) && is_kwargs(&args[len - 2]) {
+        len - 2
+    } else {
+        return;
+    };
+
+    if let Node::Hash(Hash {
+        pairs,
+        expression_l,
+        ..
+    }) = args.remove(kwargs_idx)
+    {
+        args.insert(
+            kwargs_idx,
+            Node::Kwargs(Kwargs {
+                pairs,
+                expression_l,
+            }),
+        );
+    }
+}
+
+fn is_kwargs(node: &Node) -> bool {
+    matches!(
+        node,
+        Node::Hash(Hash {
+            begin_l: None,
+            end_l: None,
+            ..
+        })
+    )
+}
+
+// Captures like `(?<Foo>...)` are valid, but they don't declare local variables
+fn is_local_variable_name(name: &[u8]) -> bool {
+    match name.first() {
+        Some(first) if first.is_ascii_lowercase() || *first == b'_' || !first.is_ascii() => name
+            .iter()
+            .all(|byte| byte.is_ascii_alphanumeric() || *byte == b'_' || !byte.is_ascii()),
+        _ => false,
+    }
+}
+
+#[test]
+fn test_regexp_named_captures() {
+    assert_eq!(
+        regexp_named_captures(br"(?<a>\d)(?'b'x)(?<=c)(?<!d)\(?<e>)[(?<f>)](?<G>)(?<_h>)"),
+        vec!["a", "b", "_h"]
+    );
 }
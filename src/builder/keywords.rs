@@ -1,8 +1,9 @@
 use crate::{
     builder::{
-        helpers::{maybe_loc, maybe_node_expr},
+        helpers::{maybe_loc, maybe_node_expr, rewrite_hash_args_to_kwargs},
         Builder,
     },
+    diagnostic::{DiagnosticMessage, Diagnostics},
     nodes::{Break, Defined, Next, Redo, Retry, Return, Super, Yield, ZSuper},
     Node, Token,
 };
@@ -70,9 +71,11 @@ impl Builder {
     pub(crate) fn yield_(
         keyword_t: Token,
         lparen_t: Option<Token>,
-        args: Vec<Node>,
+        mut args: Vec<Node>,
         rparen_t: Option<Token>,
     ) -> Box<Node> {
+        rewrite_hash_args_to_kwargs(&mut args);
+
         let keyword_l = keyword_t.loc;
         let begin_l = maybe_loc(&lparen_t);
         let end_l = maybe_loc(&rparen_t);
@@ -89,6 +92,35 @@ impl Builder {
         }))
     }
 
+    pub(crate) fn super_(
+        keyword_t: Token,
+        lparen_t: Option<Token>,
+        mut args: Vec<Node>,
+        rparen_t: Option<Token>,
+    ) -> Box<Node> {
+        rewrite_hash_args_to_kwargs(&mut args);
+
+        let keyword_l = keyword_t.loc;
+        let begin_l = maybe_loc(&lparen_t);
+        let end_l = maybe_loc(&rparen_t);
+        let expression_l = keyword_l
+            .maybe_join(&maybe_node_expr(&args.last()))
+            .maybe_join(&end_l);
+
+        Box::new(Node::Super(Super {
+            args,
+            keyword_l,
+            expression_l,
+            begin_l,
+            end_l,
+        }))
+    }
+
+    pub(crate) fn zsuper(keyword_t: Token) -> Box<Node> {
+        let expression_l = keyword_t.loc;
+        Box::new(Node::ZSuper(ZSuper { expression_l }))
+    }
+
     pub(crate) fn defined(
         keyword_t: Token,
         lparen_t: Option<Token>,
@@ -113,8 +145,9 @@ impl Builder {
         type_: KeywordCmd,
         keyword_t: Token,
         lparen_t: Option<Token>,
-        args: Vec<Node>,
+        mut args: Vec<Node>,
         rparen_t: Option<Token>,
+        diagnostics: &Diagnostics,
     ) -> Box<Node> {
         let keyword_l = keyword_t.loc;
 
@@ -122,10 +155,10 @@ impl Builder {
             KeywordCmd::Yield
                 if !args.is_empty() && matches!(args.last(), Some(Node::BlockPass(_))) =>
             {
-                // self.error(DiagnosticMessage::BlockGivenToYield {}, &keyword_l);
+                diagnostics.error(DiagnosticMessage::BlockGivenToYield, keyword_l);
             }
             KeywordCmd::Yield | KeywordCmd::Super => {
-                // self.rewrite_hash_args_to_kwargs(&mut args);
+                rewrite_hash_args_to_kwargs(&mut args);
             }
             _ => {}
         }
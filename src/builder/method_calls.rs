@@ -3,14 +3,16 @@ use crate::{
     builder::{
         helpers::{
             collection_map, maybe_boxed_node_expr, maybe_loc, maybe_node_expr, maybe_string_value,
-            static_regexp_captures, string_value,
+            rewrite_hash_args_to_kwargs, static_regexp_captures, string_value,
         },
         Builder,
     },
+    diagnostic::{DiagnosticMessage, Diagnostics},
     nodes::{
-        Begin, Block, BlockPass, Break, CSend, Index, IndexAsgn, Lambda, MatchWithLvasgn, Next,
-        Nil, Numblock, Return, Send, Yield,
+        Begin, Block, BlockPass, Break, CSend, Index, Lambda, MatchWithLvasgn, Next, Nil, Numblock,
+        Return, Send, Yield,
     },
+    static_environment::StaticEnvironment,
     string_content::StringContent,
     token::{Token, TokenKind},
     Loc, Node,
@@ -28,7 +30,7 @@ impl Builder {
         dot_t: Option<Token>,
         selector_t: Option<Token>,
         lparen_t: Option<Token>,
-        args: Vec<Node>,
+        mut args: Vec<Node>,
         rparen_t: Option<Token>,
         buffer: &Buffer,
     ) -> Box<Node> {
@@ -50,7 +52,7 @@ impl Builder {
         let method_name = maybe_string_value(selector_l, buffer);
         let method_name = method_name.unwrap_or_else(|| StringContent::from("call"));
 
-        // self.rewrite_hash_args_to_kwargs(&mut args);
+        rewrite_hash_args_to_kwargs(&mut args);
 
         match call_type_for_dot(&dot_t) {
             MethodCallType::Send => Box::new(Node::Send(Send {
@@ -91,6 +93,7 @@ impl Builder {
         block_args: ArgsType,
         body: Option<Box<Node>>,
         end_t: Token,
+        diagnostics: &Diagnostics,
     ) -> Box<Node> {
         let block_body = body;
 
@@ -98,10 +101,10 @@ impl Builder {
             if let Some(last_arg) = args.last() {
                 match last_arg {
                     Node::BlockPass(_) | Node::ForwardedArgs(_) => {
-                        // self.error(
-                        //     DiagnosticMessage::BlockAndBlockArgGiven {},
-                        //     last_arg.expression(),
-                        // );
+                        diagnostics.error(
+                            DiagnosticMessage::BlockAndBlockArgGiven,
+                            *last_arg.expression(),
+                        );
                     }
                     _ => {}
                 }
@@ -109,11 +112,8 @@ impl Builder {
         };
 
         match &*method_call {
-            Node::Yield(Yield {
-                keyword_l: _keyword_l,
-                ..
-            }) => {
-                // self.error(DiagnosticMessage::BlockGivenToYield {}, keyword_l);
+            Node::Yield(Yield { keyword_l, .. }) => {
+                diagnostics.error(DiagnosticMessage::BlockGivenToYield, *keyword_l);
             }
             Node::Send(Send { args, .. }) => {
                 validate_block_and_block_arg(args);
@@ -171,7 +171,9 @@ impl Builder {
             | Node::Index(_)
             | Node::Super(_)
             | Node::ZSuper(_)
-            | Node::Lambda(_) => {
+            | Node::Lambda(_)
+            // error has been reported above, the block is kept to get a complete AST
+            | Node::Yield(_) => {
                 let begin_l = begin_t.loc;
                 let end_l = end_t.loc;
                 let expression_l = method_call.expression().join(&end_l);
@@ -262,57 +264,17 @@ impl Builder {
         }))
     }
 
-    pub(crate) fn attr_asgn(
-        receiver: Box<Node>,
-        dot_t: Token,
-        selector_t: Token,
-        buffer: &Buffer,
-    ) -> Box<Node> {
-        let dot_l = dot_t.loc;
-        let selector_l = selector_t.loc;
-        let expression_l = receiver.expression().join(&selector_l);
-        let receiver: Box<Node> = receiver;
-
-        let mut method_name = string_value(selector_l, buffer);
-        method_name.push(b'=');
-
-        match call_type_for_dot(&Some(dot_t)) {
-            MethodCallType::Send => Box::new(Node::Send(Send {
-                recv: Some(receiver),
-                method_name,
-                args: vec![],
-                dot_l: Some(dot_l),
-                selector_l: Some(selector_l),
-                begin_l: None,
-                end_l: None,
-                operator_l: None,
-                expression_l,
-            })),
-
-            MethodCallType::CSend => Box::new(Node::CSend(CSend {
-                recv: receiver,
-                method_name,
-                args: vec![],
-                dot_l,
-                selector_l: Some(selector_l),
-                begin_l: None,
-                end_l: None,
-                operator_l: None,
-                expression_l,
-            })),
-        }
-    }
     pub(crate) fn index(
         recv: Box<Node>,
         lbrack_t: Token,
-        indexes: Vec<Node>,
+        mut indexes: Vec<Node>,
         rbrack_t: Token,
     ) -> Box<Node> {
         let begin_l = lbrack_t.loc;
         let end_l = rbrack_t.loc;
         let expression_l = recv.expression().join(&end_l);
 
-        // self.rewrite_hash_args_to_kwargs(&mut indexes);
+        rewrite_hash_args_to_kwargs(&mut indexes);
 
         Box::new(Node::Index(Index {
             recv,
@@ -322,26 +284,6 @@ impl Builder {
             expression_l,
         }))
     }
-    pub(crate) fn index_asgn(
-        recv: Box<Node>,
-        lbrack_t: Token,
-        indexes: Vec<Node>,
-        rbrack_t: Token,
-    ) -> Box<Node> {
-        let begin_l = lbrack_t.loc;
-        let end_l = rbrack_t.loc;
-        let expression_l = recv.expression().join(&end_l);
-
-        Box::new(Node::IndexAsgn(IndexAsgn {
-            recv,
-            indexes,
-            value: None,
-            begin_l,
-            end_l,
-            operator_l: None,
-            expression_l,
-        }))
-    }
     pub(crate) fn binary_op(
         receiver: Box<Node>,
         operator_t: Token,
@@ -366,7 +308,12 @@ impl Builder {
             expression_l,
         }))
     }
-    pub(crate) fn match_op(receiver: Box<Node>, match_t: Token, arg: Box<Node>) -> Box<Node> {
+    pub(crate) fn match_op(
+        receiver: Box<Node>,
+        match_t: Token,
+        arg: Box<Node>,
+        static_env: &StaticEnvironment,
+    ) -> Box<Node> {
         // TODO: check receiver is value_expr
         // TODO: check arg is value_expr
 
@@ -374,11 +321,10 @@ impl Builder {
         let expression_l = receiver.expression().join(arg.expression());
 
         let result = match static_regexp_captures(&receiver) {
-            Some(_captures) => {
-                // TODO: declare all captures in static env
-                // for capture in captures {
-                //     static_env.declare(&capture);
-                // }
+            Some(captures) => {
+                for capture in captures {
+                    static_env.declare(capture.as_bytes());
+                }
 
                 Node::MatchWithLvasgn(MatchWithLvasgn {
                     re: receiver,
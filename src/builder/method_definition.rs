@@ -69,6 +69,7 @@ impl Builder {
         }))
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn def_singleton(
         def_t: Token,
         definee: Box<Node>,
@@ -102,6 +103,7 @@ impl Builder {
         }))
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn def_endless_singleton(
         def_t: Token,
         definee: Box<Node>,
@@ -31,3 +31,4 @@ pub(crate) struct Builder;
 pub(crate) use keywords::KeywordCmd;
 pub(crate) use loops::LoopType;
 pub(crate) use method_calls::ArgsType;
+pub(crate) use pattern_matching::PKwLabel;
@@ -1,12 +1,61 @@
 use crate::{
-    builder::Builder,
-    nodes::{MatchPattern, MatchPatternP},
+    buffer::Buffer,
+    builder::{
+        helpers::{collection_map, maybe_boxed_node_expr, maybe_loc, string_value},
+        Builder,
+    },
+    diagnostic::{DiagnosticMessage, Diagnostics},
+    loc::loc,
+    nodes::{
+        ArrayPattern, ArrayPatternWithTail, CaseMatch, ConstPattern, EmptyElse, FindPattern,
+        HashPattern, IfGuard, InPattern, MatchAlt, MatchAs, MatchNilPattern, MatchPattern,
+        MatchPatternP, MatchRest, MatchVar, Pin, Str, UnlessGuard,
+    },
+    static_environment::StaticEnvironment,
+    string_content::StringContent,
     token::Token,
-    Node,
+    Loc, Node,
 };
 
+/// Key of the hash pattern element
+pub(crate) enum PKwLabel {
+    /// `foo:`
+    PlainLabel(Token),
+    /// `"foo":` (string node and `:` token)
+    QuotedLabel(Box<Node>, Token),
+}
+
 impl Builder {
-    pub(crate) fn case_match() {}
+    pub(crate) fn case_match(
+        case_t: Token,
+        expr: Box<Node>,
+        in_bodies: Vec<Node>,
+        else_t: Option<Token>,
+        else_body: Option<Box<Node>>,
+        end_t: Token,
+    ) -> Box<Node> {
+        let else_body = match (else_t, else_body) {
+            (Some(else_t), None) => Some(Box::new(Node::EmptyElse(EmptyElse {
+                expression_l: else_t.loc,
+            }))),
+            (_, else_body) => else_body,
+        };
+
+        let keyword_l = case_t.loc;
+        let else_l = maybe_loc(&else_t);
+        let end_l = end_t.loc;
+        let expression_l = keyword_l.join(&end_l);
+
+        Box::new(Node::CaseMatch(CaseMatch {
+            expr,
+            in_bodies,
+            else_body,
+            keyword_l,
+            else_l,
+            end_l,
+            expression_l,
+        }))
+    }
 
     pub(crate) fn match_pattern(value: Box<Node>, assoc_t: Token, pattern: Box<Node>) -> Box<Node> {
         let operator_l = assoc_t.loc;
@@ -32,55 +81,293 @@ impl Builder {
         }))
     }
 
-    pub(crate) fn in_pattern() -> Box<Node> {
-        todo!("builder.in_pattern")
+    pub(crate) fn in_pattern(
+        in_t: Token,
+        pattern: Box<Node>,
+        guard: Option<Box<Node>>,
+        then_t: Token,
+        body: Option<Box<Node>>,
+    ) -> Box<Node> {
+        let keyword_l = in_t.loc;
+        let begin_l = then_t.loc;
+
+        let expression_l = maybe_boxed_node_expr(&body)
+            .unwrap_or(begin_l)
+            .join(&keyword_l);
+
+        Box::new(Node::InPattern(InPattern {
+            pattern,
+            guard,
+            body,
+            keyword_l,
+            begin_l,
+            expression_l,
+        }))
     }
-    pub(crate) fn if_guard() -> Box<Node> {
-        todo!("builder.if_guard")
+
+    pub(crate) fn if_guard(if_t: Token, cond: Box<Node>) -> Box<Node> {
+        let keyword_l = if_t.loc;
+        let expression_l = keyword_l.join(cond.expression());
+
+        Box::new(Node::IfGuard(IfGuard {
+            cond,
+            keyword_l,
+            expression_l,
+        }))
     }
-    pub(crate) fn unless_guard() -> Box<Node> {
-        todo!("builder.unless_guard")
+
+    pub(crate) fn unless_guard(unless_t: Token, cond: Box<Node>) -> Box<Node> {
+        let keyword_l = unless_t.loc;
+        let expression_l = keyword_l.join(cond.expression());
+
+        Box::new(Node::UnlessGuard(UnlessGuard {
+            cond,
+            keyword_l,
+            expression_l,
+        }))
     }
-    pub(crate) fn match_var() -> Box<Node> {
-        todo!("builder.match_var")
+
+    pub(crate) fn match_var(
+        name_t: Token,
+        buffer: &Buffer,
+        static_env: &StaticEnvironment,
+    ) -> Box<Node> {
+        let name_l = name_t.loc;
+        let name = string_value(name_l, buffer);
+        static_env.declare(name.as_bytes());
+
+        Box::new(Node::MatchVar(MatchVar {
+            name,
+            name_l,
+            expression_l: name_l,
+        }))
     }
-    pub(crate) fn match_hash_var() -> Box<Node> {
-        todo!("builder.match_hash_var")
+
+    pub(crate) fn match_hash_var(
+        label_t: Token,
+        buffer: &Buffer,
+        static_env: &StaticEnvironment,
+    ) -> Box<Node> {
+        let expression_l = label_t.loc;
+        let name_l = expression_l.adjust_end(-1);
+        let name = string_value(name_l, buffer);
+        static_env.declare(name.as_bytes());
+
+        Box::new(Node::MatchVar(MatchVar {
+            name,
+            name_l,
+            expression_l,
+        }))
     }
-    pub(crate) fn match_hash_var_from_str() -> Box<Node> {
-        todo!("builder.match_hash_var_from_str")
+
+    pub(crate) fn match_hash_var_from_str(
+        key: Box<Node>,
+        colon_t: Token,
+        buffer: &Buffer,
+        static_env: &StaticEnvironment,
+        diagnostics: &Diagnostics,
+    ) -> Box<Node> {
+        let (name, name_l) = quoted_label_name(&key, buffer, diagnostics);
+        static_env.declare(name.as_bytes());
+        let expression_l = key.expression().join(&colon_t.loc);
+
+        Box::new(Node::MatchVar(MatchVar {
+            name,
+            name_l,
+            expression_l,
+        }))
     }
-    pub(crate) fn match_rest() -> Box<Node> {
-        todo!("builder.match_rest")
+
+    pub(crate) fn match_rest(star_t: Token, name: Option<Box<Node>>) -> Box<Node> {
+        let operator_l = star_t.loc;
+        let expression_l = operator_l.maybe_join(&maybe_boxed_node_expr(&name));
+
+        Box::new(Node::MatchRest(MatchRest {
+            name,
+            operator_l,
+            expression_l,
+        }))
+    }
+
+    pub(crate) fn hash_pattern(
+        lbrace_t: Option<Token>,
+        kwargs: Vec<Node>,
+        rbrace_t: Option<Token>,
+    ) -> Box<Node> {
+        let (begin_l, end_l, expression_l) = collection_map(&lbrace_t, &kwargs, &rbrace_t);
+
+        Box::new(Node::HashPattern(HashPattern {
+            elements: kwargs,
+            begin_l,
+            end_l,
+            expression_l,
+        }))
     }
-    pub(crate) fn hash_pattern() -> Box<Node> {
-        todo!("builder.hash_pattern")
+
+    pub(crate) fn array_pattern(
+        lbrack_t: Option<Token>,
+        elements: Vec<Node>,
+        trailing_comma: Option<Token>,
+        rbrack_t: Option<Token>,
+    ) -> Box<Node> {
+        let (begin_l, end_l, expression_l) = collection_map(&lbrack_t, &elements, &rbrack_t);
+        let expression_l = expression_l.maybe_join(&maybe_loc(&trailing_comma));
+
+        if trailing_comma.is_some() {
+            Box::new(Node::ArrayPatternWithTail(ArrayPatternWithTail {
+                elements,
+                begin_l,
+                end_l,
+                expression_l,
+            }))
+        } else {
+            Box::new(Node::ArrayPattern(ArrayPattern {
+                elements,
+                begin_l,
+                end_l,
+                expression_l,
+            }))
+        }
     }
-    pub(crate) fn array_pattern() -> Box<Node> {
-        todo!("builder.array_pattern")
+
+    pub(crate) fn find_pattern(
+        lbrack_t: Option<Token>,
+        elements: Vec<Node>,
+        rbrack_t: Option<Token>,
+    ) -> Box<Node> {
+        let (begin_l, end_l, expression_l) = collection_map(&lbrack_t, &elements, &rbrack_t);
+
+        Box::new(Node::FindPattern(FindPattern {
+            elements,
+            begin_l,
+            end_l,
+            expression_l,
+        }))
     }
-    pub(crate) fn find_pattern() -> Box<Node> {
-        todo!("builder.find_pattern")
+
+    pub(crate) fn const_pattern(
+        const_: Box<Node>,
+        ldelim_t: Token,
+        pattern: Box<Node>,
+        rdelim_t: Token,
+    ) -> Box<Node> {
+        let begin_l = ldelim_t.loc;
+        let end_l = rdelim_t.loc;
+        let expression_l = const_.expression().join(&end_l);
+
+        Box::new(Node::ConstPattern(ConstPattern {
+            const_,
+            pattern,
+            begin_l,
+            end_l,
+            expression_l,
+        }))
     }
-    pub(crate) fn const_pattern() -> Box<Node> {
-        todo!("builder.const_pattern")
+
+    pub(crate) fn pin(pin_t: Token, var: Box<Node>) -> Box<Node> {
+        let selector_l = pin_t.loc;
+        let expression_l = var.expression().join(&selector_l);
+
+        Box::new(Node::Pin(Pin {
+            var,
+            selector_l,
+            expression_l,
+        }))
     }
-    pub(crate) fn pin() -> Box<Node> {
-        todo!("builder.pin")
+
+    pub(crate) fn match_alt(lhs: Box<Node>, pipe_t: Token, rhs: Box<Node>) -> Box<Node> {
+        let operator_l = pipe_t.loc;
+        let expression_l = lhs.expression().join(rhs.expression());
+
+        Box::new(Node::MatchAlt(MatchAlt {
+            lhs,
+            rhs,
+            operator_l,
+            expression_l,
+        }))
     }
-    pub(crate) fn match_alt() -> Box<Node> {
-        todo!("builder.match_alt")
+
+    pub(crate) fn match_as(value: Box<Node>, assoc_t: Token, as_: Box<Node>) -> Box<Node> {
+        let operator_l = assoc_t.loc;
+        let expression_l = value.expression().join(as_.expression());
+
+        Box::new(Node::MatchAs(MatchAs {
+            value,
+            as_,
+            operator_l,
+            expression_l,
+        }))
     }
-    pub(crate) fn match_as() -> Box<Node> {
-        todo!("builder.match_as")
+
+    pub(crate) fn match_nil_pattern(dstar_t: Token, nil_t: Token) -> Box<Node> {
+        let operator_l = dstar_t.loc;
+        let name_l = nil_t.loc;
+        let expression_l = operator_l.join(&name_l);
+
+        Box::new(Node::MatchNilPattern(MatchNilPattern {
+            operator_l,
+            name_l,
+            expression_l,
+        }))
     }
-    pub(crate) fn match_nil_pattern() -> Box<Node> {
-        todo!("builder.match_nil_pattern")
+
+    pub(crate) fn match_pair(
+        label: PKwLabel,
+        value: Box<Node>,
+        buffer: &Buffer,
+        diagnostics: &Diagnostics,
+    ) -> Box<Node> {
+        match label {
+            PKwLabel::PlainLabel(label_t) => Self::pair_keyword(label_t, value, buffer),
+            PKwLabel::QuotedLabel(key, colon_t) => {
+                // only checks for interpolation, the name itself is not needed
+                quoted_label_name(&key, buffer, diagnostics);
+                Self::pair_quoted(key, colon_t, value)
+            }
+        }
     }
-    pub(crate) fn match_pair() -> Box<Node> {
-        todo!("builder.match_pair")
+
+    pub(crate) fn match_label(
+        label: PKwLabel,
+        buffer: &Buffer,
+        static_env: &StaticEnvironment,
+        diagnostics: &Diagnostics,
+    ) -> Box<Node> {
+        match label {
+            PKwLabel::PlainLabel(label_t) => Self::match_hash_var(label_t, buffer, static_env),
+            PKwLabel::QuotedLabel(key, colon_t) => {
+                Self::match_hash_var_from_str(key, colon_t, buffer, static_env, diagnostics)
+            }
+        }
     }
-    pub(crate) fn match_label() -> Box<Node> {
-        todo!("builder.match_label")
+}
+
+// Returns the name of `"foo":` label and its location (without quotes),
+// reports an error if the label has interpolation
+fn quoted_label_name(
+    key: &Node,
+    buffer: &Buffer,
+    diagnostics: &Diagnostics,
+) -> (StringContent, Loc) {
+    if let Node::Str(Str {
+        value,
+        begin_l,
+        end_l,
+        expression_l,
+    }) = key
+    {
+        let name_l = match (begin_l, end_l) {
+            (Some(begin_l), Some(end_l)) => loc!(begin_l.end, end_l.start),
+            _ => *expression_l,
+        };
+        return (value.clone(), name_l);
     }
+
+    let expression_l = *key.expression();
+    diagnostics.error(
+        DiagnosticMessage::SymbolLiteralWithInterpolation,
+        expression_l,
+    );
+    let name_l = loc!(expression_l.start + 1, expression_l.end - 1);
+    (string_value(name_l, buffer), name_l)
 }
@@ -1,7 +1,7 @@
 use crate::{
     buffer::Buffer,
     builder::{
-        helpers::{collection_map, heredoc_map, is_heredoc, string_value},
+        helpers::{collection_map, dedent_heredoc_parts, heredoc_map, is_heredoc, string_value},
         Builder,
     },
     loc::loc,
@@ -104,6 +104,17 @@ impl Builder {
         }
     }
 
+    pub(crate) fn heredoc_dedent(begin_t: &Token, parts: Vec<Node>, buffer: &Buffer) -> Vec<Node> {
+        // only `<<~` heredocs are dedented
+        if begin_t.is_one_of([TokenKind::tHEREDOC_BEG, TokenKind::tXHEREDOC_BEG])
+            && buffer.lookahead(begin_t.loc.start, b"<<~")
+        {
+            dedent_heredoc_parts(parts)
+        } else {
+            parts
+        }
+    }
+
     pub(crate) fn character(char_t: Token) -> Box<Node> {
         let expression_l = char_t.loc;
         let begin_l = loc!(expression_l.start, expression_l.start + 1);
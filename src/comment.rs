@@ -0,0 +1,66 @@
+use crate::{buffer::Buffer, Loc};
+
+/// Kind of the comment
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommentKind {
+    /// `# foo`
+    Inline,
+
+    /// `=begin ... =end`
+    Document,
+}
+
+/// A single comment, consecutive inline comments are not merged
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Comment {
+    /// Kind of the comment
+    pub kind: CommentKind,
+
+    /// Location of the comment (including `#` or `=begin`/`=end`)
+    pub loc: Loc,
+}
+
+impl Comment {
+    pub(crate) fn new(loc: Loc, buffer: &Buffer) -> Self {
+        let kind = match buffer.slice(loc.start, loc.end) {
+            Some(source) if source.starts_with(b"=begin") => CommentKind::Document,
+            _ => CommentKind::Inline,
+        };
+        Self { kind, loc }
+    }
+}
+
+#[test]
+fn test_comments_are_collected() {
+    use crate::{lexer::Lexer, loc::loc, token::TokenKind};
+
+    let mut lexer = Lexer::new(b"# foo\n# bar\nbaz # qux\n");
+    let mut kinds = vec![];
+    loop {
+        let token = lexer.current_token();
+        lexer.skip_token();
+        kinds.push(token.kind);
+        if token.is(TokenKind::tEOF) {
+            break;
+        }
+    }
+
+    assert_eq!(kinds, vec![TokenKind::tIDENTIFIER, TokenKind::tEOF]);
+    assert_eq!(
+        lexer.comments,
+        vec![
+            Comment {
+                kind: CommentKind::Inline,
+                loc: loc!(0, 5)
+            },
+            Comment {
+                kind: CommentKind::Inline,
+                loc: loc!(6, 11)
+            },
+            Comment {
+                kind: CommentKind::Inline,
+                loc: loc!(16, 21)
+            },
+        ]
+    );
+}
@@ -0,0 +1,26 @@
+use std::cell::RefCell;
+
+// Names of the arguments whose default values are being parsed right now,
+// used to detect circular references like `def foo(a = a)`.
+#[derive(Debug, Default)]
+pub(crate) struct CurrentArgStack {
+    stack: RefCell<Vec<Vec<u8>>>,
+}
+
+impl CurrentArgStack {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    pub(crate) fn push(&self, name: &[u8]) {
+        self.stack.borrow_mut().push(name.to_vec())
+    }
+
+    pub(crate) fn pop(&self) {
+        self.stack.borrow_mut().pop();
+    }
+
+    pub(crate) fn is_current(&self, name: &[u8]) -> bool {
+        self.stack.borrow().last().map(|arg| arg.as_slice()) == Some(name)
+    }
+}
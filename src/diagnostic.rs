@@ -0,0 +1,208 @@
+use std::cell::RefCell;
+
+use crate::Loc;
+
+/// Level of the diagnostic
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiagnosticLevel {
+    /// Something suspicious that doesn't prevent the code from running
+    Warning,
+
+    /// Invalid code that is still represented in the AST
+    Error,
+}
+
+/// Non-fatal problem found during lexing/parsing
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiagnosticMessage {
+    // Lexer warnings
+    /// `?ident` that is lexed as `?` + `ident`
+    AmbiguousTernaryOperator { condition: String },
+    /// `\r` that is not followed by `\n`
+    SlashRAtMiddleOfLine,
+
+    // Lexer errors
+    /// Malformed `\u` escape in a string literal
+    InvalidUnicodeEscape,
+    /// Malformed `\x` escape in a string literal
+    InvalidHexEscape,
+    /// Malformed `\M-`/`\C-`/`\c` escape in a string literal
+    InvalidMetaCtrlEscape,
+    /// Malformed escape sequence in a string literal
+    InvalidEscape,
+
+    // Builder warnings
+    /// `{ a: 1, a: 2 }`
+    DuplicateHashKey,
+    /// `$12345678901234567890` (stores the number without `$`)
+    NthRefIsTooBig { nth_ref: String },
+
+    // Builder errors
+    /// `foo(&blk) { }`
+    BlockAndBlockArgGiven,
+    /// `yield { }` or `yield(&blk)`
+    BlockGivenToYield,
+    /// `$1 = 1` or `$& += 1`
+    CantSetVariable { var_name: String },
+    /// `self = 1`
+    CantAssignToSelf,
+    /// `nil = 1`
+    CantAssignToNil,
+    /// `true = 1`
+    CantAssignToTrue,
+    /// `false = 1`
+    CantAssignToFalse,
+    /// `__FILE__ = 1`
+    CantAssignToFile,
+    /// `__LINE__ = 1`
+    CantAssignToLine,
+    /// `__ENCODING__ = 1`
+    CantAssignToEncoding,
+    /// `case foo; in "#{bar}": 1; end`
+    SymbolLiteralWithInterpolation,
+    /// `def foo(a = a); end`
+    CircularArgumentReference { arg_name: String },
+    /// `foo { |a| _1 }`
+    OrdinaryParamDefined,
+    /// `foo { _1; bar { _2 } }`
+    NumparamUsedInOuterScope,
+    /// `foo { _1; it }`
+    ItIsNotAllowedWithNumparam,
+    /// `foo { it; _1 }`
+    NumparamIsNotAllowedWithIt,
+}
+
+impl DiagnosticMessage {
+    /// Renders the message the same way MRI does
+    pub fn render(&self) -> String {
+        match self {
+            Self::AmbiguousTernaryOperator { condition } => format!(
+                "`?' just followed by `{}' is interpreted as a conditional operator, put a space after `?'",
+                condition
+            ),
+            Self::SlashRAtMiddleOfLine => {
+                "encountered \\r in middle of line, treated as a mere space".to_string()
+            }
+            Self::InvalidUnicodeEscape => "invalid Unicode escape".to_string(),
+            Self::InvalidHexEscape => "invalid hex escape".to_string(),
+            Self::InvalidMetaCtrlEscape => "invalid meta/control escape".to_string(),
+            Self::InvalidEscape => "invalid escape character syntax".to_string(),
+            Self::DuplicateHashKey => "key is duplicated and overwritten".to_string(),
+            Self::NthRefIsTooBig { nth_ref } => format!(
+                "`${}' is too big for a number variable, always nil",
+                nth_ref
+            ),
+            Self::BlockAndBlockArgGiven => "both block arg and actual block given".to_string(),
+            Self::BlockGivenToYield => "block given to yield".to_string(),
+            Self::CantSetVariable { var_name } => format!("Can't set variable {}", var_name),
+            Self::CantAssignToSelf => "Can't change the value of self".to_string(),
+            Self::CantAssignToNil => "Can't assign to nil".to_string(),
+            Self::CantAssignToTrue => "Can't assign to true".to_string(),
+            Self::CantAssignToFalse => "Can't assign to false".to_string(),
+            Self::CantAssignToFile => "Can't assign to __FILE__".to_string(),
+            Self::CantAssignToLine => "Can't assign to __LINE__".to_string(),
+            Self::CantAssignToEncoding => "Can't assign to __ENCODING__".to_string(),
+            Self::SymbolLiteralWithInterpolation => {
+                "symbol literal with interpolation is not allowed".to_string()
+            }
+            Self::CircularArgumentReference { arg_name } => {
+                format!("circular argument reference - {}", arg_name)
+            }
+            Self::OrdinaryParamDefined => "ordinary parameter is defined".to_string(),
+            Self::NumparamUsedInOuterScope => {
+                "numbered parameter is already used in outer block".to_string()
+            }
+            Self::ItIsNotAllowedWithNumparam => {
+                "`it` is not allowed when a numbered parameter is already used".to_string()
+            }
+            Self::NumparamIsNotAllowedWithIt => {
+                "numbered parameters are not allowed when 'it' is already used".to_string()
+            }
+        }
+    }
+}
+
+/// A warning or a non-fatal error attached to some location
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    /// Level of the diagnostic
+    pub level: DiagnosticLevel,
+
+    /// Message of the diagnostic
+    pub message: DiagnosticMessage,
+
+    /// Location of the diagnostic
+    pub loc: Loc,
+}
+
+impl std::fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let level = match self.level {
+            DiagnosticLevel::Warning => "warning",
+            DiagnosticLevel::Error => "error",
+        };
+        write!(f, "{}: {} at {:?}", level, self.message.render(), self.loc)
+    }
+}
+
+// Shared between lexer and builder,
+// builder functions only get `&Diagnostics` (just like `&Buffer`),
+// so it has to be mutable through a shared reference.
+#[derive(Debug, Default)]
+pub(crate) struct Diagnostics {
+    list: RefCell<Vec<Diagnostic>>,
+}
+
+impl Diagnostics {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    pub(crate) fn warn(&self, message: DiagnosticMessage, loc: Loc) {
+        self.emit(DiagnosticLevel::Warning, message, loc)
+    }
+
+    pub(crate) fn error(&self, message: DiagnosticMessage, loc: Loc) {
+        self.emit(DiagnosticLevel::Error, message, loc)
+    }
+
+    fn emit(&self, level: DiagnosticLevel, message: DiagnosticMessage, loc: Loc) {
+        self.list.borrow_mut().push(Diagnostic {
+            level,
+            message,
+            loc,
+        })
+    }
+
+    pub(crate) fn take_inner(&self) -> Vec<Diagnostic> {
+        std::mem::take(&mut *self.list.borrow_mut())
+    }
+}
+
+#[test]
+fn test_display() {
+    use crate::loc::loc;
+
+    let diagnostics = Diagnostics::new();
+    diagnostics.warn(DiagnosticMessage::DuplicateHashKey, loc!(10, 11));
+    diagnostics.error(
+        DiagnosticMessage::CantSetVariable {
+            var_name: "$1".to_string(),
+        },
+        loc!(0, 2),
+    );
+
+    let rendered = diagnostics
+        .take_inner()
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>();
+    assert_eq!(
+        rendered,
+        vec![
+            "warning: key is duplicated and overwritten at 10...11",
+            "error: Can't set variable $1 at 0...2"
+        ]
+    );
+    assert!(diagnostics.take_inner().is_empty());
+}
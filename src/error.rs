@@ -0,0 +1,142 @@
+use crate::{Diagnostic, Loc, Token, TokenKind};
+
+/// Kind of the error that stopped lexing/parsing
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseErrorKind {
+    // Lexer errors
+    /// `%` followed by an unknown literal type, like `%z()`
+    UnknownPercentLiteralType,
+    /// `%` or `%<type>` at the end of input
+    UnterminatedPercentLiteral,
+    /// `0x`, `0b`, `0o` or `0d` prefix without digits after it
+    NumericLiteralWithoutDigits,
+    /// `.1` (Ruby requires a leading `0` in float literals)
+    NoDigitsBeforeDot,
+    /// `?\u{...}` with more than one codepoint or a malformed `\u` escape
+    InvalidUnicodeEscape,
+    /// Malformed `\x` escape
+    InvalidHexEscape,
+    /// Malformed `\M-`/`\C-`/`\c` escape
+    InvalidMetaCtrlEscape,
+    /// Malformed escape sequence
+    InvalidEscape,
+    /// Byte sequence that is not a valid UTF-8 character
+    InvalidMultibyteChar,
+    /// `=begin` without a matching `=end`
+    EmbeddedDocumentMeetsEof,
+    /// String, symbol, regexp or heredoc without the closing delimiter
+    UnterminatedString,
+
+    // Parser errors
+    /// Got a token that can't be handled by the grammar at this point
+    UnexpectedToken,
+    /// `class`/`module` name is not a constant
+    InvalidClassOrModuleName,
+    /// `module Foo < Bar`
+    ModuleWithSuperclass,
+    /// `a, 1 = 2` (a value that can't be assigned)
+    InvalidAssignmentTarget,
+    /// `BEGIN { }` inside of a method, class or block
+    BeginNotAtTopLevel,
+    /// `def foo=(value) = value`
+    EndlessSetterMethod,
+}
+
+impl ParseErrorKind {
+    fn message(&self) -> &'static str {
+        match self {
+            Self::UnknownPercentLiteralType => "unknown type of %string",
+            Self::UnterminatedPercentLiteral => "unterminated quoted string meets end of file",
+            Self::NumericLiteralWithoutDigits => "numeric literal without digits",
+            Self::NoDigitsBeforeDot => "no .<digit> floating literal anymore; put 0 before dot",
+            Self::InvalidUnicodeEscape => "invalid Unicode escape",
+            Self::InvalidHexEscape => "invalid hex escape",
+            Self::InvalidMetaCtrlEscape => "invalid meta/control escape",
+            Self::InvalidEscape => "invalid escape character syntax",
+            Self::InvalidMultibyteChar => "invalid multibyte char (UTF-8)",
+            Self::EmbeddedDocumentMeetsEof => "embedded document meets end of file",
+            Self::UnterminatedString => "unterminated string meets end of file",
+            Self::UnexpectedToken => "unexpected token",
+            Self::InvalidClassOrModuleName => "class/module name must be CONSTANT",
+            Self::ModuleWithSuperclass => "module can't have a superclass",
+            Self::InvalidAssignmentTarget => "can't assign to this expression",
+            Self::BeginNotAtTopLevel => "BEGIN is permitted only at toplevel",
+            Self::EndlessSetterMethod => {
+                "setter method cannot be defined in an endless method definition"
+            }
+        }
+    }
+}
+
+/// An error that stopped lexing/parsing
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// Kind of the error
+    pub kind: ParseErrorKind,
+
+    /// Location of the error
+    pub loc: Loc,
+
+    /// Token that was expected (if known)
+    pub expected: Option<TokenKind>,
+
+    /// Token that was found instead (if any)
+    pub found: Option<TokenKind>,
+
+    /// Warnings and errors that have been reported before parsing stopped,
+    /// filled in by `Parser::parse`
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl ParseError {
+    pub(crate) fn new(kind: ParseErrorKind, loc: Loc) -> Self {
+        Self {
+            kind,
+            loc,
+            expected: None,
+            found: None,
+            diagnostics: vec![],
+        }
+    }
+
+    pub(crate) fn unexpected_token(expected: Option<TokenKind>, found: Token) -> Self {
+        Self {
+            kind: ParseErrorKind::UnexpectedToken,
+            loc: found.loc,
+            expected,
+            found: Some(found.kind),
+            diagnostics: vec![],
+        }
+    }
+}
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} at {:?}", self.kind.message(), self.loc)?;
+        if let Some(found) = self.found {
+            write!(f, ", got {:?}", found)?;
+        }
+        if let Some(expected) = self.expected {
+            write!(f, ", expected {:?}", expected)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+pub(crate) type ParseResult<T> = Result<T, ParseError>;
+
+#[test]
+fn test_display() {
+    use crate::{loc::loc, token::token};
+
+    assert_eq!(
+        ParseError::unexpected_token(Some(TokenKind::kEND), token!(tEOF, loc!(3, 3))).to_string(),
+        "unexpected token at 3...3, got tEOF, expected kEND"
+    );
+    assert_eq!(
+        ParseError::new(ParseErrorKind::NumericLiteralWithoutDigits, loc!(0, 2)).to_string(),
+        "numeric literal without digits at 0...2"
+    );
+}
@@ -11,7 +11,63 @@ impl Lexer {
             None | Some(b'\0' | 0x04 | 0x1a) => {
                 Some(token!(tEOF, loc!(self.buffer.pos(), self.buffer.pos())))
             }
+            Some(b'_') => self.handle_data_section(),
             _ => None,
         }
     }
+
+    // `__END__` on its own line stops lexing,
+    // everything after it is a data section (available in Ruby as `DATA`)
+    fn handle_data_section(&mut self) -> Option<Token> {
+        let start = self.buffer.pos();
+        let at_line_start = start == 0 || self.buffer.byte_at(start - 1) == Some(b'\n');
+        if !at_line_start || !self.buffer.lookahead(b"__END__") {
+            return None;
+        }
+
+        let mut data_start = start + "__END__".len();
+        match self.buffer.byte_at(data_start) {
+            None => {}
+            Some(b'\n') => data_start += 1,
+            Some(b'\r') if self.buffer.byte_at(data_start + 1) == Some(b'\n') => data_start += 2,
+            _ => return None,
+        }
+
+        let data_end = self.buffer.for_lookahead().bytes().len();
+        self.data_section_l = Some(loc!(data_start, data_end));
+        Some(token!(tEOF, loc!(start, start)))
+    }
+}
+
+#[test]
+fn test_data_section() {
+    let mut lexer = Lexer::new(b"foo\n__END__\nbar baz\n");
+    lexer.tokenize_until_eof();
+    assert_eq!(lexer.data_section_l, Some(loc!(12, 20)));
+}
+
+#[test]
+fn test_data_section_crlf() {
+    let mut lexer = Lexer::new(b"__END__\r\nbar");
+    assert_eq!(lexer.next_token(), token!(tEOF, loc!(0, 0)));
+    assert_eq!(lexer.data_section_l, Some(loc!(9, 12)));
+}
+
+#[test]
+fn test_data_section_at_eof() {
+    let mut lexer = Lexer::new(b"__END__");
+    assert_eq!(lexer.next_token(), token!(tEOF, loc!(0, 0)));
+    assert_eq!(lexer.data_section_l, Some(loc!(7, 7)));
+}
+
+#[test]
+fn test_end_is_not_alone() {
+    use crate::token::TokenKind;
+
+    for input in [&b"__END__ foo"[..], b"a __END__\n", b"__END__FOO\n"] {
+        let mut lexer = Lexer::new(input);
+        let tokens = lexer.tokenize_until_eof();
+        assert!(tokens.iter().any(|token| token.is(TokenKind::tIDENTIFIER)));
+        assert_eq!(lexer.data_section_l, None);
+    }
 }
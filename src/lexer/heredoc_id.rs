@@ -11,6 +11,7 @@ pub(crate) struct HeredocId {
     pub(crate) id: (usize, usize),
     pub(crate) interpolated: bool,
     pub(crate) squiggly: bool,
+    pub(crate) dash: bool,
 }
 
 pub(crate) enum HeredocIdError {
@@ -18,7 +19,10 @@ pub(crate) enum HeredocIdError {
 }
 
 impl HeredocId {
-    fn lookahead(buffer: &Buffer, mut start: usize) -> Result<Option<Self>, HeredocIdError> {
+    pub(crate) fn lookahead(
+        buffer: &Buffer,
+        mut start: usize,
+    ) -> Result<Option<Self>, HeredocIdError> {
         // We are at `<<`
         let heredoc_start = start;
         // consume `<<`
@@ -27,6 +31,7 @@ impl HeredocId {
         let mut id_start = start;
         let token_value;
         let squiggly;
+        let dash;
         let interpolated;
         let quote;
 
@@ -35,38 +40,41 @@ impl HeredocId {
             Some(b'~') => {
                 id_start += 1;
                 squiggly = true;
+                dash = false;
             }
             Some(b'-') => {
                 id_start += 1;
                 squiggly = false;
+                dash = true;
             }
             _ => {
                 squiggly = false;
+                dash = false;
             }
         }
 
         // Check if there's a ' or " or ` around heredoc id
         match buffer.byte_at(id_start) {
             Some(b'\'') => {
-                token_value = TokenKind::tSTRING_BEG;
+                token_value = TokenKind::tHEREDOC_BEG;
                 interpolated = false;
                 id_start += 1;
                 quote = Some(b'\'');
             }
             Some(b'"') => {
-                token_value = TokenKind::tDSTRING_BEG;
+                token_value = TokenKind::tHEREDOC_BEG;
                 interpolated = true;
                 id_start += 1;
                 quote = Some(b'"');
             }
             Some(b'`') => {
-                token_value = TokenKind::tXSTRING_BEG;
+                token_value = TokenKind::tXHEREDOC_BEG;
                 interpolated = true;
                 id_start += 1;
                 quote = Some(b'`');
             }
             _ => {
-                token_value = TokenKind::tDSTRING_BEG;
+                token_value = TokenKind::tHEREDOC_BEG;
                 interpolated = true;
                 quote = None;
             }
@@ -112,6 +120,7 @@ impl HeredocId {
             token,
             id: (id_start, id_end),
             squiggly,
+            dash,
             interpolated,
         }))
     }
@@ -157,10 +166,11 @@ mod tests {
         assert_parses_heredoc_id!(
             b"<<-HERE",
             Some(HeredocId {
-                token: token!(tDSTRING_BEG, loc!(0, 7)),
+                token: token!(tHEREDOC_BEG, loc!(0, 7)),
                 id: (3, 7),
                 interpolated: true,
-                squiggly: false
+                squiggly: false,
+                dash: true
             })
         );
     }
@@ -170,10 +180,25 @@ mod tests {
         assert_parses_heredoc_id!(
             b"<<~HERE",
             Some(HeredocId {
-                token: token!(tDSTRING_BEG, loc!(0, 7)),
+                token: token!(tHEREDOC_BEG, loc!(0, 7)),
                 id: (3, 7),
                 interpolated: true,
-                squiggly: true
+                squiggly: true,
+                dash: false
+            })
+        );
+    }
+
+    #[test]
+    fn test_heredoc_id_no_prefix() {
+        assert_parses_heredoc_id!(
+            b"<<HERE",
+            Some(HeredocId {
+                token: token!(tHEREDOC_BEG, loc!(0, 6)),
+                id: (2, 6),
+                interpolated: true,
+                squiggly: false,
+                dash: false
             })
         );
     }
@@ -184,10 +209,11 @@ mod tests {
         assert_parses_heredoc_id!(
             b"<<-'HERE'",
             Some(HeredocId {
-                token: token!(tSTRING_BEG, loc!(0, 9)),
+                token: token!(tHEREDOC_BEG, loc!(0, 9)),
                 id: (4, 8),
                 interpolated: false,
-                squiggly: false
+                squiggly: false,
+                dash: true
             })
         );
     }
@@ -196,10 +222,11 @@ mod tests {
         assert_parses_heredoc_id!(
             b"<<-\"HERE\"",
             Some(HeredocId {
-                token: token!(tDSTRING_BEG, loc!(0, 9)),
+                token: token!(tHEREDOC_BEG, loc!(0, 9)),
                 id: (4, 8),
                 interpolated: true,
-                squiggly: false
+                squiggly: false,
+                dash: true
             })
         );
     }
@@ -208,10 +235,11 @@ mod tests {
         assert_parses_heredoc_id!(
             b"<<-`HERE`",
             Some(HeredocId {
-                token: token!(tXSTRING_BEG, loc!(0, 9)),
+                token: token!(tXHEREDOC_BEG, loc!(0, 9)),
                 id: (4, 8),
                 interpolated: true,
-                squiggly: false
+                squiggly: false,
+                dash: true
             })
         );
     }
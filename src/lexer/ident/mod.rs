@@ -1,5 +1,6 @@
 use crate::{
     buffer::{utf8::Utf8Char, Buffer, BufferWithCursor},
+    error::{ParseError, ParseErrorKind},
     loc::loc,
     token::{token, Token},
 };
@@ -59,13 +60,16 @@ impl Ident {
         byte.is_ascii_alphanumeric() || byte == b'_' || !byte.is_ascii()
     }
 
-    pub(crate) fn parse(buffer: &mut BufferWithCursor) -> Token {
+    pub(crate) fn parse(buffer: &mut BufferWithCursor) -> Result<Token, ParseError> {
         let start = buffer.pos();
 
         let length = match Ident::lookahead(buffer.for_lookahead(), start) {
             Some(Ident { length }) => length,
             None => {
-                todo!("handle ident that start with non-UTF-8 byte")
+                return Err(ParseError::new(
+                    ParseErrorKind::InvalidMultibyteChar,
+                    loc!(start, start + 1),
+                ));
             }
         };
 
@@ -78,15 +82,15 @@ impl Ident {
 
                 // it still can be a special `defined?` keyword
                 if buffer.slice(start, buffer.pos()).unwrap() == b"defined?" {
-                    return token!(kDEFINED, loc!(start, buffer.pos()));
+                    return Ok(token!(kDEFINED, loc!(start, buffer.pos())));
                 }
 
-                return token!(tFID, loc!(start, buffer.pos()));
+                return Ok(token!(tFID, loc!(start, buffer.pos())));
             }
             Some(IdentSuffix { byte: b'=' }) => {
                 // `foo=` setter, consume `'='
                 buffer.skip_byte();
-                return token!(tIDENTIFIER, loc!(start, buffer.pos()));
+                return Ok(token!(tIDENTIFIER, loc!(start, buffer.pos())));
             }
             _ => {}
         }
@@ -114,7 +118,7 @@ impl Ident {
                 // FOO::BAR case, not a label
             } else {
                 buffer.skip_byte();
-                return token!(tLABEL, loc!(start, buffer.pos()));
+                return Ok(token!(tLABEL, loc!(start, buffer.pos())));
             }
         }
 
@@ -123,16 +127,16 @@ impl Ident {
 
         // there's a chance that it's a keyword
         if let Some(reserved_word) = find_reserved_word(slice) {
-            return token!(reserved_word.token_value, loc!(start, end));
+            return Ok(token!(reserved_word.token_value, loc!(start, end)));
         }
 
         // Can be a constant
         if const_like {
-            return token!(tCONSTANT, loc!(start, buffer.pos()));
+            return Ok(token!(tCONSTANT, loc!(start, buffer.pos())));
         }
 
         // otherwise it's just a plain identifier
-        token!(tIDENTIFIER, loc!(start, end))
+        Ok(token!(tIDENTIFIER, loc!(start, end)))
     }
 }
 
@@ -0,0 +1,420 @@
+use crate::{
+    error::ParseError,
+    lexer::{
+        strings::{literal::StringLiteral, types::Regexp as RegexpLiteral},
+        HeredocId, Lexer,
+    },
+    loc::loc,
+    token::{token, Token, TokenKind, TokenValue},
+};
+
+impl Lexer {
+    /// Constructs a standalone lexer that can be used as an iterator of tokens.
+    ///
+    /// By default whitespaces, newlines and comments are skipped,
+    /// call `.include_trivia()` to get them too.
+    pub fn from_bytes(input: &[u8]) -> Self {
+        Self::new(input)
+    }
+
+    /// Makes iterator emit `tWHITESPACE`, `tNL` and `tCOMMENT` tokens
+    pub fn include_trivia(mut self) -> Self {
+        self.include_trivia = true;
+        self
+    }
+
+    /// Returns an error that stopped lexing (if any)
+    pub fn error(&self) -> Option<&ParseError> {
+        self.error.as_ref()
+    }
+
+    /// Returns bytes of the token.
+    ///
+    /// For tokens with unescaped value (like string contents)
+    /// it's the value, for all other tokens it's their source code.
+    /// Returns `None` if the token doesn't belong to the source of this lexer.
+    pub fn token_bytes(&self, token: &Token) -> Option<Vec<u8>> {
+        match token.value {
+            // Lexer owns the buffer with unescaped sequences,
+            // so there's no need to follow the pointer that token has
+            Some(TokenValue::UnescapedChars { loc, .. }) => self
+                .buffer
+                .for_lookahead()
+                .unescaped_slice_at(loc.start, loc.end)
+                .map(|bytes| bytes.to_vec()),
+            Some(value) => Some(value.to_bytes()),
+            None => self
+                .buffer
+                .slice(token.loc.start, token.loc.end)
+                .map(|bytes| bytes.to_vec()),
+        }
+    }
+
+    // There's no parser that tells if a token begins a new expression, so like MRI
+    // it's a beginning of the expression after operators, keywords and delimiters
+    // and in a command argument (`foo <<~A`, `foo /re/`, `foo -x`),
+    // after other values it's a binary operator (`x <<A`, `a / b`, `foo - x`)
+    fn starts_expression(&self, start: usize) -> bool {
+        use TokenKind::*;
+
+        match self.prev_token_kind {
+            None => true,
+            Some(tIDENTIFIER | tFID | tCONSTANT) => {
+                let space_before = start
+                    .checked_sub(1)
+                    .and_then(|pos| self.buffer.byte_at(pos))
+                    .is_some_and(|byte| byte == b' ' || byte == b'\t');
+                let space_after = matches!(
+                    self.buffer.byte_at(start + 1),
+                    Some(b' ' | b'\t' | b'\n' | b'\r' | b'\x0b' | b'\x0c') | None
+                );
+                space_before && !space_after
+            }
+            // `def /(other)` and `foo.-@` take operators as method names
+            Some(kDEF | tDOT | tANDDOT) => false,
+            Some(kind) => !matches!(
+                kind,
+                tIVAR
+                    | tGVAR
+                    | tCVAR
+                    | tBACK_REF
+                    | tNTH_REF
+                    | tINTEGER
+                    | tFLOAT
+                    | tRATIONAL
+                    | tIMAGINARY
+                    | tCHAR
+                    | tSTRING_END
+                    | tRPAREN
+                    | tRBRACK
+                    | tRCURLY
+                    | kEND
+                    | kSELF
+                    | kNIL
+                    | kTRUE
+                    | kFALSE
+                    | k__FILE__
+                    | k__LINE__
+                    | k__ENCODING__
+            ),
+        }
+    }
+
+    // Lexer alone treats `%` and `?` followed by a non-whitespace as a literal start,
+    // but after a value they are binary operators (`10 %3`, `(a)?b:c`)
+    fn operator_after_value(&mut self) -> Option<Token> {
+        if self.error.is_some() {
+            return None;
+        }
+        if let Some(literal) = self.string_literals.last() {
+            if !literal.is_in_interpolation() {
+                return None;
+            }
+        }
+        let start = self.buffer.pos();
+        let kind = match (self.buffer.current_byte(), self.buffer.byte_at(start + 1)) {
+            (Some(b'%'), Some(b'=')) => return None,
+            (Some(b'%'), _) => TokenKind::tPERCENT,
+            (Some(b'?'), _) => TokenKind::tEH,
+            _ => return None,
+        };
+        if self.starts_expression(start) {
+            return None;
+        }
+        self.buffer.set_pos(start + 1);
+        self.token_seen = true;
+        Some(token!(kind, loc!(start, start + 1)))
+    }
+
+    // Re-lexes tokens that only parser can disambiguate using `starts_expression`
+    fn relex_at_expression_boundary(&mut self, token: Token) -> Token {
+        let start = token.loc.start;
+        match token.kind {
+            TokenKind::tLSHFT
+                if matches!(
+                    HeredocId::lookahead(self.buffer.for_lookahead(), start),
+                    Ok(Some(_))
+                ) && self.starts_expression(start) =>
+            {
+                // forget `<<` and lex it again as a heredoc start
+                self.buffer.set_pos(start);
+                self.require_new_expr();
+                self.next_token()
+            }
+            TokenKind::tDIVIDE if self.starts_expression(start) => {
+                self.string_literals
+                    .push(StringLiteral::Regexp(RegexpLiteral::new(
+                        b'/',
+                        b'/',
+                        self.curly_nest,
+                    )));
+                token!(tREGEXP_BEG, token.loc)
+            }
+            TokenKind::tPLUS if self.starts_expression(start) => token!(tUPLUS, token.loc),
+            TokenKind::tMINUS if self.starts_expression(start) => token!(tUMINUS, token.loc),
+            TokenKind::tUMINUS_NUM if !self.starts_expression(start) => token!(tMINUS, token.loc),
+            TokenKind::tINTEGER
+            | TokenKind::tFLOAT
+            | TokenKind::tRATIONAL
+            | TokenKind::tIMAGINARY
+                if self.buffer.byte_at(start) == Some(b'+') && !self.starts_expression(start) =>
+            {
+                // `a+1` is `a + 1`, the number is lexed again on the next call
+                self.buffer.set_pos(start + 1);
+                token!(tPLUS, loc!(start, start + 1))
+            }
+            _ => token,
+        }
+    }
+}
+
+impl Iterator for Lexer {
+    type Item = Token;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.reached_eof {
+            return None;
+        }
+
+        loop {
+            let token = match self.operator_after_value() {
+                Some(token) => token,
+                None => {
+                    let token = self.next_token();
+                    self.relex_at_expression_boundary(token)
+                }
+            };
+            if !token.is_one_of([TokenKind::tWHITESPACE, TokenKind::tCOMMENT]) {
+                self.prev_token_kind = Some(token.kind);
+            }
+
+            match token.kind {
+                TokenKind::tEOF => {
+                    self.reached_eof = true;
+                    return None;
+                }
+                TokenKind::tWHITESPACE | TokenKind::tNL | TokenKind::tCOMMENT
+                    if !self.include_trivia => {}
+                _ => return Some(token),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+fn kinds(lexer: Lexer) -> Vec<TokenKind> {
+    lexer.map(|token| token.kind).collect()
+}
+
+#[test]
+fn test_iter_skips_trivia() {
+    use TokenKind::*;
+    assert_eq!(
+        kinds(Lexer::from_bytes(b"foo = 1 # bar\nbaz")),
+        vec![tIDENTIFIER, tEQL, tINTEGER, tIDENTIFIER]
+    );
+}
+
+#[test]
+fn test_iter_includes_trivia() {
+    use TokenKind::*;
+    assert_eq!(
+        kinds(Lexer::from_bytes(b"foo = 1 # bar\nbaz").include_trivia()),
+        vec![
+            tIDENTIFIER,
+            tWHITESPACE,
+            tEQL,
+            tWHITESPACE,
+            tINTEGER,
+            tWHITESPACE,
+            tCOMMENT,
+            tNL,
+            tIDENTIFIER
+        ]
+    );
+}
+
+#[test]
+fn test_iter_stops_after_eof() {
+    let mut lexer = Lexer::from_bytes(b"42");
+    assert!(lexer.next().is_some());
+    assert!(lexer.next().is_none());
+    assert!(lexer.next().is_none());
+}
+
+#[test]
+fn test_token_bytes() {
+    let mut lexer = Lexer::from_bytes(b"foo \"a\\nb\"");
+    let tokens = lexer.by_ref().collect::<Vec<_>>();
+    let bytes = tokens
+        .iter()
+        .map(|token| lexer.token_bytes(token).unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        bytes,
+        vec![
+            b"foo".to_vec(),
+            b"\"".to_vec(),
+            b"a".to_vec(),
+            b"\n".to_vec(),
+            b"b".to_vec(),
+            b"\"".to_vec()
+        ]
+    );
+}
+
+#[test]
+fn test_iter_stops_on_error() {
+    let mut lexer = Lexer::from_bytes(b"x = %z(foo)");
+    assert_eq!(
+        lexer.next().map(|token| token.kind),
+        Some(TokenKind::tIDENTIFIER)
+    );
+    assert_eq!(lexer.next().map(|token| token.kind), Some(TokenKind::tEQL));
+    assert_eq!(lexer.next(), None);
+    assert!(lexer.error().is_some());
+}
+
+#[test]
+fn test_iter_heredoc() {
+    use TokenKind::*;
+    assert_eq!(
+        kinds(Lexer::from_bytes(b"foo(<<~A)\n  bar\nA\n")),
+        vec![
+            tIDENTIFIER,
+            tLPAREN,
+            tHEREDOC_BEG,
+            tSTRING_CONTENT,
+            tSTRING_END,
+            tRPAREN
+        ]
+    );
+    assert_eq!(
+        kinds(Lexer::from_bytes(b"foo <<A\nbar\nA\n")),
+        vec![tIDENTIFIER, tHEREDOC_BEG, tSTRING_CONTENT, tSTRING_END]
+    );
+    // binary operator after a value
+    assert_eq!(
+        kinds(Lexer::from_bytes(b"x = 1 <<A")),
+        vec![tIDENTIFIER, tEQL, tINTEGER, tLSHFT, tCONSTANT]
+    );
+    assert_eq!(
+        kinds(Lexer::from_bytes(b"foo<<A")),
+        vec![tIDENTIFIER, tLSHFT, tCONSTANT]
+    );
+}
+
+#[test]
+fn test_token_bytes_out_of_bounds() {
+    use crate::{loc::loc, token::token};
+    let lexer = Lexer::from_bytes(b"foo");
+    assert_eq!(lexer.token_bytes(&token!(tIDENTIFIER, loc!(1, 10))), None);
+}
+
+#[test]
+fn test_iter_regexp() {
+    use TokenKind::*;
+    assert_eq!(
+        kinds(Lexer::from_bytes(b"x = /\"/\ny = 1")),
+        vec![
+            tIDENTIFIER,
+            tEQL,
+            tREGEXP_BEG,
+            tSTRING_CONTENT,
+            tSTRING_END,
+            tIDENTIFIER,
+            tEQL,
+            tINTEGER
+        ]
+    );
+    assert_eq!(
+        kinds(Lexer::from_bytes(b"/#/")),
+        vec![tREGEXP_BEG, tSTRING_CONTENT, tSTRING_END]
+    );
+    assert_eq!(
+        kinds(Lexer::from_bytes(b"foo /re/")),
+        vec![tIDENTIFIER, tREGEXP_BEG, tSTRING_CONTENT, tSTRING_END]
+    );
+    // binary operator after a value
+    assert_eq!(
+        kinds(Lexer::from_bytes(b"a / b")),
+        vec![tIDENTIFIER, tDIVIDE, tIDENTIFIER]
+    );
+    assert_eq!(
+        kinds(Lexer::from_bytes(b"def /(other); end")),
+        vec![kDEF, tDIVIDE, tLPAREN, tIDENTIFIER, tRPAREN, tSEMI, kEND]
+    );
+}
+
+#[test]
+fn test_iter_operators_after_value() {
+    use TokenKind::*;
+    assert_eq!(
+        kinds(Lexer::from_bytes(b"10 %3")),
+        vec![tINTEGER, tPERCENT, tINTEGER]
+    );
+    assert_eq!(
+        kinds(Lexer::from_bytes(b"(a)?b : c")),
+        vec![
+            tLPAREN,
+            tIDENTIFIER,
+            tRPAREN,
+            tEH,
+            tIDENTIFIER,
+            tCOLON,
+            tIDENTIFIER
+        ]
+    );
+    assert_eq!(
+        kinds(Lexer::from_bytes(b"\"#{i%2}\"")),
+        vec![
+            tDSTRING_BEG,
+            tSTRING_DBEG,
+            tIDENTIFIER,
+            tPERCENT,
+            tINTEGER,
+            tSTRING_DEND,
+            tSTRING_END
+        ]
+    );
+    assert_eq!(
+        kinds(Lexer::from_bytes(b"a-1+2")),
+        vec![tIDENTIFIER, tMINUS, tINTEGER, tPLUS, tINTEGER]
+    );
+}
+
+#[test]
+fn test_iter_unary_operators() {
+    use TokenKind::*;
+    assert_eq!(
+        kinds(Lexer::from_bytes(b"x = -y")),
+        vec![tIDENTIFIER, tEQL, tUMINUS, tIDENTIFIER]
+    );
+    assert_eq!(
+        kinds(Lexer::from_bytes(b"foo +x")),
+        vec![tIDENTIFIER, tUPLUS, tIDENTIFIER]
+    );
+    assert_eq!(
+        kinds(Lexer::from_bytes(b"foo - x")),
+        vec![tIDENTIFIER, tMINUS, tIDENTIFIER]
+    );
+}
+
+#[test]
+fn test_iter_unterminated_string() {
+    use crate::{error::ParseErrorKind, loc::loc};
+
+    let mut lexer = Lexer::from_bytes(b"x = \"abc");
+    assert_eq!(
+        lexer.by_ref().map(|token| token.kind).collect::<Vec<_>>(),
+        vec![
+            TokenKind::tIDENTIFIER,
+            TokenKind::tEQL,
+            TokenKind::tDSTRING_BEG,
+            TokenKind::tSTRING_CONTENT
+        ]
+    );
+    let error = lexer.error().unwrap();
+    assert_eq!(error.kind, ParseErrorKind::UnterminatedString);
+    assert_eq!(error.loc, loc!(8, 8));
+}
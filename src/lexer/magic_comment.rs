@@ -0,0 +1,222 @@
+use crate::{
+    lexer::Lexer,
+    loc::loc,
+    magic_comment::{MagicComment, MagicCommentKind},
+    Loc,
+};
+
+impl Lexer {
+    // Records magic comments from the comment that spans `start..end`.
+    //
+    // Both `# key: value` and emacs-style `# -*- key: value; key: value -*-`
+    // forms are supported, keys are case-insensitive and `-` is the same as `_`.
+    pub(crate) fn parse_magic_comment(&mut self, start: usize, end: usize) {
+        let (from, to) = emacs_markers(self.buffer.for_lookahead().bytes(), start, end)
+            .unwrap_or((start + 1, end));
+
+        let mut pos = from;
+        while let Some((key_l, value_l, next_pos)) = self.parse_magic_comment_pair(pos, to) {
+            if let Some(kind) = self.magic_comment_kind(key_l) {
+                self.magic_comments.push(MagicComment {
+                    kind,
+                    key_l,
+                    value_l,
+                });
+            }
+            pos = next_pos;
+        }
+    }
+
+    // Parses `key: value` that starts at `pos`,
+    // returns locations of the key and the value + position of the next pair
+    fn parse_magic_comment_pair(&self, pos: usize, end: usize) -> Option<(Loc, Loc, usize)> {
+        let bytes = self.buffer.for_lookahead().bytes();
+        let at = |pos: usize| {
+            if pos < end {
+                bytes.get(pos).copied()
+            } else {
+                None
+            }
+        };
+        let skip_ws = |mut pos: usize| {
+            while matches!(at(pos), Some(b' ' | b'\t')) {
+                pos += 1;
+            }
+            pos
+        };
+
+        let key_start = skip_ws(pos);
+        let mut key_end = key_start;
+        while matches!(
+            at(key_end),
+            Some(b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'_' | b'-')
+        ) {
+            key_end += 1;
+        }
+        if key_start == key_end {
+            return None;
+        }
+
+        let colon = skip_ws(key_end);
+        if at(colon) != Some(b':') {
+            return None;
+        }
+
+        let value_start = skip_ws(colon + 1);
+        let mut value_end = value_start;
+        while matches!(at(value_end), Some(byte) if !byte.is_ascii_whitespace() && byte != b';') {
+            value_end += 1;
+        }
+        if value_start == value_end {
+            return None;
+        }
+
+        let mut next_pos = skip_ws(value_end);
+        match at(next_pos) {
+            Some(b';') => next_pos += 1,
+            // `# key: value` form has only one pair
+            _ => next_pos = end,
+        }
+
+        Some((
+            loc!(key_start, key_end),
+            loc!(value_start, value_end),
+            next_pos,
+        ))
+    }
+
+    fn magic_comment_kind(&self, key_l: Loc) -> Option<MagicCommentKind> {
+        let key = self
+            .buffer
+            .slice(key_l.start, key_l.end)?
+            .iter()
+            .map(|byte| match byte {
+                b'-' => b'_',
+                _ => byte.to_ascii_lowercase(),
+            })
+            .collect::<Vec<_>>();
+
+        match &key[..] {
+            // encoding can be set only on the first line
+            // (or on the second one if the first one is a shebang)
+            b"coding" | b"encoding" if self.line_of(key_l.start) < 2 => {
+                Some(MagicCommentKind::Encoding)
+            }
+            // MRI ignores it after any tokens
+            b"frozen_string_literal" if !self.token_seen => {
+                Some(MagicCommentKind::FrozenStringLiteral)
+            }
+            b"warn_indent" => Some(MagicCommentKind::WarnIndent),
+            b"shareable_constant_value" => Some(MagicCommentKind::ShareableConstantValue),
+            _ => None,
+        }
+    }
+
+    // Returns 0-based line number of the given position
+    fn line_of(&self, pos: usize) -> usize {
+        self.buffer.for_lookahead().bytes()[..pos]
+            .iter()
+            .filter(|byte| **byte == b'\n')
+            .count()
+    }
+}
+
+// Returns contents of the `-*- ... -*-` section if there's one
+fn emacs_markers(bytes: &[u8], start: usize, end: usize) -> Option<(usize, usize)> {
+    const MARKER: &[u8] = b"-*-";
+
+    let find = |from: usize| {
+        bytes[from..end]
+            .windows(MARKER.len())
+            .position(|window| window == MARKER)
+            .map(|idx| from + idx)
+    };
+
+    let open = find(start)?;
+    let close = find(open + MARKER.len())?;
+    Some((open + MARKER.len(), close))
+}
+
+#[cfg(test)]
+fn magic_comments(input: &[u8]) -> Vec<(MagicCommentKind, String, String)> {
+    let mut lexer = Lexer::new(input);
+    lexer.tokenize_until_eof();
+
+    lexer
+        .magic_comments
+        .iter()
+        .map(|magic_comment| {
+            let source = |loc: Loc| String::from_utf8_lossy(&input[loc.to_range()]).into_owned();
+            (
+                magic_comment.kind,
+                source(magic_comment.key_l),
+                source(magic_comment.value_l),
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn test_magic_comments() {
+    assert_eq!(
+        magic_comments(
+            b"# encoding: utf-8\n# frozen_string_literal: true\n# warn_indent: false\n# shareable_constant_value: literal\n"
+        ),
+        vec![
+            (MagicCommentKind::Encoding, "encoding".to_string(), "utf-8".to_string()),
+            (
+                MagicCommentKind::FrozenStringLiteral,
+                "frozen_string_literal".to_string(),
+                "true".to_string()
+            ),
+            (MagicCommentKind::WarnIndent, "warn_indent".to_string(), "false".to_string()),
+            (
+                MagicCommentKind::ShareableConstantValue,
+                "shareable_constant_value".to_string(),
+                "literal".to_string()
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_magic_comment_emacs_style() {
+    assert_eq!(
+        magic_comments(
+            b"#!/usr/bin/env ruby\n# -*- coding: binary; Frozen-String-Literal: false -*-\n"
+        ),
+        vec![
+            (
+                MagicCommentKind::Encoding,
+                "coding".to_string(),
+                "binary".to_string()
+            ),
+            (
+                MagicCommentKind::FrozenStringLiteral,
+                "Frozen-String-Literal".to_string(),
+                "false".to_string()
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_encoding_after_second_line() {
+    assert_eq!(magic_comments(b"\n\n# encoding: utf-8\n"), vec![]);
+}
+
+#[test]
+fn test_frozen_string_literal_after_tokens() {
+    assert_eq!(
+        magic_comments(b"foo\n# frozen_string_literal: true\n"),
+        vec![]
+    );
+}
+
+#[test]
+fn test_regular_comment() {
+    assert_eq!(
+        magic_comments(b"# just a comment: with colon and words\n"),
+        vec![]
+    );
+}
@@ -3,6 +3,8 @@ pub(crate) mod gvar;
 pub(crate) mod handle_eof;
 pub(crate) mod heredoc_id;
 pub(crate) mod ident;
+pub(crate) mod iter;
+pub(crate) mod magic_comment;
 pub(crate) mod numbers;
 pub(crate) mod percent;
 pub(crate) mod punctuation;
@@ -12,20 +14,33 @@ pub(crate) mod strings;
 
 use atmark::AtMark;
 use gvar::Gvar;
+use heredoc_id::HeredocId;
 use ident::Ident;
 use numbers::parse_number;
 use percent::parse_percent;
 use strings::parse_string;
 
 use crate::{
-    buffer::BufferWithCursor, lexer::strings::stack::StringLiteralStack, loc::loc, token::token,
-    Token, TokenKind,
+    buffer::BufferWithCursor,
+    comment::Comment,
+    diagnostic::Diagnostics,
+    error::{ParseError, ParseErrorKind},
+    lexer::strings::stack::StringLiteralStack,
+    loc::loc,
+    magic_comment::MagicComment,
+    token::token,
+    Loc, Token, TokenKind,
 };
 use strings::{action::StringExtendAction, literal::StringLiteral};
 
 #[derive(Debug)]
 pub struct Lexer {
     debug: bool,
+    include_trivia: bool,
+    reached_eof: bool,
+    // Last token emitted by the iterator (except whitespaces and comments),
+    // used to tell heredocs from `<<` operators without a parser
+    prev_token_kind: Option<TokenKind>,
 
     pub(crate) buffer: BufferWithCursor,
     pub(crate) required_new_expr: bool,
@@ -41,12 +56,31 @@ pub struct Lexer {
 
     pub(crate) seen_whitespace: bool,
     pub(crate) seen_nl: bool,
+
+    // Position of the line that follows the last terminated heredoc,
+    // lexer jumps there once it reaches the end of the current line
+    pub(crate) heredoc_end: Option<usize>,
+
+    // Location of the data after `__END__` line
+    pub(crate) data_section_l: Option<Loc>,
+
+    pub(crate) comments: Vec<Comment>,
+    pub(crate) magic_comments: Vec<MagicComment>,
+    // Set once the first non-trivia token is emitted,
+    // some magic comments are ignored after that
+    pub(crate) token_seen: bool,
+
+    pub(crate) error: Option<ParseError>,
+    pub(crate) diagnostics: Diagnostics,
 }
 
 impl Lexer {
     pub(crate) fn new(input: &[u8]) -> Self {
         Self {
             debug: false,
+            include_trivia: false,
+            reached_eof: false,
+            prev_token_kind: None,
 
             buffer: BufferWithCursor::new(input),
             required_new_expr: false,
@@ -62,6 +96,17 @@ impl Lexer {
 
             seen_whitespace: false,
             seen_nl: false,
+
+            heredoc_end: None,
+
+            data_section_l: None,
+
+            comments: vec![],
+            magic_comments: vec![],
+            token_seen: false,
+
+            error: None,
+            diagnostics: Diagnostics::new(),
         }
     }
 
@@ -91,6 +136,11 @@ impl Lexer {
                         self.seen_nl = true;
                         self.token_idx += 1;
                     }
+                    TokenKind::tCOMMENT => {
+                        let comment = Comment::new(token.loc, self.buffer.for_lookahead());
+                        self.comments.push(comment);
+                        self.token_idx += 1;
+                    }
                     _ => {
                         return token;
                     }
@@ -100,6 +150,12 @@ impl Lexer {
     }
 
     fn next_token(&mut self) -> Token {
+        if let Some(error) = self.error.as_ref() {
+            // lexing has been stopped by an error,
+            // so the only thing we can emit is EOF
+            return token!(tEOF, loc!(error.loc.start, error.loc.start));
+        }
+
         let token = if self.string_literals.last().is_some() {
             self.tokenize_while_in_string()
         } else {
@@ -112,6 +168,15 @@ impl Lexer {
         // Reset one-time flag
         self.required_new_expr = false;
 
+        if !token.is_one_of([
+            TokenKind::tWHITESPACE,
+            TokenKind::tNL,
+            TokenKind::tCOMMENT,
+            TokenKind::tEOF,
+        ]) {
+            self.token_seen = true;
+        }
+
         token
     }
 
@@ -123,6 +188,150 @@ impl Lexer {
         self.buffer.current_byte() == Some(b'(')
     }
 
+    pub(crate) fn lookahead_is_rparen(&self) -> bool {
+        self.buffer.current_byte() == Some(b')')
+    }
+
+    pub(crate) fn lookahead_is_whitespace(&self) -> bool {
+        matches!(
+            self.buffer.current_byte(),
+            Some(b' ' | b'\t' | b'\n' | b'\r' | b'\x0b' | b'\x0c') | None
+        )
+    }
+
+    // Returns true if there's a whitespace or a newline between the previous token and the current one,
+    // i.e. `foo[1]` is an index, but `foo [1]` is a method call with an array argument
+    pub(crate) fn space_before_current_token(&mut self) -> bool {
+        self.current_token();
+        self.seen_whitespace || self.seen_nl
+    }
+
+    // Newlines and comments are skipped by `current_token`, but sometimes parser needs to know
+    // if there was a newline, i.e. binary operators can't start a new line.
+    // Returns the first newline between the previous token and the current one.
+    pub(crate) fn newline_before_current_token(&mut self) -> Option<Token> {
+        self.current_token();
+        self.tokens[..self.token_idx]
+            .iter()
+            .rev()
+            .take_while(|token| {
+                token.is_one_of([TokenKind::tWHITESPACE, TokenKind::tNL, TokenKind::tCOMMENT])
+            })
+            .filter(|token| token.is(TokenKind::tNL))
+            .last()
+            .copied()
+    }
+
+    // `<<` is lexed as tLSHFT unless lexer has been told that a new expression begins,
+    // but only parser knows if it's a binary operator or a heredoc start.
+    // Returns kind of the heredoc start token if current `<<` can be re-lexed as a heredoc.
+    pub(crate) fn lookahead_heredoc_id(&mut self) -> Option<TokenKind> {
+        let lshft_t = self.current_token();
+        if !lshft_t.is(TokenKind::tLSHFT) {
+            return None;
+        }
+        match HeredocId::lookahead(self.buffer.for_lookahead(), lshft_t.loc.start) {
+            Ok(Some(HeredocId { token, .. })) => Some(token.kind),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn relex_as_heredoc(&mut self) {
+        let lshft_t = self.current_token();
+        debug_assert!(lshft_t.is(TokenKind::tLSHFT));
+
+        // forget `<<` and lex it again, this time as a heredoc start
+        self.tokens.truncate(self.token_idx);
+        self.buffer.set_pos(lshft_t.loc.start);
+        self.require_new_expr();
+    }
+
+    // `+1` is lexed as a single number token, but after a value it's a binary `+`:
+    // replaces the current token with `+` followed by the unsigned number
+    pub(crate) fn split_signed_number(&mut self) {
+        let number_t = self.current_token();
+        debug_assert_eq!(self.buffer.byte_at(number_t.loc.start), Some(b'+'));
+
+        let start = number_t.loc.start;
+        self.tokens[self.token_idx] = token!(tPLUS, loc!(start, start + 1));
+        self.tokens.insert(
+            self.token_idx + 1,
+            Token {
+                loc: loc!(start + 1, number_t.loc.end),
+                ..number_t
+            },
+        );
+    }
+
+    // `+@`, `-@`, `[]` and `[]=` are lexed as multiple tokens, but when parser expects a method name
+    // (after `def`, `alias` or `undef`) it's a single operator:
+    // replaces the current token with the operator that also takes the following bytes
+    pub(crate) fn relex_as_operator_method_name(&mut self) {
+        let token = self.current_token();
+        if self.token_idx + 1 != self.tokens.len() {
+            // the following bytes have already been lexed
+            return;
+        }
+
+        let end = token.loc.end;
+        // `!@` and `~@` are the same as `!` and `~`, `@` is only skipped
+        let (kind, len, skip) = match (
+            token.kind,
+            self.buffer.byte_at(end),
+            self.buffer.byte_at(end + 1),
+        ) {
+            (TokenKind::tPLUS, Some(b'@'), _) => (TokenKind::tUPLUS, 1, 0),
+            (TokenKind::tMINUS, Some(b'@'), _) => (TokenKind::tUMINUS, 1, 0),
+            (TokenKind::tBANG, Some(b'@'), _) => (TokenKind::tBANG, 0, 1),
+            (TokenKind::tTILDE, Some(b'@'), _) => (TokenKind::tTILDE, 0, 1),
+            (TokenKind::tLBRACK, Some(b']'), Some(b'=')) => (TokenKind::tASET, 2, 0),
+            (TokenKind::tLBRACK, Some(b']'), _) => (TokenKind::tAREF, 1, 0),
+            _ => return,
+        };
+        if token.is(TokenKind::tLBRACK) {
+            // `[` has been counted as an opening bracket
+            self.brack_nest -= 1;
+        }
+
+        self.tokens[self.token_idx] = token!(kind, loc!(token.loc.start, end + len));
+        self.buffer.set_pos(end + len + skip);
+    }
+
+    // Returns position where the body of the heredoc that starts on the current line begins
+    pub(crate) fn heredoc_body_starts_at(&self) -> usize {
+        if let Some(heredoc_end) = self.heredoc_end {
+            // there's another heredoc on this line,
+            // so this one starts right after it
+            return heredoc_end;
+        }
+
+        let mut pos = self.buffer.pos();
+        loop {
+            match self.buffer.byte_at(pos) {
+                Some(b'\n') => return pos + 1,
+                None => return pos,
+                _ => pos += 1,
+            }
+        }
+    }
+
+    /// Records the error (only the first one is kept)
+    /// and emits EOF, so that parsing stops as soon as possible
+    pub(crate) fn stop_with_error(&mut self, error: ParseError) -> Token {
+        let at = error.loc.start;
+        if self.error.is_none() {
+            self.error = Some(error);
+        }
+        token!(tEOF, loc!(at, at))
+    }
+
+    pub(crate) fn unwrap_or_stop(&mut self, result: Result<Token, ParseError>) -> Token {
+        match result {
+            Ok(token) => token,
+            Err(error) => self.stop_with_error(error),
+        }
+    }
+
     #[cfg(test)]
     pub(crate) fn tokenize_until_eof(&mut self) -> Vec<Token> {
         let mut tokens = vec![];
@@ -137,7 +346,6 @@ impl Lexer {
         tokens
     }
 
-    #[allow(dead_code)]
     pub(crate) fn require_new_expr(&mut self) {
         self.required_new_expr = true;
     }
@@ -155,12 +363,32 @@ impl Lexer {
                 // just emit what literal gives us
                 token
             }
+            StringExtendAction::EmitTokenWithError {
+                token,
+                message,
+                loc,
+            } => {
+                self.diagnostics.error(message, loc);
+                token
+            }
             StringExtendAction::FoundStringEnd { token } => {
                 // close current literal
                 self.string_literals.pop();
                 // and dispatch string end token
                 token
             }
+            StringExtendAction::FoundHeredocEnd {
+                token,
+                heredoc_ended_at,
+            } => {
+                // close current heredoc
+                self.string_literals.pop();
+                // buffer is already at the end of the heredoc ID,
+                // rest of the line will be lexed normally and after that
+                // we should continue right after the heredoc body
+                self.heredoc_end = Some(heredoc_ended_at);
+                token
+            }
             StringExtendAction::FoundInterpolation { token } => {
                 // dispatch dynamic string begin token
                 token
@@ -173,8 +401,11 @@ impl Lexer {
             StringExtendAction::EmitEOF { at: eof_pos } => {
                 // close current literal
                 self.string_literals.pop();
-                // and emit EOF
-                token!(tEOF, loc!(eof_pos, eof_pos))
+                // and stop with an error, there's no closing delimiter
+                self.stop_with_error(ParseError::new(
+                    ParseErrorKind::UnterminatedString,
+                    loc!(eof_pos, eof_pos),
+                ))
             }
         }
     }
@@ -207,6 +438,10 @@ impl Lexer {
             b'\n' => {
                 let token = token!(tNL, loc!(self.buffer.pos(), self.buffer.pos() + 1));
                 self.buffer.skip_byte();
+                if let Some(heredoc_end) = self.heredoc_end.take() {
+                    // skip heredoc bodies that have been already consumed
+                    self.buffer.set_pos(heredoc_end);
+                }
                 token
             }
             b'*' => OnByte::<b'*'>::on_byte(self),
@@ -225,7 +460,8 @@ impl Lexer {
             b'.' => OnByte::<b'.'>::on_byte(self),
             b'0'..=b'9' => {
                 self.buffer.set_pos(start);
-                parse_number(&mut self.buffer)
+                let result = parse_number(&mut self.buffer);
+                self.unwrap_or_stop(result)
             }
 
             b')' => OnByte::<b')'>::on_byte(self),
@@ -245,11 +481,15 @@ impl Lexer {
             b'\\' => OnByte::<b'\\'>::on_byte(self),
             b'%' => {
                 self.buffer.set_pos(start);
-                let (literal, token) = parse_percent(&mut self.buffer, self.curly_nest);
-                if let Some(literal) = literal {
-                    self.string_literals.push(literal);
+                match parse_percent(&mut self.buffer, self.curly_nest) {
+                    Ok((literal, token)) => {
+                        if let Some(literal) = literal {
+                            self.string_literals.push(literal);
+                        }
+                        token
+                    }
+                    Err(error) => self.stop_with_error(error),
                 }
-                token
             }
             b'$' => {
                 self.buffer.set_pos(start);
@@ -263,7 +503,8 @@ impl Lexer {
 
             _ident_start => {
                 self.buffer.set_pos(start);
-                Ident::parse(&mut self.buffer)
+                let result = Ident::parse(&mut self.buffer);
+                self.unwrap_or_stop(result)
             }
         }
     }
@@ -2,6 +2,7 @@ use std::ops::ControlFlow;
 
 use crate::{
     buffer::{Buffer, BufferWithCursor},
+    error::ParseError,
     loc::loc,
     token::{token, Token, TokenKind},
 };
@@ -30,11 +31,17 @@ impl Number {
 }
 
 trait ExtendNumber {
-    fn extend(number: &mut Number, buffer: &mut BufferWithCursor) -> ControlFlow<()>;
+    fn extend(
+        number: &mut Number,
+        buffer: &mut BufferWithCursor,
+    ) -> ControlFlow<Result<(), ParseError>>;
 }
 
 impl ExtendNumber for Number {
-    fn extend(number: &mut Number, buffer: &mut BufferWithCursor) -> ControlFlow<()> {
+    fn extend(
+        number: &mut Number,
+        buffer: &mut BufferWithCursor,
+    ) -> ControlFlow<Result<(), ParseError>> {
         match number.state {
             State::Uninitialized(_) => Uninitialized::extend(number, buffer),
             State::IntegerPrefix(_) => IntegerPrefix::extend(number, buffer),
@@ -65,18 +72,17 @@ impl Into<Token> for Number {
     }
 }
 
-pub(crate) fn parse_number(buffer: &mut BufferWithCursor) -> Token {
+pub(crate) fn parse_number(buffer: &mut BufferWithCursor) -> Result<Token, ParseError> {
     let mut number = Number::new(buffer.pos());
 
     loop {
-        let action = Number::extend(&mut number, buffer);
-
-        if action == ControlFlow::Break(()) {
+        if let ControlFlow::Break(result) = Number::extend(&mut number, buffer) {
+            result?;
             break;
         }
     }
 
-    number.into()
+    Ok(number.into())
 }
 
 #[cfg(test)]
@@ -2,6 +2,7 @@ use std::ops::ControlFlow;
 
 use crate::{
     buffer::BufferWithCursor,
+    error::ParseError,
     lexer::numbers::{
         state::{try_sub_parser, Imaginary, Rational, State},
         try_to_extend_with, ExtendNumber, Number,
@@ -16,7 +17,10 @@ pub(crate) enum Float {
 }
 
 impl ExtendNumber for Float {
-    fn extend(number: &mut Number, buffer: &mut BufferWithCursor) -> ControlFlow<()> {
+    fn extend(
+        number: &mut Number,
+        buffer: &mut BufferWithCursor,
+    ) -> ControlFlow<Result<(), ParseError>> {
         if let State::Float(float) = number.state {
             match float {
                 Float::WithDotNumber(_) => FloatWithDotNumber::extend(number, buffer),
@@ -38,7 +42,10 @@ impl Into<TokenKind> for Float {
 pub(crate) struct FloatWithDotNumber;
 
 impl ExtendNumber for FloatWithDotNumber {
-    fn extend(number: &mut Number, buffer: &mut BufferWithCursor) -> ControlFlow<()> {
+    fn extend(
+        number: &mut Number,
+        buffer: &mut BufferWithCursor,
+    ) -> ControlFlow<Result<(), ParseError>> {
         let start = buffer.pos();
 
         if try_sub_parser!(try_to_extend_with::e_suffix, buffer, start, number) {
@@ -56,7 +63,7 @@ impl ExtendNumber for FloatWithDotNumber {
             return ControlFlow::Continue(());
         }
 
-        ControlFlow::Break(())
+        ControlFlow::Break(Ok(()))
     }
 }
 
@@ -64,7 +71,10 @@ impl ExtendNumber for FloatWithDotNumber {
 pub(crate) struct FloatWithESuffix;
 
 impl ExtendNumber for FloatWithESuffix {
-    fn extend(number: &mut Number, buffer: &mut BufferWithCursor) -> ControlFlow<()> {
+    fn extend(
+        number: &mut Number,
+        buffer: &mut BufferWithCursor,
+    ) -> ControlFlow<Result<(), ParseError>> {
         let start = buffer.pos();
 
         if try_sub_parser!(try_to_extend_with::r_suffix, buffer, start, number) {
@@ -77,6 +87,6 @@ impl ExtendNumber for FloatWithESuffix {
             return ControlFlow::Continue(());
         }
 
-        ControlFlow::Break(())
+        ControlFlow::Break(Ok(()))
     }
 }
@@ -2,6 +2,7 @@ use std::ops::ControlFlow;
 
 use crate::{
     buffer::BufferWithCursor,
+    error::ParseError,
     lexer::numbers::{ExtendNumber, Number},
     token::TokenKind,
 };
@@ -10,9 +11,12 @@ use crate::{
 pub(crate) struct Imaginary;
 
 impl ExtendNumber for Imaginary {
-    fn extend(_number: &mut Number, _buffer: &mut BufferWithCursor) -> ControlFlow<()> {
+    fn extend(
+        _number: &mut Number,
+        _buffer: &mut BufferWithCursor,
+    ) -> ControlFlow<Result<(), ParseError>> {
         // Imaginary numbers can't be extended to anything bigger
-        ControlFlow::Break(())
+        ControlFlow::Break(Ok(()))
     }
 }
 
@@ -2,6 +2,7 @@ use std::ops::ControlFlow;
 
 use crate::{
     buffer::BufferWithCursor,
+    error::ParseError,
     lexer::numbers::{
         state::{float::*, try_sub_parser, Float, Imaginary, Rational, State},
         try_to_extend_with, ExtendNumber, Number,
@@ -13,7 +14,10 @@ use crate::{
 pub(crate) struct Integer;
 
 impl ExtendNumber for Integer {
-    fn extend(number: &mut Number, buffer: &mut BufferWithCursor) -> ControlFlow<()> {
+    fn extend(
+        number: &mut Number,
+        buffer: &mut BufferWithCursor,
+    ) -> ControlFlow<Result<(), ParseError>> {
         let start = buffer.pos();
 
         if try_sub_parser!(try_to_extend_with::dot_number_suffix, buffer, start, number) {
@@ -36,7 +40,7 @@ impl ExtendNumber for Integer {
             return ControlFlow::Continue(());
         }
 
-        ControlFlow::Break(())
+        ControlFlow::Break(Ok(()))
     }
 }
 
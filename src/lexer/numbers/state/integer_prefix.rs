@@ -2,11 +2,13 @@ use std::ops::ControlFlow;
 
 use crate::{
     buffer::BufferWithCursor,
+    error::{ParseError, ParseErrorKind},
     lexer::numbers::{
         scan,
         state::{try_sub_parser, Integer, State},
         ExtendNumber, Number,
     },
+    loc::loc,
     token::TokenKind,
 };
 
@@ -19,7 +21,10 @@ pub(crate) enum IntegerPrefix {
 }
 
 impl ExtendNumber for IntegerPrefix {
-    fn extend(number: &mut Number, buffer: &mut BufferWithCursor) -> ControlFlow<()> {
+    fn extend(
+        number: &mut Number,
+        buffer: &mut BufferWithCursor,
+    ) -> ControlFlow<Result<(), ParseError>> {
         if let State::IntegerPrefix(prefix) = number.state {
             match prefix {
                 IntegerPrefix::Hexadecimal(_) => Hexadecimal::extend(number, buffer),
@@ -44,14 +49,20 @@ pub(crate) struct Hexadecimal;
 
 // Runs after consuming `0x` hexadecimal prefix
 impl ExtendNumber for Hexadecimal {
-    fn extend(number: &mut Number, buffer: &mut BufferWithCursor) -> ControlFlow<()> {
+    fn extend(
+        number: &mut Number,
+        buffer: &mut BufferWithCursor,
+    ) -> ControlFlow<Result<(), ParseError>> {
         let start = buffer.pos();
 
         if try_sub_parser!(scan::hexadecimal, buffer, start, number) {
             number.state = State::Integer(Integer);
             ControlFlow::Continue(())
         } else {
-            panic!("numeric literal without digits")
+            ControlFlow::Break(Err(ParseError::new(
+                ParseErrorKind::NumericLiteralWithoutDigits,
+                loc!(number.begin, number.end),
+            )))
         }
     }
 }
@@ -61,14 +72,20 @@ pub(crate) struct Binary;
 
 // Runs after consuming `0b` binary prefix
 impl ExtendNumber for Binary {
-    fn extend(number: &mut Number, buffer: &mut BufferWithCursor) -> ControlFlow<()> {
+    fn extend(
+        number: &mut Number,
+        buffer: &mut BufferWithCursor,
+    ) -> ControlFlow<Result<(), ParseError>> {
         let start = buffer.pos();
 
         if try_sub_parser!(scan::binary, buffer, start, number) {
             number.state = State::Integer(Integer);
             ControlFlow::Continue(())
         } else {
-            panic!("numeric literal without digits")
+            ControlFlow::Break(Err(ParseError::new(
+                ParseErrorKind::NumericLiteralWithoutDigits,
+                loc!(number.begin, number.end),
+            )))
         }
     }
 }
@@ -78,14 +95,20 @@ pub(crate) struct Octal;
 
 // Runs after consuming octal prefix (`0`)
 impl ExtendNumber for Octal {
-    fn extend(number: &mut Number, buffer: &mut BufferWithCursor) -> ControlFlow<()> {
+    fn extend(
+        number: &mut Number,
+        buffer: &mut BufferWithCursor,
+    ) -> ControlFlow<Result<(), ParseError>> {
         let start = buffer.pos();
 
         if try_sub_parser!(scan::octal, buffer, start, number) {
             number.state = State::Integer(Integer);
             ControlFlow::Continue(())
         } else {
-            panic!("numeric literal without digits")
+            ControlFlow::Break(Err(ParseError::new(
+                ParseErrorKind::NumericLiteralWithoutDigits,
+                loc!(number.begin, number.end),
+            )))
         }
     }
 }
@@ -95,14 +118,20 @@ pub(crate) struct Decimal;
 
 // Runs after consuming decimal prefix (`0d` or no prefix)
 impl ExtendNumber for Decimal {
-    fn extend(number: &mut Number, buffer: &mut BufferWithCursor) -> ControlFlow<()> {
+    fn extend(
+        number: &mut Number,
+        buffer: &mut BufferWithCursor,
+    ) -> ControlFlow<Result<(), ParseError>> {
         let start = buffer.pos();
 
         if try_sub_parser!(scan::decimal, buffer, start, number) {
             number.state = State::Integer(Integer);
-            return ControlFlow::Continue(());
+            ControlFlow::Continue(())
         } else {
-            panic!("numeric literal without digits")
+            ControlFlow::Break(Err(ParseError::new(
+                ParseErrorKind::NumericLiteralWithoutDigits,
+                loc!(number.begin, number.end),
+            )))
         }
     }
 }
@@ -2,6 +2,7 @@ use std::ops::ControlFlow;
 
 use crate::{
     buffer::BufferWithCursor,
+    error::ParseError,
     lexer::numbers::{
         state::{try_sub_parser, Imaginary, State},
         try_to_extend_with, ExtendNumber, Number,
@@ -13,7 +14,10 @@ use crate::{
 pub(crate) struct Rational;
 
 impl ExtendNumber for Rational {
-    fn extend(number: &mut Number, buffer: &mut BufferWithCursor) -> ControlFlow<()> {
+    fn extend(
+        number: &mut Number,
+        buffer: &mut BufferWithCursor,
+    ) -> ControlFlow<Result<(), ParseError>> {
         let start = buffer.pos();
 
         if try_sub_parser!(try_to_extend_with::i_suffix, buffer, start, number) {
@@ -21,7 +25,7 @@ impl ExtendNumber for Rational {
             return ControlFlow::Continue(());
         }
 
-        ControlFlow::Break(())
+        ControlFlow::Break(Ok(()))
     }
 }
 
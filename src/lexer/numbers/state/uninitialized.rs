@@ -2,6 +2,7 @@ use std::ops::ControlFlow;
 
 use crate::{
     buffer::{scan_while_matches_pattern, BufferWithCursor, LookaheadResult},
+    error::ParseError,
     lexer::numbers::{
         state::{integer_prefix::*, Integer, IntegerPrefix, State},
         ExtendNumber, Number,
@@ -13,7 +14,10 @@ use crate::{
 pub(crate) struct Uninitialized;
 
 impl ExtendNumber for Uninitialized {
-    fn extend(number: &mut Number, buffer: &mut BufferWithCursor) -> ControlFlow<()> {
+    fn extend(
+        number: &mut Number,
+        buffer: &mut BufferWithCursor,
+    ) -> ControlFlow<Result<(), ParseError>> {
         let start = buffer.pos();
 
         let byte = buffer.current_byte().unwrap();
@@ -61,13 +65,13 @@ impl ExtendNumber for Uninitialized {
                     number.end = end;
                     buffer.set_pos(end);
                     number.state = State::Integer(Integer);
-                    return ControlFlow::Break(());
+                    return ControlFlow::Break(Ok(()));
                 }
 
                 _other => {
                     // Sole "0" digit
                     number.state = State::Integer(Integer);
-                    return ControlFlow::Break(());
+                    return ControlFlow::Break(Ok(()));
                 }
             }
         }
@@ -199,3 +199,17 @@ mod rational_and_imaginary_tests {
         assert_lex!(b"1.2ri", token!(tIMAGINARY, loc!(0, 5)));
     }
 }
+
+mod error_tests {
+    use crate::{error::ParseErrorKind, lexer::Lexer, loc::loc, token::token};
+
+    #[test]
+    fn test_prefix_without_digits() {
+        let mut lexer = Lexer::new(b"0x");
+        assert_eq!(lexer.next_token(), token!(tEOF, loc!(0, 0)));
+
+        let error = lexer.error.expect("expected lexer to fail");
+        assert_eq!(error.kind, ParseErrorKind::NumericLiteralWithoutDigits);
+        assert_eq!(error.loc, loc!(0, 2));
+    }
+}
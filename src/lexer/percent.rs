@@ -1,5 +1,6 @@
 use crate::{
     buffer::BufferWithCursor,
+    error::{ParseError, ParseErrorKind},
     lexer::strings::{literal::StringLiteral, types::*},
     loc::loc,
     token::{token, Token},
@@ -8,10 +9,23 @@ use crate::{
 pub(crate) fn parse_percent(
     buffer: &mut BufferWithCursor,
     curly_level: usize,
-) -> (Option<StringLiteral>, Token) {
+) -> Result<(Option<StringLiteral>, Token), ParseError> {
     let start = buffer.pos();
     buffer.skip_byte();
 
+    // `a % b` and `a %= b`, the lexer doesn't track the state,
+    // so `% ` and `%=` are never treated as a beginning of the literal
+    match buffer.current_byte() {
+        Some(b' ' | b'\t' | b'\n' | b'\r' | b'\x0b' | b'\x0c') => {
+            return Ok((None, token!(tPERCENT, loc!(start, start + 1))));
+        }
+        Some(b'=') => {
+            buffer.skip_byte();
+            return Ok((None, token!(tOP_ASGN, loc!(start, start + 2))));
+        }
+        _ => {}
+    }
+
     let starts_with;
     let literal_type;
 
@@ -23,7 +37,10 @@ pub(crate) fn parse_percent(
                 literal_type = b'Q';
                 buffer.skip_byte();
             } else {
-                panic!("percent_unknown")
+                return Err(ParseError::new(
+                    ParseErrorKind::UnknownPercentLiteralType,
+                    loc!(start, buffer.pos() + 1),
+                ));
             }
         } else {
             literal_type = c;
@@ -31,16 +48,25 @@ pub(crate) fn parse_percent(
 
             if let Some(c) = buffer.current_byte() {
                 if c.is_ascii_alphabetic() {
-                    panic!("percent_unknown")
+                    return Err(ParseError::new(
+                        ParseErrorKind::UnknownPercentLiteralType,
+                        loc!(start, buffer.pos()),
+                    ));
                 }
                 starts_with = c;
                 buffer.skip_byte();
             } else {
-                panic!("percent_unterminated")
+                return Err(ParseError::new(
+                    ParseErrorKind::UnterminatedPercentLiteral,
+                    loc!(start, buffer.pos()),
+                ));
             }
         }
     } else {
-        panic!("percent_unterminated")
+        return Err(ParseError::new(
+            ParseErrorKind::UnterminatedPercentLiteral,
+            loc!(start, buffer.pos()),
+        ));
     }
 
     let ends_with = match starts_with {
@@ -108,10 +134,15 @@ pub(crate) fn parse_percent(
             literal = StringLiteral::StringPlain(StringPlain::new(starts_with, ends_with));
         }
 
-        _ => panic!("percent_unknown"),
+        _ => {
+            return Err(ParseError::new(
+                ParseErrorKind::UnknownPercentLiteralType,
+                loc!(start, start + 2),
+            ))
+        }
     };
 
-    (Some(literal), token)
+    Ok((Some(literal), token))
 }
 
 #[cfg(test)]
@@ -282,4 +313,26 @@ mod tests {
             literal = StringLiteral::StringPlain(StringPlain::new(b'{', b'}'))
         );
     }
+
+    #[test]
+    fn test_unknown_percent_literal_type() {
+        let mut lexer = Lexer::new(b"%z(");
+        assert_eq!(lexer.next_token(), token!(tEOF, loc!(0, 0)));
+        assert_eq!(
+            lexer.error,
+            Some(ParseError::new(
+                ParseErrorKind::UnknownPercentLiteralType,
+                loc!(0, 2)
+            ))
+        );
+        // lexing is stopped
+        assert_eq!(lexer.next_token(), token!(tEOF, loc!(0, 0)));
+    }
+
+    #[test]
+    fn test_tPERCENT_operator() {
+        use crate::testing::assert_lex;
+        assert_lex!(b"% ", token!(tPERCENT, loc!(0, 1)));
+        assert_lex!(b"%=", token!(tOP_ASGN, loc!(0, 2)));
+    }
 }
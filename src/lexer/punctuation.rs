@@ -1,4 +1,5 @@
 use crate::{
+    error::{ParseError, ParseErrorKind},
     lexer::{
         heredoc_id::HeredocId,
         ident::Ident,
@@ -24,9 +25,11 @@ impl OnByte<b'#'> for Lexer {
                 _ => self.buffer.skip_byte(),
             }
         }
-        // Multiple consecutive comments are merged on the parser level
+        // Comments are collected by `current_token`, one per line
+        let end = self.buffer.pos();
+        self.parse_magic_comment(start, end);
 
-        token!(tCOMMENT, loc!(start, self.buffer.pos()))
+        token!(tCOMMENT, loc!(start, end))
     }
 }
 #[test]
@@ -118,12 +121,10 @@ fn test_tBANG() {
 impl OnByte<b'='> for Lexer {
     fn on_byte(&mut self) -> Token {
         let start = self.buffer.pos();
-        self.buffer.skip_byte();
-
-        if self.buffer.lookahead(b"begin") {
-            self.buffer.set_pos(self.buffer.pos() + 5);
-            return token!(tEMBEDDED_COMMENT_START, loc!(start, start + 6));
+        if self.is_embedded_document_marker(start, b"=begin") {
+            return self.embedded_document(start);
         }
+        self.buffer.skip_byte();
 
         match self.buffer.current_byte() {
             Some(b'=') => {
@@ -149,9 +150,97 @@ impl OnByte<b'='> for Lexer {
     }
 }
 #[test]
-fn test_tEMBEDDED_COMMENT_START() {
+fn test_embedded_document() {
+    use crate::testing::assert_lex;
+    assert_lex!(b"=begin\nfoo\n=end", token!(tCOMMENT, loc!(0, 15)));
+}
+#[test]
+fn test_embedded_document_rest_of_end_line() {
     use crate::testing::assert_lex;
-    assert_lex!(b"=begin", token!(tEMBEDDED_COMMENT_START, loc!(0, 6)));
+    assert_lex!(b"=begin foo\n=end bar\nbaz", token!(tCOMMENT, loc!(0, 19)));
+}
+#[test]
+fn test_embedded_document_is_a_document_comment() {
+    use crate::comment::{Comment, CommentKind};
+
+    let mut lexer = Lexer::new(b"=begin\n  =end\n=endless\n=end\nfoo");
+    assert_eq!(lexer.current_token(), token!(tIDENTIFIER, loc!(28, 31)));
+    assert_eq!(
+        lexer.comments,
+        vec![Comment {
+            kind: CommentKind::Document,
+            loc: loc!(0, 27)
+        }]
+    );
+}
+#[test]
+fn test_embedded_document_not_at_line_start() {
+    let mut lexer = Lexer::new(b"a =begin");
+    let kinds = lexer
+        .tokenize_until_eof()
+        .into_iter()
+        .map(|token| token.kind)
+        .collect::<Vec<_>>();
+    use crate::token::TokenKind::*;
+    assert_eq!(kinds, vec![tIDENTIFIER, tWHITESPACE, tEQL, kBEGIN, tEOF]);
+}
+#[test]
+fn test_embedded_document_unterminated() {
+    let mut lexer = Lexer::new(b"=begin\nfoo\n  =end\n");
+    assert_eq!(lexer.next_token(), token!(tEOF, loc!(0, 0)));
+    assert_eq!(
+        lexer.error,
+        Some(ParseError::new(
+            ParseErrorKind::EmbeddedDocumentMeetsEof,
+            loc!(0, 6)
+        ))
+    );
+}
+
+impl Lexer {
+    // `=begin` and `=end` work only at the beginning of the line
+    // and must be followed by a whitespace or a newline
+    fn is_embedded_document_marker(&self, pos: usize, marker: &[u8]) -> bool {
+        let at_line_start = pos == 0 || self.buffer.byte_at(pos - 1) == Some(b'\n');
+        at_line_start
+            && self.buffer.slice(pos, pos + marker.len()) == Some(marker)
+            && matches!(
+                self.buffer.byte_at(pos + marker.len()),
+                None | Some(b' ' | b'\t' | b'\r' | b'\n')
+            )
+    }
+
+    // Reads `=begin ... =end` as a single comment,
+    // the rest of the `=end` line is also a part of it
+    fn embedded_document(&mut self, start: usize) -> Token {
+        let mut pos = start;
+        loop {
+            // go to the next line
+            loop {
+                match self.buffer.byte_at(pos) {
+                    Some(b'\n') => {
+                        pos += 1;
+                        break;
+                    }
+                    Some(_) => pos += 1,
+                    None => {
+                        return self.stop_with_error(ParseError::new(
+                            ParseErrorKind::EmbeddedDocumentMeetsEof,
+                            loc!(start, start + 6),
+                        ))
+                    }
+                }
+            }
+
+            if self.is_embedded_document_marker(pos, b"=end") {
+                while !matches!(self.buffer.byte_at(pos), None | Some(b'\n')) {
+                    pos += 1;
+                }
+                self.buffer.set_pos(pos);
+                return token!(tCOMMENT, loc!(start, pos));
+            }
+        }
+    }
 }
 #[test]
 fn test_tEQQ() {
@@ -190,6 +279,7 @@ impl OnByte<b'<'> for Lexer {
                     token,
                     id: (id_start, id_end),
                     squiggly,
+                    dash,
                     interpolated,
                 }) = HeredocId::parse(&mut self.buffer)
                 {
@@ -203,8 +293,11 @@ impl OnByte<b'<'> for Lexer {
                             interpolated,
                             loc!(id_start, id_end),
                             token.loc.end,
-                            squiggly,
+                            squiggly || dash,
                         )));
+                    // heredoc body starts on the next line
+                    let body_starts_at = self.heredoc_body_starts_at();
+                    self.buffer.set_pos(body_starts_at);
                     return token;
                 }
             }
@@ -243,7 +336,7 @@ fn test_tSTRING_BEG_HEREDOC() {
     use crate::testing::assert_lex;
     assert_lex!(
         input = b"<<-HERE",
-        token = token!(tDSTRING_BEG, loc!(0, 7)),
+        token = token!(tHEREDOC_BEG, loc!(0, 7)),
         setup = |lexer: &mut Lexer| {
             lexer.curly_nest = 42;
             lexer.require_new_expr();
@@ -257,7 +350,7 @@ fn test_tSTRING_BEG_HEREDOC() {
                     Some(Interpolation::new(42)),
                     loc!(3, 7),
                     7,
-                    false
+                    true
                 )))
             );
         }
@@ -417,7 +510,8 @@ fn test_tSTRING_BEG1_SQUOTE() {
 
 impl OnByte<b'?'> for Lexer {
     fn on_byte(&mut self) -> Token {
-        QMark::parse(&mut self.buffer)
+        let result = QMark::parse(&mut self.buffer, &self.diagnostics);
+        self.unwrap_or_stop(result)
     }
 }
 
@@ -528,11 +622,13 @@ impl OnByte<b'+'> for Lexer {
                 self.buffer.skip_byte();
                 token!(tOP_ASGN, loc!(start, start + 2))
             }
-            Some(b'0'..=b'9') => {
-                let mut token = parse_number(&mut self.buffer);
-                token.loc.start = start;
-                token
-            }
+            Some(b'0'..=b'9') => match parse_number(&mut self.buffer) {
+                Ok(mut token) => {
+                    token.loc.start = start;
+                    token
+                }
+                Err(error) => self.stop_with_error(error),
+            },
             _ => token!(tPLUS, loc!(start, start + 1)),
         }
     }
@@ -610,14 +706,14 @@ impl OnByte<b'.'> for Lexer {
                 }
             }
             Some(b'0'..=b'9') => {
-                let mut end = start;
+                let mut end = start + 1;
                 while matches!(self.buffer.byte_at(end), Some(b'0'..=b'9')) {
                     end += 1;
                 }
-                panic!(
-                    "no .<digit> floating literal anymore; put 0 before dot ({:?})",
-                    self.buffer.slice(start, end)
-                );
+                self.stop_with_error(ParseError::new(
+                    ParseErrorKind::NoDigitsBeforeDot,
+                    loc!(start, end),
+                ))
             }
             _ => token!(tDOT, loc!(start, start + 1)),
         }
@@ -638,15 +734,26 @@ fn test_tDOT() {
     use crate::testing::assert_lex;
     assert_lex!(b".", token!(tDOT, loc!(0, 1)));
 }
+#[test]
+fn test_tDOT_followed_by_digits() {
+    let mut lexer = Lexer::new(b".42");
+    assert_eq!(lexer.next_token(), token!(tEOF, loc!(0, 0)));
+    assert_eq!(
+        lexer.error,
+        Some(ParseError::new(
+            ParseErrorKind::NoDigitsBeforeDot,
+            loc!(0, 3)
+        ))
+    );
+}
 
 impl OnByte<b')'> for Lexer {
     fn on_byte(&mut self) -> Token {
         let start = self.buffer.pos();
         self.buffer.skip_byte();
+        // unbalanced closing bracket is reported by the parser
         if self.paren_nest > 0 {
             self.paren_nest -= 1;
-        } else {
-            panic!("negative paren_nest");
         }
 
         token!(tRPAREN, loc!(start, start + 1))
@@ -669,10 +776,9 @@ impl OnByte<b']'> for Lexer {
     fn on_byte(&mut self) -> Token {
         let start = self.buffer.pos();
         self.buffer.skip_byte();
+        // unbalanced closing bracket is reported by the parser
         if self.brack_nest > 0 {
             self.brack_nest -= 1;
-        } else {
-            panic!("negative brack_nest");
         }
         token!(tRBRACK, loc!(start, start + 1))
     }
@@ -694,10 +800,9 @@ impl OnByte<b'}'> for Lexer {
     fn on_byte(&mut self) -> Token {
         let start = self.buffer.pos();
         self.buffer.skip_byte();
+        // unbalanced closing bracket is reported by the parser
         if self.curly_nest > 0 {
             self.curly_nest -= 1;
-        } else {
-            panic!("negative curly_nest");
         }
         token!(tRCURLY, loc!(start, start + 1))
     }
@@ -996,25 +1101,10 @@ fn test_tESCAPED_VTAB() {
 
 impl OnByte<b'_'> for Lexer {
     fn on_byte(&mut self) -> Token {
-        let start = self.buffer.pos();
-
-        let prev_byte = start
-            .checked_sub(1)
-            .map(|idx| self.buffer.byte_at(idx))
-            .flatten();
-        match prev_byte {
-            // prev byte is either
-            //   + None (i.e. it's the first byte of the file)
-            //   + Some(b'\n')
-            // AND it's "__END__" sequence
-            None | Some(b'\n') if self.buffer.lookahead(b"__END__") => {
-                return token!(tEOF, loc!(start, start));
-            }
-            _ => {}
-        }
-
-        // otherwise it's a `_foo`/`_foo?`/`_foo!` identifier
-        Ident::parse(&mut self.buffer)
+        // `__END__` is handled by `handle_eof`,
+        // so it's a `_foo`/`_foo?`/`_foo!` identifier
+        let result = Ident::parse(&mut self.buffer);
+        self.unwrap_or_stop(result)
     }
 }
 #[test]
@@ -1,5 +1,7 @@
 use crate::{
     buffer::{utf8::Utf8Char, Buffer, BufferWithCursor},
+    diagnostic::{DiagnosticMessage, Diagnostics},
+    error::{ParseError, ParseErrorKind},
     lexer::{
         ident::Ident,
         strings::escapes::{
@@ -8,7 +10,7 @@ use crate::{
         },
     },
     loc::loc,
-    token::{token, Token},
+    token::{token, Token, TokenKind},
 };
 
 pub(crate) struct QMark {
@@ -16,20 +18,25 @@ pub(crate) struct QMark {
 }
 
 impl QMark {
-    pub(crate) fn lookahead(buffer: &mut Buffer, start: usize) -> Self {
+    pub(crate) fn lookahead(buffer: &mut Buffer, start: usize) -> Result<Self, ParseError> {
         match buffer.byte_at(start + 1) {
             Some(byte) => {
-                if (byte.is_ascii_alphanumeric() || byte == b'_')
+                if matches!(byte, b' ' | b'\t' | b'\n' | b'\r' | b'\x0b' | b'\x0c') {
+                    // `cond ? a : b`, `? ` can't be a character literal
+                    return Ok(QMark {
+                        token: token!(tEH, loc!(start, start + 1)),
+                    });
+                } else if (byte.is_ascii_alphanumeric() || byte == b'_')
                     && matches!(
                         Ident::lookahead(buffer, start + 1),
                         Some(Ident { length: 2.. })
                     )
                 {
                     // split ?ident into `?` + `ident`
-                    // TODO: warn about ambiguity
-                    return QMark {
+                    // (ambiguity is reported in `QMark::parse`)
+                    return Ok(QMark {
                         token: token!(tEH, loc!(start, start + 1)),
-                    };
+                    });
                 } else if byte == b'\\' {
                     match Escape::lookahead(buffer, start + 1) {
                         Ok(None) => {
@@ -38,55 +45,48 @@ impl QMark {
                         Ok(Some(escape)) => match escape {
                             // single char `?f` syntax doesn't support wide \u escapes
                             // because they may have multiple codepoints
-                            Escape::SlashU(SlashU::Wide {
-                                escaped_loc,
-                                length,
-                            }) => {
-                                panic!(
-                                    "wide codepoint in ?\\u syntax: {:?}, {}",
-                                    escaped_loc, length
-                                );
+                            Escape::SlashU(SlashU::Wide { length, .. }) => {
+                                return Err(ParseError::new(
+                                    ParseErrorKind::InvalidUnicodeEscape,
+                                    loc!(start, start + 1 + length),
+                                ));
                             }
 
                             Escape::SlashU(SlashU::Short {
                                 codepoint: bytes,
                                 length,
                             }) => {
-                                return QMark {
+                                return Ok(QMark {
                                     token: token!(tCHAR, loc!(start, start + 1 + length), bytes),
-                                };
+                                });
                             }
 
                             Escape::SlashOctal(SlashOctal { byte, length })
                             | Escape::SlashX(SlashX { byte, length })
                             | Escape::SlashMetaCtrl(SlashMetaCtrl { byte, length })
                             | Escape::SlashByte(SlashByte { byte, length }) => {
-                                return QMark {
+                                return Ok(QMark {
                                     token: token!(tCHAR, loc!(start, start + 1 + length), byte),
-                                };
-                            }
-                        },
-                        Err(err) => match err {
-                            EscapeError::SlashUError(SlashUError {
-                                escaped_loc,
-                                errors,
-                                length,
-                            }) => {
-                                panic!(
-                                    "SlashUError {:?} / {:?} / {:?}",
-                                    escaped_loc, errors, length
-                                );
-                            }
-                            EscapeError::SlashXError(SlashXError { length }) => {
-                                panic!("SlashXError {:?}", length);
-                            }
-                            EscapeError::SlashMetaCtrlError(SlashMetaCtrlError { length }) => {
-                                panic!("SlashMetaCtrlError {:?}", length)
-                            }
-                            EscapeError::SlashByteError(SlashByteError { length }) => {
-                                panic!("SlashByteError {:?}", length)
+                                });
                             }
                         },
+                        Err(err) => {
+                            let (kind, length) = match err {
+                                EscapeError::SlashUError(SlashUError { length, .. }) => {
+                                    (ParseErrorKind::InvalidUnicodeEscape, length)
+                                }
+                                EscapeError::SlashXError(SlashXError { length }) => {
+                                    (ParseErrorKind::InvalidHexEscape, length)
+                                }
+                                EscapeError::SlashMetaCtrlError(SlashMetaCtrlError { length }) => {
+                                    (ParseErrorKind::InvalidMetaCtrlEscape, length)
+                                }
+                                EscapeError::SlashByteError(SlashByteError { length }) => {
+                                    (ParseErrorKind::InvalidEscape, length)
+                                }
+                            };
+                            return Err(ParseError::new(kind, loc!(start, start + 1 + length)));
+                        }
                     }
                 }
             }
@@ -102,35 +102,59 @@ impl QMark {
                     .chars()
                     .next()
                     .unwrap();
-                QMark {
+                Ok(QMark {
                     token: token!(tCHAR, loc!(start, end), c),
-                }
+                })
             }
-            _ => QMark {
+            _ => Ok(QMark {
                 token: token!(tEH, loc!(start, start + 1)),
-            },
+            }),
         }
     }
 }
 
 impl QMark {
-    pub(crate) fn parse(buffer: &mut BufferWithCursor) -> Token {
+    pub(crate) fn parse(
+        buffer: &mut BufferWithCursor,
+        diagnostics: &Diagnostics,
+    ) -> Result<Token, ParseError> {
         let start = buffer.pos();
-        let QMark { token } = QMark::lookahead(buffer.for_lookahead_mut(), start);
+        let QMark { token } = QMark::lookahead(buffer.for_lookahead_mut(), start)?;
+
+        if token.is(TokenKind::tEH) {
+            if let Some(Ident { length }) = Ident::lookahead(buffer.for_lookahead(), start + 1) {
+                let condition = buffer.slice(start + 1, start + 1 + length).expect("bug");
+                diagnostics.warn(
+                    DiagnosticMessage::AmbiguousTernaryOperator {
+                        condition: String::from_utf8_lossy(condition).into_owned(),
+                    },
+                    loc!(start, start + 1),
+                );
+            }
+        }
+
         buffer.set_pos(token.loc.end);
-        token
+        Ok(token)
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::{testing::assert_lex, token::token};
+    use crate::{
+        diagnostic::{Diagnostic, DiagnosticLevel, DiagnosticMessage},
+        testing::assert_lex,
+        token::token,
+    };
 
     #[test]
     fn test_tEH() {
         assert_lex!(b"?", token!(tEH, loc!(0, 1)));
     }
     #[test]
+    fn test_tEH_before_whitespace() {
+        assert_lex!(b"? ", token!(tEH, loc!(0, 1)));
+    }
+    #[test]
     fn test_tCHAR_ascii() {
         assert_lex!(b"?a", token!(tCHAR, loc!(0, 2), b'a'));
     }
@@ -144,6 +168,22 @@ mod tests {
     }
     #[test]
     fn test_tEH_and_ident() {
-        assert_lex!(b"?ident", token!(tEH, loc!(0, 1)));
+        assert_lex!(
+            input = b"?ident",
+            token = token!(tEH, loc!(0, 1)),
+            setup = |_lexer: &mut Lexer| {},
+            assert = |lexer: &Lexer| {
+                assert_eq!(
+                    lexer.diagnostics.take_inner(),
+                    vec![Diagnostic {
+                        level: DiagnosticLevel::Warning,
+                        message: DiagnosticMessage::AmbiguousTernaryOperator {
+                            condition: String::from("ident")
+                        },
+                        loc: loc!(0, 1)
+                    }]
+                );
+            }
+        );
     }
 }
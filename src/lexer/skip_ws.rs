@@ -1,4 +1,4 @@
-use crate::{lexer::Lexer, loc::loc, token::token, Token};
+use crate::{diagnostic::DiagnosticMessage, lexer::Lexer, loc::loc, token::token, Token};
 
 impl Lexer {
     pub(crate) fn skip_ws(&mut self) -> Option<Token> {
@@ -8,7 +8,11 @@ impl Lexer {
             match self.buffer.current_byte() {
                 // whitespaces
                 Some(b'\r') => {
-                    // TODO: warn about \r at middle of the line
+                    let pos = self.buffer.pos();
+                    if self.buffer.byte_at(pos + 1) != Some(b'\n') {
+                        self.diagnostics
+                            .warn(DiagnosticMessage::SlashRAtMiddleOfLine, loc!(pos, pos + 1));
+                    }
                     self.buffer.skip_byte();
                     continue;
                 }
@@ -32,3 +36,19 @@ impl Lexer {
         }
     }
 }
+
+#[test]
+fn test_slash_r_at_middle_of_line() {
+    use crate::diagnostic::{Diagnostic, DiagnosticLevel};
+
+    let mut lexer = Lexer::new(b"\r \r\n");
+    assert_eq!(lexer.skip_ws(), Some(token!(tWHITESPACE, loc!(0, 3))));
+    assert_eq!(
+        lexer.diagnostics.take_inner(),
+        vec![Diagnostic {
+            level: DiagnosticLevel::Warning,
+            message: DiagnosticMessage::SlashRAtMiddleOfLine,
+            loc: loc!(0, 1)
+        }]
+    );
+}
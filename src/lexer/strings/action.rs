@@ -1,10 +1,29 @@
-use crate::token::Token;
+use crate::{diagnostic::DiagnosticMessage, token::Token, Loc};
 
 #[derive(PartialEq, Eq, Debug)]
 pub(crate) enum StringExtendAction {
-    EmitToken { token: Token },
-    FoundStringEnd { token: Token },
-    FoundInterpolation { token: Token },
-    EmitEOF { at: usize },
+    EmitToken {
+        token: Token,
+    },
+    // a token that is still emitted, but its source is invalid,
+    // like a malformed escape sequence
+    EmitTokenWithError {
+        token: Token,
+        message: DiagnosticMessage,
+        loc: Loc,
+    },
+    FoundStringEnd {
+        token: Token,
+    },
+    FoundHeredocEnd {
+        token: Token,
+        heredoc_ended_at: usize,
+    },
+    FoundInterpolation {
+        token: Token,
+    },
+    EmitEOF {
+        at: usize,
+    },
     ReadInterpolatedContent,
 }
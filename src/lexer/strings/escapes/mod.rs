@@ -1,5 +1,5 @@
 mod slash_u;
-pub(crate) use slash_u::{SlashU, SlashUError, SlashUPerCodepointError};
+pub(crate) use slash_u::{SlashU, SlashUError};
 
 mod slash_octal;
 pub(crate) use slash_octal::SlashOctal;
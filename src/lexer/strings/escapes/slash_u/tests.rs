@@ -10,7 +10,7 @@ macro_rules! assert_lookahead {
             #[allow(unused_imports)]
             use crate::{
                 buffer::Buffer,
-                lexer::strings::escapes::{SlashU, SlashUError, SlashUPerCodepointError},
+                lexer::strings::escapes::{slash_u::SlashUPerCodepointError, SlashU, SlashUError},
                 loc::Loc,
             };
             let mut buffer = Buffer::new($input);
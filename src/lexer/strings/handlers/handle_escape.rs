@@ -2,11 +2,12 @@ use std::ops::ControlFlow;
 
 use crate::{
     buffer::BufferWithCursor,
+    diagnostic::DiagnosticMessage,
     lexer::strings::{
         action::StringExtendAction,
         escapes::{
             Escape, EscapeError, SlashByte, SlashByteError, SlashMetaCtrl, SlashMetaCtrlError,
-            SlashOctal, SlashU, SlashUError, SlashUPerCodepointError, SlashX, SlashXError,
+            SlashOctal, SlashU, SlashUError, SlashX, SlashXError,
         },
         handlers::handle_processed_string_content,
     },
@@ -57,69 +58,43 @@ pub(crate) fn handle_escape(
         },
 
         Err(err) => {
-            // in case of error:
-            // 1. record all valid codepoints (if any)
-            let escaped_loc;
-
-            match &err {
+            // record all valid codepoints (if any) and report the error,
+            // the escape is still emitted, so that the literal is not interrupted
+            let escaped_loc = match &err {
                 EscapeError::SlashUError(SlashUError {
                     escaped_loc: loc, ..
-                }) if !loc.is_empty() => {
-                    escaped_loc = *loc;
-                }
-                _ => escaped_loc = Loc { start: 0, end: 0 },
-            }
-
-            match &err {
-                EscapeError::SlashUError(SlashUError {
-                    escaped_loc,
-                    errors,
-                    length,
-                }) => {
-                    eprintln!(
-                        "Got \\u errors: {:?} {:?} {:?}",
-                        escaped_loc, errors, length
-                    );
-                    for error in errors {
-                        match error {
-                            SlashUPerCodepointError::Expected4Got { start, length } => {
-                                eprintln!("expected 4 got {:?} {:?}", start, length);
-                            }
-                            SlashUPerCodepointError::TooLong { start, length } => {
-                                eprintln!("too long {:?} {:?}", start, length);
-                            }
-                            SlashUPerCodepointError::NonHex { start, length } => {
-                                eprintln!("non-hex {:?} {:?}", start, length);
-                            }
-                            SlashUPerCodepointError::NoRCurly { start } => {
-                                eprintln!("no closing rcurly {:?} {:?}", start, length);
-                            }
-                        }
-                    }
+                }) if !loc.is_empty() => *loc,
+                _ => Loc { start: 0, end: 0 },
+            };
+            let (message, length) = match err {
+                EscapeError::SlashUError(SlashUError { length, .. }) => {
+                    (DiagnosticMessage::InvalidUnicodeEscape, length)
                 }
                 EscapeError::SlashXError(SlashXError { length }) => {
-                    eprintln!("\\x error {:?}", length);
+                    (DiagnosticMessage::InvalidHexEscape, length)
                 }
                 EscapeError::SlashMetaCtrlError(SlashMetaCtrlError { length }) => {
-                    eprintln!("\\ meta/ctrl error {:?}", length);
+                    (DiagnosticMessage::InvalidMetaCtrlEscape, length)
                 }
                 EscapeError::SlashByteError(SlashByteError { length }) => {
-                    eprintln!("\\byte error {:?}", length);
+                    (DiagnosticMessage::InvalidEscape, length)
                 }
-            }
-            // 2. TODO: report `err`
-
-            escape_content = TokenValue::UnescapedChars {
-                loc: escaped_loc,
-                buffer: buffer.for_lookahead(),
             };
 
-            escape_length = match err {
-                EscapeError::SlashUError(SlashUError { length, .. })
-                | EscapeError::SlashXError(SlashXError { length })
-                | EscapeError::SlashMetaCtrlError(SlashMetaCtrlError { length })
-                | EscapeError::SlashByteError(SlashByteError { length }) => length,
-            };
+            buffer.set_pos(lookahead_start + length);
+
+            return ControlFlow::Break(StringExtendAction::EmitTokenWithError {
+                token: token!(
+                    tSTRING_CONTENT,
+                    loc!(start, start + length),
+                    TokenValue::UnescapedChars {
+                        loc: escaped_loc,
+                        buffer: buffer.for_lookahead(),
+                    }
+                ),
+                message,
+                loc: loc!(lookahead_start, lookahead_start + length),
+            });
         }
     };
 
@@ -34,6 +34,21 @@ pub(crate) enum StringLiteral {
     Regexp(Regexp),
 }
 
+impl StringLiteral {
+    // Returns `true` after `#{` until the matching `}`,
+    // when the code inside of the literal is lexed normally
+    pub(crate) fn is_in_interpolation(&self) -> bool {
+        match self {
+            Self::StringInterp(string) => string.is_in_interpolation(),
+            Self::SymbolInterp(symbol) => symbol.is_in_interpolation(),
+            Self::Heredoc(heredoc) => heredoc.is_in_interpolation(),
+            Self::Regexp(regexp) => regexp.is_in_interpolation(),
+            Self::WordsInterp(words) => words.is_in_interpolation(),
+            Self::StringPlain(_) | Self::SymbolPlain(_) | Self::WordsPlain(_) => false,
+        }
+    }
+}
+
 impl StringLiteralExtend for StringLiteral {
     fn extend(
         &mut self,
@@ -21,3 +21,102 @@ fn test_string_interp_braces() {
         ]
     );
 }
+
+#[test]
+fn test_heredoc_rest_of_the_line() {
+    let mut lexer = Lexer::new(b"<<-HERE + 1\n  foo\n  HERE\n2");
+    lexer.require_new_expr();
+    assert_eq!(
+        lexer.tokenize_until_eof(),
+        vec![
+            token!(tHEREDOC_BEG, loc!(0, 7)),
+            token!(tSTRING_CONTENT, loc!(12, 18)),
+            token!(tSTRING_END, loc!(20, 24)),
+            token!(tWHITESPACE, loc!(7, 8)),
+            token!(tPLUS, loc!(8, 9)),
+            token!(tWHITESPACE, loc!(9, 10)),
+            token!(tINTEGER, loc!(10, 11)),
+            token!(tNL, loc!(11, 12)),
+            token!(tINTEGER, loc!(25, 26)),
+            token!(tEOF, loc!(26, 26)),
+        ]
+    );
+}
+
+#[test]
+fn test_heredoc_interp() {
+    let mut lexer = Lexer::new(b"<<HERE\na#{1}\nHERE");
+    lexer.require_new_expr();
+    assert_eq!(
+        lexer.tokenize_until_eof(),
+        vec![
+            token!(tHEREDOC_BEG, loc!(0, 6)),
+            token!(tSTRING_CONTENT, loc!(7, 8)),
+            token!(tSTRING_DBEG, loc!(8, 10)),
+            token!(tINTEGER, loc!(10, 11)),
+            token!(tSTRING_DEND, loc!(11, 12)),
+            token!(tSTRING_CONTENT, loc!(12, 13)),
+            token!(tSTRING_END, loc!(13, 17)),
+            token!(tNL, loc!(6, 7)),
+            token!(tEOF, loc!(17, 17)),
+        ]
+    );
+}
+
+#[test]
+fn test_heredoc_no_interp() {
+    let mut lexer = Lexer::new(b"<<'HERE'\na#{1}\n HERE\nHERE\n");
+    lexer.require_new_expr();
+    assert_eq!(
+        lexer.tokenize_until_eof(),
+        vec![
+            token!(tHEREDOC_BEG, loc!(0, 8)),
+            token!(tSTRING_CONTENT, loc!(9, 15)),
+            token!(tSTRING_CONTENT, loc!(15, 21)),
+            token!(tSTRING_END, loc!(21, 25)),
+            token!(tNL, loc!(8, 9)),
+            token!(tEOF, loc!(26, 26)),
+        ]
+    );
+}
+
+#[test]
+fn test_heredoc_unterminated() {
+    let mut lexer = Lexer::new(b"<<~HERE\nfoo\n");
+    lexer.require_new_expr();
+    assert_eq!(
+        lexer.tokenize_until_eof(),
+        vec![
+            token!(tHEREDOC_BEG, loc!(0, 7)),
+            token!(tSTRING_CONTENT, loc!(8, 12)),
+            token!(tEOF, loc!(12, 12)),
+        ]
+    );
+}
+
+#[test]
+fn test_invalid_escape_is_reported() {
+    use crate::diagnostic::{Diagnostic, DiagnosticLevel, DiagnosticMessage};
+
+    let mut lexer = Lexer::new(b"\"a\\xZ\\u{zz}\"");
+    let tokens = lexer.tokenize_until_eof();
+    assert_eq!(
+        tokens.last().map(|token| token.kind),
+        Some(crate::token::TokenKind::tEOF)
+    );
+    assert_eq!(
+        lexer.diagnostics.take_inner(),
+        vec![
+            Diagnostic {
+                level: DiagnosticLevel::Error,
+                message: DiagnosticMessage::InvalidHexEscape,
+                loc: loc!(2, 4)
+            },
+            Diagnostic {
+                level: DiagnosticLevel::Error,
+                message: DiagnosticMessage::InvalidUnicodeEscape,
+                loc: loc!(5, 11)
+            }
+        ]
+    );
+}
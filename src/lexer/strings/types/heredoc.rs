@@ -3,8 +3,16 @@ use std::ops::ControlFlow;
 use crate::{
     buffer::BufferWithCursor,
     lexer::strings::{
-        action::StringExtendAction, literal::StringLiteralExtend, types::Interpolation,
+        action::StringExtendAction,
+        handlers::{
+            handle_eof, handle_escape, handle_interpolation, handle_interpolation_end,
+            handle_line_continuation,
+        },
+        literal::StringLiteralExtend,
+        types::Interpolation,
     },
+    loc::loc,
+    token::token,
     Loc,
 };
 
@@ -14,16 +22,50 @@ pub(crate) struct Heredoc {
     id_loc: Loc,
 
     heredoc_id_ended_at: usize,
-    squiggly: bool,
+    // `<<-` and `<<~` heredocs can have indented terminator
+    indent: bool,
 }
 
 impl StringLiteralExtend for Heredoc {
     fn extend(
         &mut self,
-        _buffer: &mut BufferWithCursor,
-        _current_curly_nest: usize,
+        buffer: &mut BufferWithCursor,
+        current_curly_nest: usize,
     ) -> ControlFlow<StringExtendAction> {
-        todo!("heredoc.extend")
+        if let Some(interpolation) = self.interpolation.as_mut() {
+            handle_interpolation_end(buffer, current_curly_nest, interpolation)?;
+        }
+
+        let start = buffer.pos();
+
+        // heredoc terminator can only be at the beginning of the line
+        if start == 0 || buffer.byte_at(start - 1) == Some(b'\n') {
+            self.handle_heredoc_end(buffer)?;
+        }
+
+        loop {
+            handle_eof(buffer, start)?;
+
+            if let Some(interpolation) = self.interpolation.as_mut() {
+                handle_line_continuation(buffer, start)?;
+
+                handle_escape(buffer, start)?;
+
+                handle_interpolation(interpolation, buffer, start)?;
+            }
+
+            // every line is emitted as a separate token,
+            // so that `<<~` heredocs can be dedented line by line
+            if buffer.current_byte() == Some(b'\n') {
+                let end = buffer.pos() + 1;
+                buffer.set_pos(end);
+                return ControlFlow::Break(StringExtendAction::EmitToken {
+                    token: token!(tSTRING_CONTENT, loc!(start, end)),
+                });
+            }
+
+            buffer.skip_byte();
+        }
     }
 }
 
@@ -32,13 +74,53 @@ impl Heredoc {
         interpolation: Option<Interpolation>,
         id_loc: Loc,
         heredoc_id_ended_at: usize,
-        squiggly: bool,
+        indent: bool,
     ) -> Self {
         Self {
             interpolation,
             id_loc,
             heredoc_id_ended_at,
-            squiggly,
+            indent,
         }
     }
+
+    pub(crate) fn is_in_interpolation(&self) -> bool {
+        self.interpolation
+            .is_some_and(|interpolation| interpolation.enabled)
+    }
+
+    // Checks if the line that starts at `buffer.pos()` is a heredoc terminator
+    fn handle_heredoc_end(&self, buffer: &mut BufferWithCursor) -> ControlFlow<StringExtendAction> {
+        let mut id_start = buffer.pos();
+        if self.indent {
+            while let Some(b' ' | b'\t') = buffer.byte_at(id_start) {
+                id_start += 1;
+            }
+        }
+
+        let id = buffer
+            .slice(self.id_loc.start, self.id_loc.end)
+            .expect("bug: heredoc id is out of bounds");
+        if !buffer.for_lookahead().lookahead(id_start, &id) {
+            return ControlFlow::Continue(());
+        }
+
+        let id_end = id_start + id.len();
+        let heredoc_ended_at = match (buffer.byte_at(id_end), buffer.byte_at(id_end + 1)) {
+            (None, _) => id_end,
+            (Some(b'\n'), _) => id_end + 1,
+            (Some(b'\r'), Some(b'\n')) => id_end + 2,
+            // something else after heredoc id, like
+            // HERE_AND_NOT_THE_END
+            _ => return ControlFlow::Continue(()),
+        };
+
+        // continue lexing the rest of the line where heredoc has started
+        buffer.set_pos(self.heredoc_id_ended_at);
+
+        ControlFlow::Break(StringExtendAction::FoundHeredocEnd {
+            token: token!(tSTRING_END, loc!(id_start, id_end)),
+            heredoc_ended_at,
+        })
+    }
 }
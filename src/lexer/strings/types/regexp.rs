@@ -37,6 +37,10 @@ impl Regexp {
         }
     }
 
+    pub(crate) fn is_in_interpolation(&self) -> bool {
+        self.interpolation.enabled
+    }
+
     #[cfg(test)]
     pub(crate) fn enable_interpolation(&mut self) {
         self.interpolation.enabled = true;
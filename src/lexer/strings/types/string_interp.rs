@@ -32,6 +32,10 @@ impl StringInterp {
         }
     }
 
+    pub(crate) fn is_in_interpolation(&self) -> bool {
+        self.interpolation.enabled
+    }
+
     #[cfg(test)]
     pub(crate) fn enable_interpolation(&mut self) {
         self.interpolation.enabled = true;
@@ -25,6 +25,10 @@ impl SymbolInterp {
         }
     }
 
+    pub(crate) fn is_in_interpolation(&self) -> bool {
+        self.interpolation.enabled
+    }
+
     #[cfg(test)]
     pub(crate) fn enable_interpolation(&mut self) {
         self.interpolation.enabled = true;
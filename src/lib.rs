@@ -1,4 +1,5 @@
 mod buffer;
+pub use buffer::{Buffer, LineCol, Position, PositionEncoding};
 
 #[cfg_attr(test, allow(non_snake_case))]
 mod lexer;
@@ -7,10 +8,10 @@ pub use lexer::Lexer;
 mod precedence;
 
 pub mod nodes;
-pub use nodes::Node;
+pub use nodes::{Fold, Node, Visitor, Walk};
 
 pub(crate) mod parser;
-pub use parser::Parser;
+pub use parser::{Parser, ParserResult};
 
 pub(crate) mod string_content;
 
@@ -22,5 +23,32 @@ pub use token::{Token, TokenKind};
 
 pub(crate) mod builder;
 
+pub(crate) mod error;
+pub use error::{ParseError, ParseErrorKind};
+
+pub(crate) mod diagnostic;
+pub use diagnostic::{Diagnostic, DiagnosticLevel, DiagnosticMessage};
+
+pub(crate) mod comment;
+pub use comment::{Comment, CommentKind};
+
+pub(crate) mod magic_comment;
+pub use magic_comment::{MagicComment, MagicCommentKind};
+
+pub(crate) mod associator;
+pub use associator::Associator;
+
+pub(crate) mod node_index;
+pub use node_index::NodeIndex;
+
+pub(crate) mod tree_rewriter;
+pub use tree_rewriter::{RewriteError, RewriteErrorKind, TreeRewriter};
+
+pub(crate) mod static_environment;
+
+pub(crate) mod current_arg_stack;
+
+pub(crate) mod max_numparam_stack;
+
 #[cfg(test)]
 mod testing;
@@ -1,3 +1,5 @@
+use crate::buffer::{Buffer, LineCol};
+
 #[derive(Clone, Copy, PartialEq, Eq, Default)]
 #[repr(C)]
 pub struct Loc {
@@ -88,31 +90,44 @@ impl Loc {
         self.start == self.end
     }
 
-    // pub(crate) fn start_line_col(&self, _input: &Buffer) -> Option<(usize, usize)> {
-    //     input.line_col_for_pos(self.start)
-    // }
-
-    // pub(crate) fn expand_to_line(&self, _input: &Buffer) -> Option<(usize, Loc)> {
-    //     let (start_line, _) = self.start_line_col(input)?;
-    //     let line_no = start_line;
-    //     let line = input.line_at(line_no);
-    //     Some((line_no, Self { start: line.start, line.line_end())))
-    // }
-
-    // /// Returns source code of the current `Loc` on a given `Input`
-    // pub fn source(&self, _input: &Buffer) -> Option<String> {
-    //     let bytes = input.substr_at(self.start, self.end)?;
-    //     Some(String::from_utf8_lossy(bytes).into_owned())
-    // }
-
-    // pub(crate) fn print(&self, name: &str) {
-    //     println!(
-    //         "{}{} {}",
-    //         " ".repeat(self.start),
-    //         "~".repeat(self.size()),
-    //         name
-    //     )
-    // }
+    /// Returns line and column of the `start` of the `Loc` in a given `Buffer`
+    pub fn start_line_col(&self, input: &Buffer) -> Option<LineCol> {
+        input.line_col_for_pos(self.start)
+    }
+
+    /// Returns line and column of the `end` of the `Loc` in a given `Buffer`
+    pub fn end_line_col(&self, input: &Buffer) -> Option<LineCol> {
+        input.line_col_for_pos(self.end)
+    }
+
+    /// Returns 1-based number of the line where the `Loc` starts
+    /// and the location of the whole line (without trailing newline)
+    pub fn expand_to_line(&self, input: &Buffer) -> Option<(usize, Loc)> {
+        let LineCol { line, .. } = self.start_line_col(input)?;
+        Some((line, input.line_at(line)?))
+    }
+
+    /// Returns source code of the current `Loc` in a given `Buffer`
+    pub fn source(&self, input: &Buffer) -> Option<String> {
+        let bytes = input.slice(self.start, self.end)?;
+        Some(String::from_utf8_lossy(bytes).into_owned())
+    }
+
+    /// Renders the `Loc` as `~~~ name` to be printed under its line of source code (for debugging).
+    /// Multi-line locations are underlined until the end of the first line.
+    pub fn underline(&self, input: &Buffer, name: &str) -> Option<String> {
+        let (_, line_l) = self.expand_to_line(input)?;
+        let start = self.start_line_col(input)?;
+        let end = self
+            .with_end(std::cmp::min(self.end, line_l.end))
+            .end_line_col(input)?;
+        Some(format!(
+            "{}{} {}",
+            " ".repeat(start.char_column),
+            "~".repeat(end.char_column - start.char_column),
+            name
+        ))
+    }
 }
 
 impl std::fmt::Debug for Loc {
@@ -146,3 +161,58 @@ fn test_is_empty() {
     assert!(Loc { start: 1, end: 1 }.is_empty());
     assert!(!Loc { start: 1, end: 2 }.is_empty());
 }
+
+#[test]
+fn test_source() {
+    let input = Buffer::new(b"foo = 42");
+    assert_eq!(
+        Loc { start: 6, end: 8 }.source(&input),
+        Some(String::from("42"))
+    );
+    assert_eq!(Loc { start: 6, end: 9 }.source(&input), None);
+}
+
+#[test]
+fn test_line_col() {
+    let input = Buffer::new("foo\n  \"é\" + bar".as_bytes());
+    let loc = Loc { start: 13, end: 16 };
+    assert_eq!(
+        loc.start_line_col(&input),
+        Some(LineCol {
+            line: 2,
+            column: 9,
+            char_column: 8
+        })
+    );
+    assert_eq!(
+        loc.end_line_col(&input),
+        Some(LineCol {
+            line: 2,
+            column: 12,
+            char_column: 11
+        })
+    );
+}
+
+#[test]
+fn test_expand_to_line() {
+    let input = Buffer::new(b"foo\nbar + baz\nqux");
+    let loc = Loc { start: 10, end: 13 };
+    assert_eq!(
+        loc.expand_to_line(&input),
+        Some((2, Loc { start: 4, end: 13 }))
+    );
+}
+
+#[test]
+fn test_underline() {
+    let input = Buffer::new("foo\n  \"é\" + bar".as_bytes());
+    assert_eq!(
+        Loc { start: 13, end: 16 }.underline(&input, "name"),
+        Some(String::from("        ~~~ name"))
+    );
+    assert_eq!(
+        Loc { start: 1, end: 7 }.underline(&input, "multiline"),
+        Some(String::from(" ~~ multiline"))
+    );
+}
@@ -0,0 +1,30 @@
+use crate::Loc;
+
+/// Kind of the magic comment
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MagicCommentKind {
+    /// `# encoding: utf-8` or `# coding: utf-8`
+    Encoding,
+
+    /// `# frozen_string_literal: true`
+    FrozenStringLiteral,
+
+    /// `# warn_indent: true`
+    WarnIndent,
+
+    /// `# shareable_constant_value: literal`
+    ShareableConstantValue,
+}
+
+/// Comment that changes the way the file is interpreted
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MagicComment {
+    /// Kind of the magic comment
+    pub kind: MagicCommentKind,
+
+    /// Location of the key (`frozen_string_literal` in `# frozen_string_literal: true`)
+    pub key_l: Loc,
+
+    /// Location of the value (`true` in `# frozen_string_literal: true`)
+    pub value_l: Loc,
+}
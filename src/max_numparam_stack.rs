@@ -0,0 +1,129 @@
+use std::cell::RefCell;
+
+// Implicit parameters (`_1`..`_9` and `it`) used by blocks that are being parsed.
+//
+// Blocks and lambdas push a dynamic scope, `def`/`class`/`module` push a static scope
+// that hides outer blocks, so `_1` inside a method body is a regular identifier.
+#[derive(Debug, Default)]
+pub(crate) struct MaxNumparamStack {
+    stack: RefCell<Vec<Scope>>,
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+struct Scope {
+    is_static: bool,
+    has_ordinary_params: bool,
+    max_numparam: u8,
+    uses_it: bool,
+}
+
+impl MaxNumparamStack {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    pub(crate) fn push_static(&self) {
+        self.stack.borrow_mut().push(Scope {
+            is_static: true,
+            ..Scope::default()
+        })
+    }
+
+    pub(crate) fn push_dynamic(&self) {
+        self.stack.borrow_mut().push(Scope::default())
+    }
+
+    // Returns the number of implicit parameters that the popped block takes,
+    // `it` is the same as `_1`
+    pub(crate) fn pop(&self) -> Option<u8> {
+        let scope = self
+            .stack
+            .borrow_mut()
+            .pop()
+            .expect("bug: pop is called without push");
+
+        if scope.max_numparam > 0 {
+            Some(scope.max_numparam)
+        } else if scope.uses_it {
+            Some(1)
+        } else {
+            None
+        }
+    }
+
+    pub(crate) fn in_dynamic_block(&self) -> bool {
+        self.top().is_some_and(|scope| !scope.is_static)
+    }
+
+    pub(crate) fn set_has_ordinary_params(&self) {
+        self.update_top(|scope| scope.has_ordinary_params = true)
+    }
+
+    pub(crate) fn has_ordinary_params(&self) -> bool {
+        self.top().is_some_and(|scope| scope.has_ordinary_params)
+    }
+
+    pub(crate) fn has_numparams(&self) -> bool {
+        self.top().is_some_and(|scope| scope.max_numparam > 0)
+    }
+
+    pub(crate) fn uses_it(&self) -> bool {
+        self.top().is_some_and(|scope| scope.uses_it)
+    }
+
+    // Returns true if any enclosing block (up to the closest static scope) uses numbered parameters
+    pub(crate) fn outer_block_has_numparams(&self) -> bool {
+        self.stack
+            .borrow()
+            .iter()
+            .rev()
+            .skip(1)
+            .take_while(|scope| !scope.is_static)
+            .any(|scope| scope.max_numparam > 0)
+    }
+
+    pub(crate) fn register_numparam(&self, numparam: u8) {
+        self.update_top(|scope| scope.max_numparam = scope.max_numparam.max(numparam))
+    }
+
+    pub(crate) fn register_it(&self) {
+        self.update_top(|scope| scope.uses_it = true)
+    }
+
+    fn top(&self) -> Option<Scope> {
+        self.stack.borrow().last().copied()
+    }
+
+    fn update_top(&self, f: impl FnOnce(&mut Scope)) {
+        if let Some(scope) = self.stack.borrow_mut().last_mut() {
+            f(scope)
+        }
+    }
+}
+
+#[test]
+fn test_max_numparam() {
+    let stack = MaxNumparamStack::new();
+    stack.push_dynamic();
+    assert!(stack.in_dynamic_block());
+    stack.register_numparam(2);
+    stack.register_numparam(1);
+    assert_eq!(stack.pop(), Some(2));
+}
+
+#[test]
+fn test_static_scope_hides_outer_blocks() {
+    let stack = MaxNumparamStack::new();
+    stack.push_dynamic();
+    stack.register_numparam(1);
+
+    stack.push_static();
+    assert!(!stack.in_dynamic_block());
+    stack.push_dynamic();
+    assert!(!stack.outer_block_has_numparams());
+    assert_eq!(stack.pop(), None);
+    stack.pop();
+
+    stack.push_dynamic();
+    assert!(stack.outer_block_has_numparams());
+}
@@ -0,0 +1,149 @@
+use std::collections::HashMap;
+
+use crate::Node;
+
+/// Index of all nodes of the AST with links to their parents.
+///
+/// Nodes are identified by their address, so lookups only work for nodes
+/// that are borrowed from the same tree that the index was built for.
+#[derive(Debug)]
+pub struct NodeIndex<'a> {
+    // all nodes in pre-order
+    nodes: Vec<IndexedNode<'a>>,
+    ids: HashMap<*const Node, usize>,
+}
+
+#[derive(Debug)]
+struct IndexedNode<'a> {
+    node: &'a Node,
+    depth: usize,
+    parent: Option<usize>,
+}
+
+impl<'a> NodeIndex<'a> {
+    /// Indexes the given tree
+    pub fn new(ast: &'a Node) -> Self {
+        let mut nodes: Vec<IndexedNode<'a>> = vec![];
+        // index of the last visited node on every depth,
+        // in pre-order a parent is the last visited node one level above
+        let mut last_on_depth: Vec<usize> = vec![];
+
+        for (depth, node) in ast.walk() {
+            last_on_depth.truncate(depth);
+            let parent = last_on_depth.last().copied();
+            last_on_depth.push(nodes.len());
+            nodes.push(IndexedNode {
+                node,
+                depth,
+                parent,
+            });
+        }
+
+        let ids = nodes
+            .iter()
+            .enumerate()
+            .map(|(id, indexed)| (indexed.node as *const Node, id))
+            .collect();
+
+        Self { nodes, ids }
+    }
+
+    /// Returns parent of the node, `None` for the root
+    /// and for nodes that don't belong to the indexed tree
+    pub fn parent(&self, node: &Node) -> Option<&'a Node> {
+        let parent = self.indexed(node)?.parent?;
+        Some(self.nodes[parent].node)
+    }
+
+    /// Returns all ancestors of the node starting from its parent and ending with the root
+    pub fn ancestors(&self, node: &Node) -> Vec<&'a Node> {
+        let mut ancestors = vec![];
+        let mut parent = self.indexed(node).and_then(|indexed| indexed.parent);
+        while let Some(id) = parent {
+            ancestors.push(self.nodes[id].node);
+            parent = self.nodes[id].parent;
+        }
+        ancestors
+    }
+
+    /// Returns the innermost node whose `expression_l` covers the given byte offset.
+    ///
+    /// Children are not always located inside of their parents (like bodies of heredocs),
+    /// so all nodes are checked and the deepest one wins, among nodes of the same depth
+    /// the first one is picked.
+    pub fn node_at(&self, pos: usize) -> Option<&'a Node> {
+        let mut result: Option<&IndexedNode<'a>> = None;
+        for indexed in self.nodes.iter() {
+            let loc = indexed.node.expression();
+            if loc.start <= pos
+                && pos < loc.end
+                && result.is_none_or(|result| indexed.depth > result.depth)
+            {
+                result = Some(indexed);
+            }
+        }
+        result.map(|indexed| indexed.node)
+    }
+
+    fn indexed(&self, node: &Node) -> Option<&IndexedNode<'a>> {
+        let id = *self.ids.get(&(node as *const Node))?;
+        Some(&self.nodes[id])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::NodeIndex;
+    use crate::{loc::loc, Node, Parser};
+
+    fn parse(input: &[u8]) -> Box<Node> {
+        Parser::new(input).parse().unwrap().ast.unwrap()
+    }
+
+    #[test]
+    fn test_node_at() {
+        let input = b"def foo; bar(1, 2 + baz); end";
+        let ast = parse(input);
+        let index = NodeIndex::new(&ast);
+
+        let node_at = |pos: usize| index.node_at(pos).map(|node| node.inspect(0));
+
+        // `2`
+        assert_eq!(node_at(16), Some(r#"s(:int, "2")"#.to_string()));
+        // `baz`
+        assert_eq!(node_at(21), Some(r#"s(:send, nil, "baz")"#.to_string()));
+        // space between `2` and `+`, belongs to the binary operator
+        assert_eq!(index.node_at(17).map(Node::expression), Some(&loc!(16, 23)));
+        // `def` keyword
+        assert_eq!(index.node_at(0).map(|node| node.str_type()), Some("def"));
+        // past the end
+        assert_eq!(index.node_at(input.len()), None);
+    }
+
+    #[test]
+    fn test_parent_and_ancestors() {
+        let ast = parse(b"def foo; bar(1, 2 + baz); end");
+        let index = NodeIndex::new(&ast);
+
+        let baz = index.node_at(20).unwrap();
+        let ancestors: Vec<_> = index
+            .ancestors(baz)
+            .into_iter()
+            .map(|node| node.str_type())
+            .collect();
+        assert_eq!(ancestors, vec!["send", "send", "def"]);
+        assert_eq!(index.parent(baz).map(|node| node.str_type()), Some("send"));
+
+        assert_eq!(index.parent(&ast), None);
+        assert!(index.ancestors(&ast).is_empty());
+    }
+
+    #[test]
+    fn test_foreign_node() {
+        let ast = parse(b"foo");
+        let other = parse(b"foo");
+        let index = NodeIndex::new(&ast);
+        assert_eq!(index.parent(&other), None);
+        assert!(index.ancestors(&other).is_empty());
+    }
+}
@@ -6,6 +6,7 @@ pub trait InnerNode: std::fmt::Debug {
     fn expression(&self) -> &Loc;
     fn str_type(&self) -> &'static str;
     fn inspected_children(&self, indent: usize) -> Vec<String>;
+    fn child_nodes(&self) -> Vec<&Node>;
 
     fn inspect(&self, indent: usize) -> String {
         let indented = "  ".repeat(indent);
@@ -2,7 +2,13 @@ mod inner_node;
 mod node;
 mod node_enum;
 mod types;
+pub mod visitor;
 
 pub(crate) use inner_node::{InnerNode, InspectVec};
+pub use node::Walk;
 pub use node_enum::Node;
 pub use types::*;
+pub use visitor::{Fold, Visitor};
+
+#[cfg(test)]
+mod tests;
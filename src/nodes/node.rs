@@ -21,8 +21,74 @@ impl Node {
         self.inner_ref().str_type()
     }
 
+    /// Returns direct children of the node
+    pub(crate) fn child_nodes(&self) -> Vec<&Node> {
+        self.inner_ref().child_nodes()
+    }
+
+    /// Returns an iterator over direct children of the node, in the order of fields
+    pub fn children(&self) -> impl Iterator<Item = &Node> {
+        self.child_nodes().into_iter()
+    }
+
+    /// Returns a pre-order iterator over the node and all its descendants,
+    /// each node comes with its depth (0 for `self`)
+    pub fn walk(&self) -> Walk<'_> {
+        Walk {
+            stack: vec![(0, self)],
+        }
+    }
+
     /// Prints itself + location information
     pub fn print_with_locs(&self) {
         self.inner_ref().print_with_locs()
     }
 }
+
+/// Pre-order iterator over a tree of nodes, see `Node::walk`
+#[derive(Debug)]
+pub struct Walk<'a> {
+    stack: Vec<(usize, &'a Node)>,
+}
+
+impl<'a> Iterator for Walk<'a> {
+    type Item = (usize, &'a Node);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (depth, node) = self.stack.pop()?;
+        // reversed so that the first child is popped first
+        for child in node.child_nodes().into_iter().rev() {
+            self.stack.push((depth + 1, child));
+        }
+        Some((depth, node))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Parser;
+
+    #[test]
+    fn test_children() {
+        let ast = Parser::new(b"foo(1, 2) { bar }")
+            .parse()
+            .unwrap()
+            .ast
+            .unwrap();
+        let children: Vec<_> = ast.children().map(|node| node.str_type()).collect();
+        assert_eq!(children, vec!["send", "send"]);
+    }
+
+    #[test]
+    fn test_walk() {
+        let ast = Parser::new(b"foo(1, [2])").parse().unwrap().ast.unwrap();
+        let nodes: Vec<_> = ast
+            .walk()
+            .map(|(depth, node)| (depth, node.str_type()))
+            .collect();
+        assert_eq!(
+            nodes,
+            vec![(0, "send"), (1, "int"), (1, "array"), (2, "int")]
+        );
+    }
+}
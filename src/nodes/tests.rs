@@ -0,0 +1,112 @@
+use crate::{
+    nodes::{
+        visitor::{walk_def, walk_send},
+        Def, Int, Lvar, Nil, Send, Str,
+    },
+    Fold, Node, Parser, Visitor,
+};
+
+fn parse(input: &[u8]) -> Box<Node> {
+    Parser::new(input).parse().unwrap().ast.unwrap()
+}
+
+#[derive(Default)]
+struct CollectSends {
+    method_names: Vec<String>,
+}
+
+impl Visitor for CollectSends {
+    fn visit_send(&mut self, node: &Send) {
+        self.method_names.push(node.method_name.to_string_lossy());
+        walk_send(self, node)
+    }
+}
+
+#[test]
+fn test_visitor_recurses_into_children() {
+    let ast = parse(b"foo(bar(1), baz) if qux\nx = [a, { b: c }]");
+    let mut visitor = CollectSends::default();
+    visitor.visit(&ast);
+    // children are visited in the order of fields, `if` visits its condition first
+    assert_eq!(
+        visitor.method_names,
+        vec!["qux", "foo", "bar", "baz", "a", "c"]
+    );
+}
+
+#[derive(Default)]
+struct CountIntsOutsideOfDefs {
+    count: usize,
+    depth: usize,
+}
+
+impl Visitor for CountIntsOutsideOfDefs {
+    fn visit_int(&mut self, _node: &Int) {
+        if self.depth == 0 {
+            self.count += 1;
+        }
+    }
+
+    fn visit_def(&mut self, node: &Def) {
+        self.depth += 1;
+        walk_def(self, node);
+        self.depth -= 1;
+    }
+}
+
+#[test]
+fn test_visitor_overridden_method() {
+    let ast = parse(b"1; def foo; 2; 3; end; [4, 5]");
+    let mut visitor = CountIntsOutsideOfDefs::default();
+    visitor.visit(&ast);
+    assert_eq!(visitor.count, 3);
+}
+
+// Replaces `int` literals with strings and every local variable with `nil`
+struct Rewrite;
+
+impl Fold for Rewrite {
+    fn fold_int(&mut self, node: Int) -> Node {
+        Node::Str(Str {
+            begin_l: None,
+            end_l: None,
+            value: node.value,
+            expression_l: node.expression_l,
+        })
+    }
+
+    fn fold_lvar(&mut self, node: Lvar) -> Node {
+        Node::Nil(Nil {
+            expression_l: node.expression_l,
+        })
+    }
+}
+
+#[test]
+fn test_fold_replaces_nodes() {
+    let ast = parse(b"a = 1\nfoo(a, [2, a])");
+    let ast = Rewrite.fold(*ast);
+    assert_eq!(
+        ast.inspect(0),
+        r#"
+s(:begin,
+  s(:lvasgn, "a",
+    s(:str, "1")),
+  s(:send, nil, "foo",
+    s(:nil),
+    s(:array,
+      s(:str, "2"),
+      s(:nil))))
+        "#
+        .trim()
+    );
+}
+
+struct Identity;
+impl Fold for Identity {}
+
+#[test]
+fn test_fold_identity() {
+    let ast = parse(b"def foo(a, b = 1); a&.b { |x| x + b }; end");
+    assert_eq!(Identity.fold((*ast).clone()), *ast);
+}
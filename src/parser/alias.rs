@@ -1,5 +1,6 @@
 use crate::{
     builder::Builder,
+    error::{ParseError, ParseResult},
     parser::{base::Rule, BackRef, Fitem, Gvar},
     token::TokenKind,
     Node, Parser,
@@ -13,30 +14,30 @@ impl Rule for Alias {
         parser.current_token().is(TokenKind::kALIAS)
     }
 
-    fn parse(parser: &mut Parser) -> Self::Output {
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
         let alias_t = parser.take_token();
 
         let (lhs, rhs) = if Fitem::starts_now(parser) {
-            let lhs = Fitem::parse(parser);
-            let rhs = Fitem::parse(parser);
+            let lhs = Fitem::parse(parser)?;
+            let rhs = Fitem::parse(parser)?;
             (lhs, rhs)
         } else if parser.current_token().is(TokenKind::tGVAR) {
-            let lhs = Gvar::parse(parser);
+            let lhs = Gvar::parse(parser)?;
 
             let rhs = if Gvar::starts_now(parser) {
-                Gvar::parse(parser)
+                Gvar::parse(parser)?
             } else if BackRef::starts_now(parser) {
-                BackRef::parse(parser)
+                BackRef::parse(parser)?
             } else {
-                panic!("wring token type")
+                return Err(ParseError::unexpected_token(None, parser.current_token()));
             };
 
             (lhs, rhs)
         } else {
-            panic!("wrong token type")
+            return Err(ParseError::unexpected_token(None, parser.current_token()));
         };
 
-        Builder::alias(alias_t, lhs, rhs)
+        Ok(Builder::alias(alias_t, lhs, rhs))
     }
 }
 
@@ -1,8 +1,9 @@
 use crate::{
     builder::Builder,
+    error::{ParseError, ParseResult},
     parser::{
-        base::{at_most_one_is_true, ExactToken, Maybe1, Rule, SeparatedBy},
-        Value,
+        base::{any_is_true, ExactToken, Maybe1, Rule, SeparatedBy},
+        Assoc, Value,
     },
     Node, Parser, Token, TokenKind,
 };
@@ -15,8 +16,16 @@ impl Rule for ParenArgs {
         parser.current_token().is(TokenKind::tLPAREN)
     }
 
-    fn parse(parser: &mut Parser) -> Self::Output {
-        todo!()
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
+        let lparen_t = parser.take_token();
+        let args = parser
+            .with_do_block_allowed(true, Maybe1::<Args>::parse)?
+            .unwrap_or_default();
+        if parser.current_token().is(TokenKind::tCOMMA) {
+            parser.skip_token()
+        }
+        let rparen_t = parser.expect_token(TokenKind::tRPAREN)?;
+        Ok((lparen_t, args, rparen_t))
     }
 }
 
@@ -28,10 +37,10 @@ impl Rule for Args {
         Arglist::starts_now(parser)
     }
 
-    fn parse(parser: &mut Parser) -> Self::Output {
-        let arglist = Arglist::parse(parser);
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
+        let arglist = Arglist::parse(parser)?;
         // TODO: validate that it has exactly 1 element if it's a command
-        arglist
+        Ok(arglist)
     }
 }
 
@@ -43,19 +52,81 @@ impl Rule for CallArgs {
         true
     }
 
-    fn parse(parser: &mut Parser) -> Self::Output {
-        if Args::starts_now(parser) {
-            let args = Args::parse(parser);
-            (None, args, None)
-        } else if ParenArgs::starts_now(parser) {
-            let (lparen_t, args, rparen_t) = ParenArgs::parse(parser);
-            (Some(lparen_t), args, Some(rparen_t))
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
+        let seen_any_space = parser.lexer.space_before_current_token();
+        if parser.newline_before_current_token().is_some() {
+            // `foo\nbar` is two statements
+            Ok((None, vec![], None))
+        } else if parser.current_token().is(TokenKind::tLCURLY) {
+            // `foo {` is always a block, never a hash argument
+            Ok((None, vec![], None))
+        } else if parser.current_token().is(TokenKind::tLBRACK) && !seen_any_space {
+            // `foo[1]` is an index, `foo [1]` is a call with an array argument
+            Ok((None, vec![], None))
+        } else if parser.current_token().is(TokenKind::tCOLON2) && !seen_any_space {
+            // `Foo::Bar` is a const lookup, `foo ::Bar` is a call with a top-level const argument
+            Ok((None, vec![], None))
+        } else if ParenArgs::starts_now(parser) && !seen_any_space {
+            // `foo(1)` takes parenthesized args, `foo (1)` is a command with a parenthesized value
+            let (lparen_t, args, rparen_t) = ParenArgs::parse(parser)?;
+            Ok((Some(lparen_t), args, Some(rparen_t)))
+        } else if parser.current_token().is_one_of([
+            TokenKind::kIF,
+            TokenKind::kUNLESS,
+            TokenKind::kWHILE,
+            TokenKind::kUNTIL,
+            TokenKind::kRESCUE,
+        ]) {
+            // `foo if bar` is a modifier, never a command with a conditional argument
+            Ok((None, vec![], None))
+        } else if starts_with_binary_operator(parser) {
+            // `foo - 1`, `foo-1`, `foo * bar`, `foo / 2` are binary operators
+            Ok((None, vec![], None))
+        } else if Args::starts_now(parser) {
+            // `do` after command args belongs to the command itself
+            let args = parser.with_do_block_allowed(false, Args::parse)?;
+            Ok((None, args, None))
         } else {
-            (None, vec![], None)
+            Ok((None, vec![], None))
         }
     }
 }
 
+// Operators that can either start a command argument or be a binary operator:
+// like in MRI they start an argument (`foo -1`, `foo *args`, `foo /re/`)
+// only if there's a whitespace before them and no whitespace after
+fn starts_with_binary_operator(parser: &mut Parser) -> bool {
+    let token = parser.current_token();
+    if is_signed_number(parser, token) {
+        // there's always a digit after the sign
+        return !parser.lexer.space_before_current_token();
+    }
+    let is_ambiguous = token.is_one_of([
+        TokenKind::tMINUS,
+        TokenKind::tUMINUS_NUM,
+        TokenKind::tPLUS,
+        TokenKind::tSTAR,
+        TokenKind::tDSTAR,
+        TokenKind::tAMPER,
+        TokenKind::tDOT2,
+        TokenKind::tDOT3,
+        TokenKind::tDIVIDE,
+        TokenKind::tLSHFT,
+    ]);
+    is_ambiguous
+        && (!parser.lexer.space_before_current_token() || parser.lexer.lookahead_is_whitespace())
+}
+
+// `+1` is lexed as a single number token
+pub(crate) fn is_signed_number(parser: &Parser, token: Token) -> bool {
+    token.is_one_of([
+        TokenKind::tINTEGER,
+        TokenKind::tFLOAT,
+        TokenKind::tRATIONAL,
+        TokenKind::tIMAGINARY,
+    ]) && parser.buffer().byte_at(token.loc.start) == Some(b'+')
+}
+
 pub(crate) struct Mrhs;
 impl Rule for Mrhs {
     type Output = Vec<Node>;
@@ -64,11 +135,11 @@ impl Rule for Mrhs {
         Mrhs1::starts_now(parser)
     }
 
-    fn parse(parser: &mut Parser) -> Self::Output {
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
         type CommaT = ExactToken<{ TokenKind::tCOMMA as u8 }>;
         type R = SeparatedBy<Mrhs1, CommaT>;
-        let (items, _commas) = R::parse(parser);
-        items
+        let (items, _commas) = R::parse(parser)?;
+        Ok(items)
     }
 }
 
@@ -77,21 +148,21 @@ impl Rule for Mrhs1 {
     type Output = Box<Node>;
 
     fn starts_now(parser: &mut Parser) -> bool {
-        at_most_one_is_true([
+        any_is_true([
             parser.current_token().is(TokenKind::tSTAR),
             Value::starts_now(parser),
         ])
     }
 
-    fn parse(parser: &mut Parser) -> Self::Output {
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
         if parser.current_token().is(TokenKind::tSTAR) {
             let star_t = parser.take_token();
-            let value = Value::parse(parser);
-            Builder::splat(star_t, value)
+            let value = Value::parse_arg(parser)?;
+            Ok(Builder::splat(star_t, value))
         } else if Value::starts_now(parser) {
-            Value::parse(parser)
+            Value::parse_arg(parser)
         } else {
-            unreachable!()
+            Err(ParseError::unexpected_token(None, parser.current_token()))
         }
     }
 }
@@ -104,20 +175,52 @@ impl Rule for Arglist {
         Arg::starts_now(parser)
     }
 
-    fn parse(parser: &mut Parser) -> Self::Output {
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
         let (args, _commas) =
-            SeparatedBy::<Arg, ExactToken<{ TokenKind::tCOMMA as u8 }>>::parse(parser);
-        args
+            SeparatedBy::<Arg, ExactToken<{ TokenKind::tCOMMA as u8 }>>::parse(parser)?;
+
+        // TODO: There must be runtime validations:
+        // 1. pairs go after values
+        // 2. block pass is the last argument
+
+        Ok(group_pairs(parser, args))
     }
 }
 
+// Pairs and keyword splats are passed as a single braceless hash:
+// `foo(a, k: 1, **opts)` is `foo(a, { k: 1, **opts })`
+fn group_pairs(parser: &Parser, args: Vec<Node>) -> Vec<Node> {
+    let mut result = vec![];
+    let mut pairs = vec![];
+
+    for arg in args {
+        if matches!(
+            arg,
+            Node::Pair(_) | Node::Kwsplat(_) | Node::ForwardedKwrestarg(_)
+        ) {
+            pairs.push(arg);
+        } else {
+            if !pairs.is_empty() {
+                let pairs = std::mem::take(&mut pairs);
+                result.push(*Builder::associate(None, pairs, None, parser.diagnostics()));
+            }
+            result.push(arg);
+        }
+    }
+    if !pairs.is_empty() {
+        result.push(*Builder::associate(None, pairs, None, parser.diagnostics()));
+    }
+
+    result
+}
+
 struct Arg;
 impl Rule for Arg {
     type Output = Box<Node>;
 
     fn starts_now(parser: &mut Parser) -> bool {
         let token = parser.current_token();
-        at_most_one_is_true([
+        any_is_true([
             Value::starts_now(parser),
             token.is(TokenKind::tLABEL),
             token.is(TokenKind::tSTAR),
@@ -126,34 +229,326 @@ impl Rule for Arg {
         ])
     }
 
-    fn parse(parser: &mut Parser) -> Self::Output {
-        if Value::starts_now(parser) {
-            let value = Value::parse(parser);
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
+        if parser.current_token().is(TokenKind::tDOT3) && parser.lexer.lookahead_is_rparen() {
+            // foo(...)
+            let dots_t = parser.take_token();
+            Ok(Builder::forwarded_args(dots_t))
+        } else if Value::starts_now(parser) {
+            let value = Value::parse_arg(parser)?;
             if parser.current_token().is(TokenKind::tASSOC) {
+                // key => value
                 let key = value;
                 let assoc_t = parser.take_token();
-                let value = Value::parse(parser);
-                Builder::pair(key, assoc_t, value)
+                let value = Value::parse_arg(parser)?;
+                Ok(Builder::pair(key, assoc_t, value))
             } else if parser.current_token().is(TokenKind::tCOLON)
-            /* TODO: && key is a string */
+                && matches!(&*value, Node::Str(_) | Node::Dstr(_))
+                && parser.current_token().loc.start == value.expression().end
             {
+                // "key": value
                 let key = value;
                 let colon_t = parser.take_token();
-                let value = Value::parse(parser);
-                Builder::pair_quoted(key, colon_t, value)
+                let value = Value::parse_arg(parser)?;
+                Ok(Builder::pair_quoted(key, colon_t, value))
             } else {
-                value
+                Ok(value)
             }
-        } else if parser.current_token().is(TokenKind::tLABEL) {
-            todo!()
+        } else if parser
+            .current_token()
+            .is_one_of([TokenKind::tLABEL, TokenKind::tDSTAR])
+        {
+            // key: value, key: or **value
+            Assoc::parse(parser)
         } else if parser.current_token().is(TokenKind::tSTAR) {
-            todo!()
-        } else if parser.current_token().is(TokenKind::tDSTAR) {
-            todo!()
+            let star_t = parser.take_token();
+            if Value::starts_now(parser) {
+                // *value
+                let value = Value::parse_arg(parser)?;
+                Ok(Builder::splat(star_t, value))
+            } else {
+                // anonymous rest argument
+                Ok(Builder::nameless_splat(star_t))
+            }
         } else if parser.current_token().is(TokenKind::tAMPER) {
-            todo!()
+            let amper_t = parser.take_token();
+            let value = if Value::starts_now(parser) {
+                // &value
+                Some(Value::parse_arg(parser)?)
+            } else {
+                // anonymous block argument
+                None
+            };
+            Ok(Builder::block_pass(amper_t, value))
         } else {
-            unreachable!()
+            Err(ParseError::unexpected_token(None, parser.current_token()))
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{parser::Value, testing::assert_parses_rule};
+
+    #[test]
+    fn test_full_arglist() {
+        assert_parses_rule!(
+            Value,
+            b"foo(a, *rest, k: 1, \"s\": 2, **opts, &blk)",
+            r#"
+s(:send, nil, "foo",
+  s(:send, nil, "a"),
+  s(:splat,
+    s(:send, nil, "rest")),
+  s(:kwargs,
+    s(:pair,
+      s(:sym, "k"),
+      s(:int, "1")),
+    s(:pair,
+      s(:sym, "s"),
+      s(:int, "2")),
+    s(:kwsplat,
+      s(:send, nil, "opts"))),
+  s(:block_pass,
+    s(:send, nil, "blk")))
+            "#
+        )
+    }
+
+    #[test]
+    fn test_anonymous_forwarding() {
+        assert_parses_rule!(
+            Value,
+            b"foo(*, **, &)",
+            r#"
+s(:send, nil, "foo",
+  s(:splat),
+  s(:kwargs,
+    s(:forwarded_kwrestarg)),
+  s(:block_pass))
+            "#
+        )
+    }
+
+    #[test]
+    fn test_forwarded_args() {
+        assert_parses_rule!(
+            Value,
+            b"foo(a, ...)",
+            r#"
+s(:send, nil, "foo",
+  s(:send, nil, "a"),
+  s(:forwarded_args))
+            "#
+        )
+    }
+
+    #[test]
+    fn test_shorthand_hash_values() {
+        assert_parses_rule!(
+            Value,
+            b"foo(x:, Y:)",
+            r#"
+s(:send, nil, "foo",
+  s(:kwargs,
+    s(:pair,
+      s(:sym, "x"),
+      s(:send, nil, "x")),
+    s(:pair,
+      s(:sym, "Y"),
+      s(:const, nil, "Y"))))
+            "#
+        )
+    }
+
+    #[test]
+    fn test_command_kwargs() {
+        assert_parses_rule!(
+            Value,
+            b"foo 1, k => 2",
+            r#"
+s(:send, nil, "foo",
+  s(:int, "1"),
+  s(:kwargs,
+    s(:pair,
+      s(:send, nil, "k"),
+      s(:int, "2"))))
+            "#
+        )
+    }
+
+    #[test]
+    fn test_braced_hash_is_not_kwargs() {
+        assert_parses_rule!(
+            Value,
+            b"foo(1, { k: 2 })",
+            r#"
+s(:send, nil, "foo",
+  s(:int, "1"),
+  s(:hash,
+    s(:pair,
+      s(:sym, "k"),
+      s(:int, "2"))))
+            "#
+        )
+    }
+
+    #[test]
+    fn test_spaced_binary_operators_after_command() {
+        assert_parses_rule!(
+            Value,
+            b"a - b",
+            r#"
+s(:send,
+  s(:send, nil, "a"), "-",
+  s(:send, nil, "b"))
+            "#
+        );
+        assert_parses_rule!(
+            Value,
+            b"A - 1",
+            r#"
+s(:send,
+  s(:const, nil, "A"), "-",
+  s(:int, "1"))
+            "#
+        );
+        assert_parses_rule!(
+            Value,
+            b"a * b ** c",
+            r#"
+s(:send,
+  s(:send, nil, "a"), "*",
+  s(:send,
+    s(:send, nil, "b"), "**",
+    s(:send, nil, "c")))
+            "#
+        );
+        assert_parses_rule!(
+            Value,
+            b"foo.bar & baz",
+            r#"
+s(:send,
+  s(:send,
+    s(:send, nil, "foo"), "bar"), "&",
+  s(:send, nil, "baz"))
+            "#
+        );
+        assert_parses_rule!(
+            Value,
+            b"x.size / 2",
+            r#"
+s(:send,
+  s(:send,
+    s(:send, nil, "x"), "size"), "/",
+  s(:int, "2"))
+            "#
+        );
+    }
+
+    #[test]
+    fn test_unspaced_binary_operators_after_command() {
+        assert_parses_rule!(
+            Value,
+            b"a..b",
+            r#"
+s(:irange,
+  s(:send, nil, "a"),
+  s(:send, nil, "b"))
+            "#
+        );
+        assert_parses_rule!(
+            Value,
+            b"a.b*2",
+            r#"
+s(:send,
+  s(:send,
+    s(:send, nil, "a"), "b"), "*",
+  s(:int, "2"))
+            "#
+        );
+    }
+
+    #[test]
+    fn test_ambiguous_first_argument() {
+        assert_parses_rule!(
+            Value,
+            b"a -b, *c, **d, &e",
+            r#"
+s(:send, nil, "a",
+  s(:send,
+    s(:send, nil, "b"), "-@"),
+  s(:splat,
+    s(:send, nil, "c")),
+  s(:kwargs,
+    s(:kwsplat,
+      s(:send, nil, "d"))),
+  s(:block_pass,
+    s(:send, nil, "e")))
+            "#
+        );
+        assert_parses_rule!(
+            Value,
+            b"A /b/",
+            r#"
+s(:send, nil, "A",
+  s(:regexp,
+    s(:str, "b"),
+    s(:regopt)))
+            "#
+        );
+        assert_parses_rule!(
+            Value,
+            b"a ..b",
+            r#"
+s(:send, nil, "a",
+  s(:irange, nil,
+    s(:send, nil, "b")))
+            "#
+        );
+    }
+
+    #[test]
+    fn test_colon2_after_command() {
+        assert_parses_rule!(
+            Value,
+            b"Foo::Bar",
+            r#"
+s(:const,
+  s(:const, nil, "Foo"), "Bar")
+            "#
+        );
+        assert_parses_rule!(
+            Value,
+            b"foo ::Bar",
+            r#"
+s(:send, nil, "foo",
+  s(:const,
+    s(:cbase), "Bar"))
+            "#
+        );
+    }
+
+    #[test]
+    fn test_modifier_after_command() {
+        assert_parses_rule!(
+            Value,
+            b"foo if bar",
+            r#"
+s(:if,
+  s(:send, nil, "bar"),
+  s(:send, nil, "foo"), nil)
+            "#
+        );
+        assert_parses_rule!(
+            Value,
+            b"foo baz while bar",
+            r#"
+s(:while,
+  s(:send, nil, "bar"),
+  s(:send, nil, "foo",
+    s(:send, nil, "baz")))
+            "#
+        );
+    }
+}
@@ -1,7 +1,8 @@
 use crate::{
     builder::Builder,
+    error::{ParseError, ParseResult},
     parser::{
-        base::{at_most_one_is_true, ExactToken, Maybe1, Rule, SeparatedBy},
+        base::{any_is_true, ExactToken, Maybe1, Rule, SeparatedBy},
         Value,
     },
     token::TokenKind,
@@ -16,16 +17,12 @@ impl Rule for Array {
         parser.current_token().is(TokenKind::tLBRACK)
     }
 
-    fn parse(parser: &mut Parser) -> Self::Output {
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
         let lbrack_t = parser.take_token();
-        let elements = Items::parse(parser);
-        let rbrack_t = if parser.current_token().is(TokenKind::tRBRACK) {
-            parser.take_token()
-        } else {
-            panic!("wrong toke type")
-        };
+        let elements = parser.with_do_block_allowed(true, Items::parse)?;
+        let rbrack_t = parser.expect_token(TokenKind::tRBRACK)?;
 
-        Builder::array(Some(lbrack_t), elements, Some(rbrack_t))
+        Ok(Builder::array(Some(lbrack_t), elements, Some(rbrack_t)))
     }
 }
 #[test]
@@ -50,17 +47,17 @@ impl Rule for Items {
         !parser.current_token().is(TokenKind::tRPAREN)
     }
 
-    fn parse(parser: &mut Parser) -> Self::Output {
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
         type CommaT = ExactToken<{ TokenKind::tCOMMA as u8 }>;
 
-        let (items, _commas) = SeparatedBy::<Item, CommaT>::parse(parser);
-        let _trailing_comma = Maybe1::<CommaT>::parse(parser);
+        let (items, _commas) = SeparatedBy::<Item, CommaT>::parse(parser)?;
+        let _trailing_comma = Maybe1::<CommaT>::parse(parser)?;
 
         // TODO: There must be runtime validations:
         // 1. pairs go after values
         // 2. ',' requires non-empty list of items
 
-        items
+        Ok(items)
     }
 }
 
@@ -69,7 +66,7 @@ impl Rule for Item {
     type Output = Box<Node>;
 
     fn starts_now(parser: &mut Parser) -> bool {
-        at_most_one_is_true([
+        any_is_true([
             SplatElement::starts_now(parser),
             KeywordSplat::starts_now(parser),
             LabelToValuePair::starts_now(parser),
@@ -78,7 +75,7 @@ impl Rule for Item {
         ])
     }
 
-    fn parse(parser: &mut Parser) -> Self::Output {
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
         if SplatElement::starts_now(parser) {
             SplatElement::parse(parser)
         } else if KeywordSplat::starts_now(parser) {
@@ -86,25 +83,25 @@ impl Rule for Item {
         } else if LabelToValuePair::starts_now(parser) {
             LabelToValuePair::parse(parser)
         } else if Value::starts_now(parser) {
-            let value = Value::parse(parser);
+            let value = Value::parse_arg(parser)?;
             if matches!(&*value, Node::Str(_)) && parser.current_token().is(TokenKind::tCOLON) {
                 // "foo": value
                 let key = value;
                 let colon_t = parser.take_token();
-                let value = Value::parse(parser);
-                Builder::pair_quoted(key, colon_t, value)
+                let value = Value::parse_arg(parser)?;
+                Ok(Builder::pair_quoted(key, colon_t, value))
             } else if parser.current_token().is(TokenKind::tASSOC) {
                 // pair `value => value`
                 let key = value;
                 let assoc_t = parser.take_token();
-                let value = Value::parse(parser);
-                Builder::pair(key, assoc_t, value)
+                let value = Value::parse_arg(parser)?;
+                Ok(Builder::pair(key, assoc_t, value))
             } else {
                 // just value
-                value
+                Ok(value)
             }
         } else {
-            unreachable!()
+            Err(ParseError::unexpected_token(None, parser.current_token()))
         }
     }
 }
@@ -145,10 +142,10 @@ impl Rule for SplatElement {
         parser.current_token().is(TokenKind::tSTAR)
     }
 
-    fn parse(parser: &mut Parser) -> Self::Output {
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
         let star_t = parser.take_token();
-        let value = Value::parse(parser);
-        Builder::splat(star_t, value)
+        let value = Value::parse(parser)?;
+        Ok(Builder::splat(star_t, value))
     }
 }
 #[test]
@@ -171,13 +168,20 @@ impl Rule for LabelToValuePair {
         parser.current_token().is(TokenKind::tLABEL)
     }
 
-    fn parse(parser: &mut Parser) -> Self::Output {
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
         let key_t = parser.take_token();
-        let value = Maybe1::<Value>::parse(parser);
+        let value = Maybe1::<Value>::parse(parser)?;
         if let Some(value) = value {
-            Builder::pair_keyword(key_t, value, parser.buffer())
+            Ok(Builder::pair_keyword(key_t, value, parser.buffer()))
         } else {
-            Builder::pair_label(key_t, parser.buffer())
+            Ok(Builder::pair_label(
+                key_t,
+                parser.buffer(),
+                parser.static_env(),
+                parser.current_arg_stack(),
+                parser.max_numparam_stack(),
+                parser.diagnostics(),
+            ))
         }
     }
 }
@@ -201,7 +205,7 @@ fn test_label_without_value() {
         r#"
 s(:pair,
   s(:sym, "foo"),
-  s(:lvar, "foo"))
+  s(:send, nil, "foo"))
         "#
     )
 }
@@ -214,10 +218,10 @@ impl Rule for KeywordSplat {
         parser.current_token().is(TokenKind::tDSTAR)
     }
 
-    fn parse(parser: &mut Parser) -> Self::Output {
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
         let dstar_t = parser.take_token();
-        let value = Value::parse(parser);
-        Builder::kwsplat(dstar_t, value)
+        let value = Value::parse(parser)?;
+        Ok(Builder::kwsplat(dstar_t, value))
     }
 }
 #[test]
@@ -0,0 +1,13 @@
+// Returns true if any of the rules that are checked together starts now.
+// They are expected to be mutually exclusive, but if some input makes several of them match
+// it's not a reason to crash: callers try them in the same order, so the first matching rule wins.
+pub(crate) fn any_is_true<const N: usize>(values: [bool; N]) -> bool {
+    values.into_iter().any(|value| value)
+}
+
+#[test]
+fn test_any_is_true() {
+    assert!(!any_is_true([false, false]));
+    assert!(any_is_true([false, true]));
+    assert!(any_is_true([true, true]));
+}
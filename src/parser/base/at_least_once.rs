@@ -1,4 +1,5 @@
 use crate::{
+    error::ParseResult,
     parser::base::{Rule, Unbox},
     Parser,
 };
@@ -22,10 +23,10 @@ where
         R::starts_now(parser)
     }
 
-    fn parse(parser: &mut Parser) -> Self::Output {
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
         let mut values = vec![];
 
-        let value = R::parse(parser).unbox();
+        let value = R::parse(parser)?.unbox();
         values.push(value);
 
         loop {
@@ -33,10 +34,10 @@ where
                 break;
             }
 
-            let value = R::parse(parser).unbox();
+            let value = R::parse(parser)?.unbox();
             values.push(value);
         }
 
-        values
+        Ok(values)
     }
 }
@@ -1,4 +1,4 @@
-use crate::{parser::base::Rule, Parser, Token};
+use crate::{error::ParseResult, parser::base::Rule, Parser, Token};
 
 pub(crate) struct ExactToken<const TOKEN_KIND: u8>;
 
@@ -11,8 +11,8 @@ impl<const TOKEN_KIND: u8> Rule for ExactToken<TOKEN_KIND> {
             .is(unsafe { std::mem::transmute(TOKEN_KIND) })
     }
 
-    fn parse(parser: &mut Parser) -> Self::Output {
-        parser.take_token()
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
+        Ok(parser.take_token())
     }
 }
 
@@ -23,5 +23,8 @@ fn test_exact_token() {
 
     let mut parser = Parser::new(b"42");
     assert!(IntToken::starts_now(&mut parser));
-    assert_eq!(IntToken::parse(&mut parser), token!(tINTEGER, loc!(0, 2)));
+    assert_eq!(
+        IntToken::parse(&mut parser),
+        Ok(token!(tINTEGER, loc!(0, 2)))
+    );
 }
@@ -1,4 +1,4 @@
-use crate::{parser::base::Rule, Parser};
+use crate::{error::ParseResult, parser::base::Rule, Parser};
 
 pub(crate) struct Maybe1<R>
 where
@@ -17,11 +17,11 @@ where
         true
     }
 
-    fn parse(parser: &mut Parser) -> Self::Output {
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
         if !R::starts_now(parser) {
-            return None;
+            return Ok(None);
         }
 
-        Some(R::parse(parser))
+        Ok(Some(R::parse(parser)?))
     }
 }
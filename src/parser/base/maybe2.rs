@@ -1,4 +1,4 @@
-use crate::{parser::base::Rule, Parser};
+use crate::{error::ParseResult, parser::base::Rule, Parser};
 
 pub(crate) struct Maybe2<R1, R2>
 where
@@ -20,14 +20,14 @@ where
         true
     }
 
-    fn parse(parser: &mut Parser) -> Self::Output {
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
         if !R1::starts_now(parser) {
-            return None;
+            return Ok(None);
         }
 
-        let v1 = R1::parse(parser);
-        let v2 = R2::parse(parser);
+        let v1 = R1::parse(parser)?;
+        let v2 = R2::parse(parser)?;
 
-        Some((v1, v2))
+        Ok(Some((v1, v2)))
     }
 }
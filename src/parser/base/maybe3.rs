@@ -1,4 +1,4 @@
-use crate::{parser::base::Rule, Parser};
+use crate::{error::ParseResult, parser::base::Rule, Parser};
 
 pub(crate) struct Maybe3<R1, R2, R3>
 where
@@ -23,15 +23,15 @@ where
         R1::starts_now(parser)
     }
 
-    fn parse(parser: &mut Parser) -> Self::Output {
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
         if !R1::starts_now(parser) {
-            return None;
+            return Ok(None);
         }
 
-        let v1 = R1::parse(parser);
-        let v2 = R2::parse(parser);
-        let v3 = R3::parse(parser);
+        let v1 = R1::parse(parser)?;
+        let v2 = R2::parse(parser)?;
+        let v3 = R3::parse(parser)?;
 
-        Some((v1, v2, v3))
+        Ok(Some((v1, v2, v3)))
     }
 }
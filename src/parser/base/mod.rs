@@ -28,5 +28,5 @@ pub(crate) use exact_token::ExactToken;
 mod unbox;
 pub(crate) use unbox::Unbox;
 
-mod at_most_one_is_true;
-pub(crate) use at_most_one_is_true::at_most_one_is_true;
+mod any_is_true;
+pub(crate) use any_is_true::any_is_true;
@@ -1,4 +1,5 @@
 use crate::{
+    error::ParseResult,
     parser::base::{Rule, Unbox},
     Parser,
 };
@@ -22,7 +23,7 @@ where
         true
     }
 
-    fn parse(parser: &mut Parser) -> Self::Output {
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
         let mut values = vec![];
 
         loop {
@@ -30,9 +31,9 @@ where
                 break;
             }
 
-            let value = R::parse(parser).unbox();
+            let value = R::parse(parser)?.unbox();
             values.push(value);
         }
-        values
+        Ok(values)
     }
 }
@@ -1,4 +1,4 @@
-use crate::{parser::base::Rule, Parser};
+use crate::{error::ParseResult, parser::base::Rule, Parser};
 
 pub(crate) struct Repeat2<R1, R2>
 where
@@ -20,7 +20,7 @@ where
         R1::starts_now(parser)
     }
 
-    fn parse(parser: &mut Parser) -> Self::Output {
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
         let mut v1s = vec![];
         let mut v2s = vec![];
 
@@ -29,17 +29,17 @@ where
                 break;
             }
 
-            let v1 = R1::parse(parser);
+            let v1 = R1::parse(parser)?;
             v1s.push(v1);
 
             if !R2::starts_now(parser) {
                 break;
             }
 
-            let v2 = R2::parse(parser);
+            let v2 = R2::parse(parser)?;
             v2s.push(v2);
         }
 
-        (v1s, v2s)
+        Ok((v1s, v2s))
     }
 }
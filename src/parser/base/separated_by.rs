@@ -1,4 +1,5 @@
 use crate::{
+    error::ParseResult,
     parser::base::{Maybe1, Rule, Unbox},
     Parser,
 };
@@ -30,27 +31,27 @@ where
         true
     }
 
-    fn parse(parser: &mut Parser) -> Self::Output {
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
         let mut items = vec![];
         let mut seps = vec![];
 
-        match Maybe1::<Item>::parse(parser) {
+        match Maybe1::<Item>::parse(parser)? {
             Some(item) => items.push(item.unbox()),
-            None => return (items, seps),
+            None => return Ok((items, seps)),
         }
 
         loop {
-            match Maybe1::<Sep>::parse(parser) {
+            match Maybe1::<Sep>::parse(parser)? {
                 Some(sep) => seps.push(sep.unbox()),
                 None => break,
             }
 
-            match Maybe1::<Item>::parse(parser) {
+            match Maybe1::<Item>::parse(parser)? {
                 Some(item) => items.push(item.unbox()),
                 None => break,
             }
         }
 
-        (items, seps)
+        Ok((items, seps))
     }
 }
@@ -1,5 +1,10 @@
 use crate::{
-    parser::base::{ExactToken, Maybe1, Maybe2, Rule, SeparatedBy},
+    builder::ArgsType,
+    error::{ParseError, ParseResult},
+    parser::{
+        base::{Maybe1, Rule},
+        BlockParams, Bodystmt, Compstmt,
+    },
     Node, Parser, Token, TokenKind,
 };
 
@@ -7,39 +12,262 @@ pub(crate) type MaybeBlock = Maybe1<Block>;
 
 pub(crate) struct Block;
 impl Rule for Block {
-    type Output = (Token, Option<Box<Node>>, Box<Node>, Token);
+    type Output = (Token, ArgsType, Option<Box<Node>>, Token);
 
     fn starts_now(parser: &mut Parser) -> bool {
         BraceBlock::starts_now(parser) || DoBlock::starts_now(parser)
     }
 
-    fn parse(parser: &mut Parser) -> Self::Output {
-        todo!()
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
+        if BraceBlock::starts_now(parser) {
+            BraceBlock::parse(parser)
+        } else if DoBlock::starts_now(parser) {
+            DoBlock::parse(parser)
+        } else {
+            Err(ParseError::unexpected_token(None, parser.current_token()))
+        }
     }
 }
 
 struct DoBlock;
 impl Rule for DoBlock {
-    type Output = (Token, Box<Node>, Token);
+    type Output = (Token, ArgsType, Option<Box<Node>>, Token);
 
     fn starts_now(parser: &mut Parser) -> bool {
-        parser.current_token().is(TokenKind::kDO)
+        parser.current_token().is(TokenKind::kDO) && parser.do_block_allowed()
     }
 
-    fn parse(parser: &mut Parser) -> Self::Output {
-        todo!()
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
+        let begin_t = parser.take_token();
+        let (args, body) = parse_block_scope(parser, Bodystmt::parse)?;
+        let end_t = parser.expect_token(TokenKind::kEND)?;
+        Ok((begin_t, args, body, end_t))
     }
 }
 
 pub(crate) struct BraceBlock;
 impl Rule for BraceBlock {
-    type Output = Box<Node>;
+    type Output = (Token, ArgsType, Option<Box<Node>>, Token);
 
     fn starts_now(parser: &mut Parser) -> bool {
         parser.current_token().is(TokenKind::tLCURLY)
     }
 
-    fn parse(parser: &mut Parser) -> Self::Output {
-        todo!()
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
+        let begin_t = parser.take_token();
+        let (args, body) = parse_block_scope(parser, Compstmt::parse)?;
+        let end_t = parser.expect_token(TokenKind::tRCURLY)?;
+        Ok((begin_t, args, body, end_t))
     }
 }
+
+// Parses params and body of the block in a new dynamic scope,
+// a block without params that uses `_1`..`_9` (or `it`) becomes a numblock
+fn parse_block_scope(
+    parser: &mut Parser,
+    parse_body: fn(&mut Parser) -> ParseResult<Option<Box<Node>>>,
+) -> ParseResult<(ArgsType, Option<Box<Node>>)> {
+    parser.static_env().extend_dynamic();
+    parser.max_numparam_stack().push_dynamic();
+
+    let result = parse_params_and_body(parser, parse_body);
+
+    let numargs = parser.max_numparam_stack().pop();
+    parser.static_env().unextend();
+
+    let (args, body) = result?;
+    match (args, numargs) {
+        (None, Some(numargs)) => Ok((ArgsType::Numargs(numargs), body)),
+        (args, _) => Ok((ArgsType::Args(args), body)),
+    }
+}
+
+// (args, body)
+type ParamsAndBody = (Option<Box<Node>>, Option<Box<Node>>);
+
+fn parse_params_and_body(
+    parser: &mut Parser,
+    parse_body: fn(&mut Parser) -> ParseResult<Option<Box<Node>>>,
+) -> ParseResult<ParamsAndBody> {
+    let args = if BlockParams::starts_now(parser) {
+        parser.max_numparam_stack().set_has_ordinary_params();
+        Some(BlockParams::parse(parser)?)
+    } else {
+        None
+    };
+    let body = parse_body(parser)?;
+    Ok((args, body))
+}
+
+#[cfg(test)]
+fn parse_call_with_block(input: &[u8]) -> (String, Vec<crate::Diagnostic>) {
+    use crate::parser::Value;
+
+    let mut parser = Parser::new(input);
+    let ast = Value::parse(&mut parser).unwrap();
+    (ast.inspect(0), parser.diagnostics().take_inner())
+}
+
+#[test]
+fn test_brace_block() {
+    assert_eq!(
+        parse_call_with_block(b"foo { |x| x }").0,
+        r#"
+s(:block,
+  s(:send, nil, "foo"),
+  s(:args,
+    s(:procarg0,
+      s(:arg, "x"))),
+  s(:lvar, "x"))
+        "#
+        .trim()
+    )
+}
+
+#[test]
+fn test_do_block() {
+    assert_eq!(
+        parse_call_with_block(b"foo do |a, (b, c), *d, k:, &blk; e|\n  a\nend").0,
+        r#"
+s(:block,
+  s(:send, nil, "foo"),
+  s(:args,
+    s(:arg, "a"),
+    s(:mlhs,
+      s(:arg, "b"),
+      s(:arg, "c")),
+    s(:restarg, "d"),
+    s(:kwarg, "k"),
+    s(:blockarg, "blk"),
+    s(:shadowarg, "e")),
+  s(:lvar, "a"))
+        "#
+        .trim()
+    )
+}
+
+#[test]
+fn test_block_args_are_not_visible_outside() {
+    assert_eq!(
+        parse_call_with_block(b"[foo { |x| }, x]").0,
+        r#"
+s(:array,
+  s(:block,
+    s(:send, nil, "foo"),
+    s(:args,
+      s(:procarg0,
+        s(:arg, "x"))), nil),
+  s(:send, nil, "x"))
+        "#
+        .trim()
+    )
+}
+
+#[test]
+fn test_numblock() {
+    assert_eq!(
+        parse_call_with_block(b"foo { _1 + _2 }").0,
+        r#"
+s(:numblock,
+  s(:send, nil, "foo"), 2,
+  s(:send,
+    s(:lvar, "_1"), "+",
+    s(:lvar, "_2")))
+        "#
+        .trim()
+    )
+}
+
+#[test]
+fn test_it_block() {
+    assert_eq!(
+        parse_call_with_block(b"foo { it }").0,
+        r#"
+s(:numblock,
+  s(:send, nil, "foo"), 1,
+  s(:lvar, "it"))
+        "#
+        .trim()
+    )
+}
+
+#[test]
+fn test_it_with_ordinary_params() {
+    assert_eq!(
+        parse_call_with_block(b"foo { |x| it }").0,
+        r#"
+s(:block,
+  s(:send, nil, "foo"),
+  s(:args,
+    s(:procarg0,
+      s(:arg, "x"))),
+  s(:send, nil, "it"))
+        "#
+        .trim()
+    )
+}
+
+#[test]
+fn test_numparam_with_ordinary_params() {
+    use crate::{loc::loc, Diagnostic, DiagnosticLevel, DiagnosticMessage};
+
+    assert_eq!(
+        parse_call_with_block(b"foo { |x| _1 }").1,
+        vec![Diagnostic {
+            level: DiagnosticLevel::Error,
+            message: DiagnosticMessage::OrdinaryParamDefined,
+            loc: loc!(10, 12)
+        }]
+    )
+}
+
+#[test]
+fn test_numparam_in_nested_blocks() {
+    use crate::{loc::loc, Diagnostic, DiagnosticLevel, DiagnosticMessage};
+
+    assert_eq!(
+        parse_call_with_block(b"foo { _1; bar { _2 } }").1,
+        vec![Diagnostic {
+            level: DiagnosticLevel::Error,
+            message: DiagnosticMessage::NumparamUsedInOuterScope,
+            loc: loc!(16, 18)
+        }]
+    );
+    // the same numbered parameter is not a local variable of the outer block
+    assert_eq!(
+        parse_call_with_block(b"foo { _1; bar { _1 } }").1,
+        vec![Diagnostic {
+            level: DiagnosticLevel::Error,
+            message: DiagnosticMessage::NumparamUsedInOuterScope,
+            loc: loc!(16, 18)
+        }]
+    )
+}
+
+#[test]
+fn test_brace_block_binds_to_the_last_command_arg() {
+    assert_eq!(
+        parse_call_with_block(b"foo bar { 1 }").0,
+        r#"
+s(:send, nil, "foo",
+  s(:block,
+    s(:send, nil, "bar"), nil,
+    s(:int, "1")))
+        "#
+        .trim()
+    )
+}
+
+#[test]
+fn test_do_block_binds_to_the_command() {
+    assert_eq!(
+        parse_call_with_block(b"foo bar do 1 end").0,
+        r#"
+s(:block,
+  s(:send, nil, "foo",
+    s(:send, nil, "bar")), nil,
+  s(:int, "1"))
+        "#
+        .trim()
+    )
+}
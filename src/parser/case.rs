@@ -1,7 +1,11 @@
 use crate::{
     builder::Builder,
-    parser::base::Rule,
-    token::{Token, TokenKind},
+    error::{ParseError, ParseResult},
+    parser::{
+        base::{any_is_true, AtLeastOnce, ExactToken, Rule, SeparatedBy},
+        Compstmt, OptElse, OptTerms, PCaseBody, Then, Value,
+    },
+    token::TokenKind,
     Node, Parser,
 };
 
@@ -13,7 +17,193 @@ impl Rule for Case {
         parser.current_token().is(TokenKind::kCASE)
     }
 
-    fn parse(parser: &mut Parser) -> Self::Output {
-        todo!()
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
+        let case_t = parser.take_token();
+        let expr = if Value::starts_now(parser) {
+            Some(Value::parse_expr_value(parser)?)
+        } else {
+            None
+        };
+        OptTerms::parse(parser)?;
+
+        if PCaseBody::starts_now(parser) {
+            let expr = match expr {
+                Some(expr) => expr,
+                // `case in` requires a value to match
+                None => return Err(ParseError::unexpected_token(None, parser.current_token())),
+            };
+            let in_bodies = AtLeastOnce::<PCaseBody>::parse(parser)?;
+            let (else_t, else_body) = match OptElse::parse(parser)? {
+                Some((else_t, else_body)) => (Some(else_t), else_body),
+                None => (None, None),
+            };
+            let end_t = parser.expect_token(TokenKind::kEND)?;
+            Ok(Builder::case_match(
+                case_t, expr, in_bodies, else_t, else_body, end_t,
+            ))
+        } else {
+            let when_bodies = AtLeastOnce::<CaseBody>::parse(parser)?;
+            let (else_t, else_body) = match OptElse::parse(parser)? {
+                Some((else_t, else_body)) => (Some(else_t), else_body),
+                None => (None, None),
+            };
+            let end_t = parser.expect_token(TokenKind::kEND)?;
+            Ok(Builder::case(
+                case_t,
+                expr,
+                when_bodies,
+                else_t,
+                else_body,
+                end_t,
+            ))
+        }
     }
 }
+#[test]
+fn test_case_when() {
+    crate::testing::assert_parses_rule!(
+        Case,
+        b"case foo\nwhen 1, *bar then 2\nwhen 3\n  4\nelse 5\nend",
+        r#"
+s(:case,
+  s(:send, nil, "foo"),
+  s(:when,
+    s(:int, "1"),
+    s(:splat,
+      s(:send, nil, "bar")),
+    s(:int, "2")),
+  s(:when,
+    s(:int, "3"),
+    s(:int, "4")),
+  s(:int, "5"))
+        "#
+    )
+}
+#[test]
+fn test_case_without_subject() {
+    crate::testing::assert_parses_rule!(
+        Case,
+        b"case\nwhen a; 1\nwhen b\nend",
+        r#"
+s(:case, nil,
+  s(:when,
+    s(:send, nil, "a"),
+    s(:int, "1")),
+  s(:when,
+    s(:send, nil, "b"), nil), nil)
+        "#
+    )
+}
+#[test]
+fn test_case_when_keyword_locations() {
+    use crate::{loc::loc, Node};
+
+    let mut parser = Parser::new(b"case x when 1 then 2 else 3 end");
+    let node = Case::parse(&mut parser).unwrap();
+    let Node::Case(case) = *node else {
+        panic!("expected case")
+    };
+    assert_eq!(case.keyword_l, loc!(0, 4));
+    assert_eq!(case.else_l, Some(loc!(21, 25)));
+    assert_eq!(case.end_l, loc!(28, 31));
+    assert_eq!(case.expression_l, loc!(0, 31));
+
+    let Node::When(when) = &case.when_bodies[0] else {
+        panic!("expected when")
+    };
+    assert_eq!(when.keyword_l, loc!(7, 11));
+    assert_eq!(when.begin_l, loc!(14, 18));
+    assert_eq!(when.expression_l, loc!(7, 20));
+}
+
+// `when a, *b then body`
+struct CaseBody;
+impl Rule for CaseBody {
+    type Output = Box<Node>;
+
+    fn starts_now(parser: &mut Parser) -> bool {
+        parser.current_token().is(TokenKind::kWHEN)
+    }
+
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
+        type CommaT = ExactToken<{ TokenKind::tCOMMA as u8 }>;
+
+        let when_t = parser.take_token();
+        let (patterns, _commas) = SeparatedBy::<CaseArg, CommaT>::parse(parser)?;
+        let then_t = match Then::parse(parser)? {
+            Some(then_t) => then_t,
+            None => return Err(ParseError::unexpected_token(None, parser.current_token())),
+        };
+        let body = Compstmt::parse(parser)?;
+        Ok(Builder::when(when_t, patterns, then_t, body))
+    }
+}
+
+struct CaseArg;
+impl Rule for CaseArg {
+    type Output = Box<Node>;
+
+    fn starts_now(parser: &mut Parser) -> bool {
+        any_is_true([
+            parser.current_token().is(TokenKind::tSTAR),
+            Value::starts_now(parser),
+        ])
+    }
+
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
+        if parser.current_token().is(TokenKind::tSTAR) {
+            let star_t = parser.take_token();
+            let value = Value::parse_arg(parser)?;
+            Ok(Builder::splat(star_t, value))
+        } else if Value::starts_now(parser) {
+            Value::parse_arg(parser)
+        } else {
+            Err(ParseError::unexpected_token(None, parser.current_token()))
+        }
+    }
+}
+#[test]
+fn test_case_in() {
+    crate::testing::assert_parses_rule!(
+        Case,
+        b"case foo; in 1 then 2; in [a, *] if a; 3; else 4; end",
+        r#"
+s(:case_match,
+  s(:send, nil, "foo"),
+  s(:in_pattern,
+    s(:int, "1"), nil,
+    s(:int, "2")),
+  s(:in_pattern,
+    s(:array_pattern,
+      s(:match_var, "a"),
+      s(:match_rest)),
+    s(:if_guard,
+      s(:lvar, "a")),
+    s(:int, "3")),
+  s(:int, "4"))
+        "#
+    )
+}
+#[test]
+fn test_case_in_multiline() {
+    crate::testing::assert_parses_rule!(
+        Case,
+        b"case foo\nin {x:}\n  x\nin Integer | Float => n\n  n\nelse\nend",
+        r#"
+s(:case_match,
+  s(:send, nil, "foo"),
+  s(:in_pattern,
+    s(:hash_pattern,
+      s(:match_var, "x")), nil,
+    s(:lvar, "x")),
+  s(:in_pattern,
+    s(:match_as,
+      s(:match_alt,
+        s(:const, nil, "Integer"),
+        s(:const, nil, "Float")),
+      s(:match_var, "n")), nil,
+    s(:lvar, "n")),
+  s(:empty_else))
+        "#
+    )
+}
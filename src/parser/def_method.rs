@@ -1,7 +1,8 @@
 use crate::{
     builder::Builder,
+    error::{ParseError, ParseErrorKind, ParseResult},
     parser::{
-        base::{at_most_one_is_true, ExactToken, Maybe1, Maybe3, Rule},
+        base::{any_is_true, ExactToken, Maybe1, Maybe3, Rule},
         Bodystmt, FnameT, Params, TermT, Value, VarRef,
     },
     token::{Token, TokenKind},
@@ -16,12 +17,61 @@ impl Rule for MethodDef {
         DefHead::starts_now(parser)
     }
 
-    fn parse(parser: &mut Parser) -> Self::Output {
-        let def_head = DefHead::parse(parser);
-        let args = MethodDefArgs::parse(parser);
-        let body = Bodystmt::parse(parser);
-        let end_t = parser.expect_token(TokenKind::kEND);
-        match def_head {
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
+        let def_head = DefHead::parse(parser)?;
+        // method body doesn't see outer local variables
+        parser.static_env().extend_static();
+        parser.max_numparam_stack().push_static();
+        let args = MethodDefArgs::parse(parser)?;
+
+        if EndlessMethodBody::starts_now(parser) {
+            // def foo(a) = a
+            let name_t = match &def_head {
+                DefHead::DefnHead { name_t, .. } | DefHead::DefsHead { name_t, .. } => *name_t,
+            };
+            if is_setter_name(parser, name_t) {
+                return Err(ParseError::new(
+                    ParseErrorKind::EndlessSetterMethod,
+                    name_t.loc,
+                ));
+            }
+            let (assignment_t, body) = EndlessMethodBody::parse(parser)?;
+            parser.max_numparam_stack().pop();
+            parser.static_env().unextend();
+
+            let method_def = match def_head {
+                DefHead::DefnHead { def_t, name_t } => Builder::def_endless_method(
+                    def_t,
+                    name_t,
+                    args,
+                    assignment_t,
+                    Some(body),
+                    parser.buffer(),
+                ),
+                DefHead::DefsHead {
+                    def_t,
+                    definee,
+                    dot_t,
+                    name_t,
+                } => Builder::def_endless_singleton(
+                    def_t,
+                    definee,
+                    dot_t,
+                    name_t,
+                    args,
+                    assignment_t,
+                    Some(body),
+                    parser.buffer(),
+                ),
+            };
+            return Ok(method_def);
+        }
+
+        let body = Bodystmt::parse(parser)?;
+        parser.max_numparam_stack().pop();
+        parser.static_env().unextend();
+        let end_t = parser.expect_token(TokenKind::kEND)?;
+        let method_def = match def_head {
             DefHead::DefnHead { def_t, name_t } => {
                 Builder::def_method(def_t, name_t, args, body, end_t, parser.buffer())
             }
@@ -40,9 +90,20 @@ impl Rule for MethodDef {
                 end_t,
                 parser.buffer(),
             ),
-        }
+        };
+        Ok(method_def)
     }
 }
+
+// `foo=` can't be defined without `end`, but `==` can
+fn is_setter_name(parser: &Parser, name_t: Token) -> bool {
+    let name = parser
+        .buffer()
+        .slice(name_t.loc.start, name_t.loc.end)
+        .expect("bug: token is out of bounds");
+    name_t.is_one_of([TokenKind::tIDENTIFIER, TokenKind::tCONSTANT]) && name.last() == Some(&b'=')
+}
+
 #[test]
 fn test_instance_method_def() {
     crate::testing::assert_parses_rule!(
@@ -55,6 +116,93 @@ s(:def, "foo", nil,
     )
 }
 #[test]
+fn test_unary_operator_method_def() {
+    crate::testing::assert_parses_rule!(MethodDef, b"def +@; end", r#"s(:def, "+@", nil, nil)"#);
+    crate::testing::assert_parses_rule!(MethodDef, b"def -@; end", r#"s(:def, "-@", nil, nil)"#);
+    crate::testing::assert_parses_rule!(MethodDef, b"def ~@; end", r#"s(:def, "~", nil, nil)"#);
+}
+#[test]
+fn test_index_operator_method_def() {
+    crate::testing::assert_parses_rule!(
+        MethodDef,
+        b"def [](k); end",
+        r#"
+s(:def, "[]",
+  s(:args,
+    s(:arg, "k")), nil)
+        "#
+    );
+    crate::testing::assert_parses_rule!(
+        MethodDef,
+        b"def []=(k, v); end",
+        r#"
+s(:def, "[]=",
+  s(:args,
+    s(:arg, "k"),
+    s(:arg, "v")), nil)
+        "#
+    );
+}
+#[test]
+fn test_method_def_args_are_locals() {
+    crate::testing::assert_parses_rule!(
+        MethodDef,
+        b"def foo(a); a; b; end",
+        r#"
+s(:def, "foo",
+  s(:args,
+    s(:arg, "a")),
+  s(:begin,
+    s(:lvar, "a"),
+    s(:send, nil, "b")))
+            "#
+    )
+}
+#[test]
+fn test_method_def_forwards_all_args() {
+    crate::testing::assert_parses_rule!(
+        MethodDef,
+        b"def foo(a, ...); bar(...); end",
+        r#"
+s(:def, "foo",
+  s(:args,
+    s(:arg, "a"),
+    s(:forward_arg)),
+  s(:send, nil, "bar",
+    s(:forwarded_args)))
+        "#
+    )
+}
+#[test]
+fn test_method_def_forwards_anonymous_args() {
+    crate::testing::assert_parses_rule!(
+        MethodDef,
+        b"def foo(*, **, &); bar(*, **, &); end",
+        r#"
+s(:def, "foo",
+  s(:args,
+    s(:restarg),
+    s(:kwrestarg),
+    s(:blockarg)),
+  s(:send, nil, "bar",
+    s(:splat),
+    s(:kwargs,
+      s(:forwarded_kwrestarg)),
+    s(:block_pass)))
+        "#
+    )
+}
+#[test]
+fn test_method_def_has_static_scope() {
+    use crate::parser::base::Rule;
+
+    let mut parser = crate::Parser::new(b"def foo(a); a; end");
+    parser.static_env().declare(b"b");
+    MethodDef::parse(&mut parser).unwrap();
+    assert!(parser.static_env().is_declared(b"b"));
+    assert!(!parser.static_env().is_declared(b"a"));
+}
+#[test]
 fn test_singleton_method_def() {
     crate::testing::assert_parses_rule!(
         MethodDef,
@@ -67,17 +215,74 @@ s(:defs,
     )
 }
 
-pub(crate) struct EndlessMethodDef;
-impl Rule for EndlessMethodDef {
-    type Output = Box<Node>;
+#[test]
+fn test_endless_method_def() {
+    crate::testing::assert_parses_rule!(
+        MethodDef,
+        b"def foo(x) = x",
+        r#"
+s(:def, "foo",
+  s(:args,
+    s(:arg, "x")),
+  s(:lvar, "x"))
+            "#
+    )
+}
+#[test]
+fn test_endless_singleton_method_def() {
+    crate::testing::assert_parses_rule!(
+        MethodDef,
+        b"def self.bar = baz",
+        r#"
+s(:defs,
+  s(:self), "bar", nil,
+  s(:send, nil, "baz"))
+            "#
+    )
+}
+#[test]
+fn test_endless_method_def_with_rescue_modifier() {
+    crate::testing::assert_parses_rule!(
+        MethodDef,
+        b"def foo = bar rescue nil",
+        r#"
+s(:def, "foo", nil,
+  s(:rescue,
+    s(:send, nil, "bar"),
+    s(:resbody, nil, nil,
+      s(:nil)), nil))
+            "#
+    )
+}
+#[test]
+fn test_endless_operator_method_def() {
+    crate::testing::assert_parses_rule!(
+        MethodDef,
+        b"def ==(other) = true",
+        r#"
+s(:def, "==",
+  s(:args,
+    s(:arg, "other")),
+  s(:true))
+            "#
+    )
+}
+#[test]
+fn test_endless_setter_method_def() {
+    use crate::{error::ParseErrorKind, loc::loc};
 
-    fn starts_now(parser: &mut Parser) -> bool {
-        parser.current_token().is(TokenKind::kDEF)
-    }
+    let mut parser = Parser::new(b"def foo=(x) = x");
+    let error = MethodDef::parse(&mut parser).unwrap_err();
+    assert_eq!(error.kind, ParseErrorKind::EndlessSetterMethod);
+    assert_eq!(error.loc, loc!(4, 8));
+}
+#[test]
+fn test_method_def_without_name() {
+    let mut parser = Parser::new(b"def self.");
+    assert!(MethodDef::parse(&mut parser).is_err());
 
-    fn parse(parser: &mut Parser) -> Self::Output {
-        todo!()
-    }
+    let mut parser = Parser::new(b"def");
+    assert!(MethodDef::parse(&mut parser).is_err());
 }
 
 enum DefHead {
@@ -100,51 +305,51 @@ impl Rule for DefHead {
         parser.current_token().is(TokenKind::kDEF)
     }
 
-    fn parse(parser: &mut Parser) -> Self::Output {
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
         let def_t = parser.take_token();
 
-        if FnameT::starts_now(parser) || Singleton::starts_now(parser) {
+        if FnameT::starts_now(parser) && Singleton::starts_now(parser) {
             // unclear, depends on the presence of '.' or '::'
             // because 'def self; end' is a valid construction
 
             let as_token = parser.current_token();
-            let as_node = Singleton::parse(parser);
+            let as_node = Singleton::parse(parser)?;
 
             if DotOrColonT::starts_now(parser) {
                 // singleton method
                 let definee = as_node;
-                let dot_t = DotOrColonT::parse(parser);
-                let name_t = FnameT::parse(parser);
-                Self::DefsHead {
+                let dot_t = DotOrColonT::parse(parser)?;
+                let name_t = FnameT::parse(parser)?;
+                Ok(Self::DefsHead {
                     def_t,
                     definee,
                     dot_t,
                     name_t,
-                }
+                })
             } else {
                 // instance method
                 let name_t = as_token;
-                Self::DefnHead { def_t, name_t }
+                Ok(Self::DefnHead { def_t, name_t })
             }
         } else if FnameT::starts_now(parser) {
             // obvious instance method
             // like `def +(other)`
-            let name_t = FnameT::parse(parser);
-            Self::DefnHead { def_t, name_t }
+            let name_t = FnameT::parse(parser)?;
+            Ok(Self::DefnHead { def_t, name_t })
         } else if Singleton::starts_now(parser) {
             // obvious singleton method
             // like `def self.foo`
-            let definee = Singleton::parse(parser);
-            let dot_t = DotOrColonT::parse(parser);
-            let name_t = FnameT::parse(parser);
-            Self::DefsHead {
+            let definee = Singleton::parse(parser)?;
+            let dot_t = DotOrColonT::parse(parser)?;
+            let name_t = FnameT::parse(parser)?;
+            Ok(Self::DefsHead {
                 def_t,
                 definee,
                 dot_t,
                 name_t,
-            }
+            })
         } else {
-            unreachable!()
+            Err(ParseError::unexpected_token(None, parser.current_token()))
         }
     }
 }
@@ -157,7 +362,7 @@ impl Rule for MethodDefArgs {
         true
     }
 
-    fn parse(parser: &mut Parser) -> Self::Output {
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
         type MaybeParams = Maybe1<Params>;
 
         let begin_t;
@@ -166,31 +371,50 @@ impl Rule for MethodDefArgs {
 
         if parser.current_token().is(TokenKind::tLPAREN) {
             begin_t = Some(parser.take_token());
-            args = MaybeParams::parse(parser).unwrap_or_default();
-            end_t = Some(parser.expect_token(TokenKind::tRPAREN));
+            args = MaybeParams::parse(parser)?.unwrap_or_default();
+            end_t = Some(parser.expect_token(TokenKind::tRPAREN)?);
         } else {
             begin_t = None;
-            args = MaybeParams::parse(parser).unwrap_or_default();
             end_t = None;
-            if !args.is_empty() {
-                TermT::parse(parser);
+            // newlines are skipped by the lexer, but params without parens
+            // can't go to the next line, it's already a body of the method
+            if parser.newline_before_current_token().is_none() {
+                args = MaybeParams::parse(parser)?.unwrap_or_default();
+                if !args.is_empty() && parser.newline_before_current_token().is_none() {
+                    TermT::parse(parser)?;
+                }
+            } else {
+                args = vec![];
             }
         }
 
-        Builder::args(begin_t, args, end_t)
+        Ok(Builder::args(begin_t, args, end_t))
     }
 }
 
-struct EndlessMethodArgs;
-impl Rule for EndlessMethodArgs {
-    type Output = Box<Node>;
+// `= value` or `= value rescue value`
+struct EndlessMethodBody;
+impl Rule for EndlessMethodBody {
+    type Output = (Token, Box<Node>);
 
     fn starts_now(parser: &mut Parser) -> bool {
-        todo!()
+        parser.current_token().is(TokenKind::tEQL)
     }
 
-    fn parse(parser: &mut Parser) -> Self::Output {
-        todo!()
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
+        let assignment_t = parser.take_token();
+        let body = Value::parse_arg(parser)?;
+        if parser.current_token().is(TokenKind::kRESCUE) {
+            let rescue_t = parser.take_token();
+            let rescue_value = Value::parse_arg(parser)?;
+            let rescue_body =
+                Builder::rescue_body(rescue_t, vec![], None, None, Some(rescue_value));
+            let body = Builder::begin_body(Some(body), vec![*rescue_body], None, None)
+                .expect("bug: `foo rescue bar` always has a body");
+            Ok((assignment_t, body))
+        } else {
+            Ok((assignment_t, body))
+        }
     }
 }
 
@@ -199,22 +423,22 @@ impl Rule for Singleton {
     type Output = Box<Node>;
 
     fn starts_now(parser: &mut Parser) -> bool {
-        at_most_one_is_true([
+        any_is_true([
             VarRef::starts_now(parser),
             parser.current_token().is(TokenKind::tLPAREN),
         ])
     }
 
-    fn parse(parser: &mut Parser) -> Self::Output {
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
         if VarRef::starts_now(parser) {
             VarRef::parse(parser)
         } else if parser.current_token().is(TokenKind::tLPAREN) {
             let lparen_t = parser.take_token();
-            let value = Value::parse(parser);
-            let rparen_t = parser.expect_token(TokenKind::tRPAREN);
-            Builder::begin(lparen_t, vec![*value], rparen_t)
+            let value = Value::parse(parser)?;
+            let rparen_t = parser.expect_token(TokenKind::tRPAREN)?;
+            Ok(Builder::begin(lparen_t, vec![*value], rparen_t))
         } else {
-            unreachable!()
+            Err(ParseError::unexpected_token(None, parser.current_token()))
         }
     }
 }
@@ -225,14 +449,14 @@ impl Rule for DotOrColonT {
 
     fn starts_now(parser: &mut Parser) -> bool {
         let token = parser.current_token();
-        at_most_one_is_true([token.is(TokenKind::tDOT), token.is(TokenKind::tCOLON2)])
+        any_is_true([token.is(TokenKind::tDOT), token.is(TokenKind::tCOLON2)])
     }
 
-    fn parse(parser: &mut Parser) -> Self::Output {
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
         if Self::starts_now(parser) {
-            parser.take_token()
+            Ok(parser.take_token())
         } else {
-            unreachable!()
+            Err(ParseError::unexpected_token(None, parser.current_token()))
         }
     }
 }
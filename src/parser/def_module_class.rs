@@ -1,5 +1,6 @@
 use crate::{
     builder::Builder,
+    error::{ParseError, ParseErrorKind, ParseResult},
     nodes::Send,
     parser::{base::Rule, Bodystmt, TermT, Value},
     token::{Token, TokenKind},
@@ -14,24 +15,24 @@ impl Rule for Module {
         parser.current_token().is(TokenKind::kMODULE)
     }
 
-    fn parse(parser: &mut Parser) -> Self::Output {
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
         let module_t = parser.take_token();
-        let name = match CPath::parse(parser) {
+        let name = match CPath::parse(parser)? {
             CPath::Const(name) => name,
-            CPath::Inheritance {
-                name,
-                lt_t,
-                superclass,
-            } => {
-                panic!(
-                    "Inheritance in module declaration: {:?} {:?} {:?}",
-                    name, lt_t, superclass
-                )
+            CPath::Inheritance { lt_t, .. } => {
+                return Err(ParseError::new(
+                    ParseErrorKind::ModuleWithSuperclass,
+                    lt_t.loc,
+                ));
             }
         };
-        let body = Bodystmt::parse(parser);
-        let end_t = parser.expect_token(TokenKind::kEND);
-        Builder::def_module(module_t, name, body, end_t)
+        parser.static_env().extend_static();
+        parser.max_numparam_stack().push_static();
+        let body = Bodystmt::parse(parser)?;
+        parser.max_numparam_stack().pop();
+        parser.static_env().unextend();
+        let end_t = parser.expect_token(TokenKind::kEND)?;
+        Ok(Builder::def_module(module_t, name, body, end_t))
     }
 }
 
@@ -43,18 +44,22 @@ impl Rule for Class {
         parser.current_token().is(TokenKind::kCLASS)
     }
 
-    fn parse(parser: &mut Parser) -> Self::Output {
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
         let class_t = parser.take_token();
         if parser.current_token().is(TokenKind::tLSHFT) {
             // class << foo
             let lshift_t = parser.take_token();
-            let expr = Value::parse(parser);
-            let _ = TermT::parse(parser);
-            let body = Bodystmt::parse(parser);
-            let end_t = parser.expect_token(TokenKind::kEND);
-            Builder::def_sclass(class_t, lshift_t, expr, body, end_t)
+            let expr = Value::parse(parser)?;
+            let _ = TermT::parse(parser)?;
+            parser.static_env().extend_static();
+            parser.max_numparam_stack().push_static();
+            let body = Bodystmt::parse(parser)?;
+            parser.max_numparam_stack().pop();
+            parser.static_env().unextend();
+            let end_t = parser.expect_token(TokenKind::kEND)?;
+            Ok(Builder::def_sclass(class_t, lshift_t, expr, body, end_t))
         } else {
-            let (name, lt_t, superclass) = match dbg!(CPath::parse(parser)) {
+            let (name, lt_t, superclass) = match CPath::parse(parser)? {
                 CPath::Const(name) => (name, None, None),
                 CPath::Inheritance {
                     name,
@@ -62,9 +67,15 @@ impl Rule for Class {
                     superclass,
                 } => (name, Some(lt_t), Some(superclass)),
             };
-            let body = Bodystmt::parse(parser);
-            let end_t = parser.expect_token(TokenKind::kEND);
-            Builder::def_class(class_t, name, lt_t, superclass, body, end_t)
+            parser.static_env().extend_static();
+            parser.max_numparam_stack().push_static();
+            let body = Bodystmt::parse(parser)?;
+            parser.max_numparam_stack().pop();
+            parser.static_env().unextend();
+            let end_t = parser.expect_token(TokenKind::kEND)?;
+            Ok(Builder::def_class(
+                class_t, name, lt_t, superclass, body, end_t,
+            ))
         }
     }
 }
@@ -86,11 +97,11 @@ impl Rule for CPath {
         Value::starts_now(parser)
     }
 
-    fn parse(parser: &mut Parser) -> Self::Output {
-        let node = Value::parse(parser);
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
+        let node = Value::parse(parser)?;
 
         if matches!(&*node, Node::Const(_)) {
-            return Self::Const(node);
+            return Ok(Self::Const(node));
         }
 
         match *node {
@@ -108,14 +119,17 @@ impl Rule for CPath {
                     value: None,
                 };
                 let superclass = Box::new(args.into_iter().next().unwrap());
-                Self::Inheritance {
+                Ok(Self::Inheritance {
                     name,
                     lt_t,
                     superclass,
-                }
+                })
             }
 
-            other => panic!("Wrong type of CPath: {:?}", other),
+            other => Err(ParseError::new(
+                ParseErrorKind::InvalidClassOrModuleName,
+                *other.inner_ref().expression(),
+            )),
         }
     }
 }
@@ -136,6 +150,16 @@ s(:module,
     )
 }
 
+#[test]
+fn test_module_with_superclass() {
+    use crate::{error::ParseErrorKind, loc::loc};
+
+    let mut parser = Parser::new(b"module Foo < Bar; end");
+    let error = Module::parse(&mut parser).unwrap_err();
+    assert_eq!(error.kind, ParseErrorKind::ModuleWithSuperclass);
+    assert_eq!(error.loc, loc!(11, 12));
+}
+
 #[test]
 fn test_class() {
     use crate::testing::assert_parses_rule;
@@ -152,6 +176,34 @@ s(:class,
     )
 }
 
+#[test]
+fn test_class_with_scoped_name() {
+    use crate::testing::assert_parses_rule;
+
+    assert_parses_rule!(
+        Class,
+        b"class Foo::Bar < Baz; end",
+        r#"
+s(:class,
+  s(:const,
+    s(:const, nil, "Foo"), "Bar"),
+  s(:const, nil, "Baz"), nil)
+            "#
+    )
+}
+
+#[test]
+fn test_class_has_static_scope() {
+    let mut parser = Parser::new(b"class Foo; a; end");
+    parser.static_env().declare(b"a");
+    let ast = Class::parse(&mut parser).unwrap();
+    assert_eq!(
+        ast.inspect(0),
+        "s(:class,\n  s(:const, nil, \"Foo\"), nil,\n  s(:send, nil, \"a\"))"
+    );
+    assert!(parser.static_env().is_declared(b"a"));
+}
+
 #[test]
 fn test_sclass() {
     use crate::testing::assert_parses_rule;
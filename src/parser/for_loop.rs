@@ -1,7 +1,9 @@
 use crate::{
     builder::Builder,
-    parser::base::Rule,
-    token::{Token, TokenKind},
+    error::ParseResult,
+    nodes::Mlhs,
+    parser::{base::Rule, Compstmt, DoT, Value, MLHS},
+    token::TokenKind,
     Node, Parser,
 };
 
@@ -13,8 +15,42 @@ impl Rule for ForLoop {
         parser.current_token().is(TokenKind::kFOR)
     }
 
-    fn parse(parser: &mut Parser) -> Self::Output {
-        todo!()
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
+        let for_t = parser.take_token();
+        let iterator = ForVar::parse(parser)?;
+        let in_t = parser.expect_token(TokenKind::kIN)?;
+        let iteratee = parser.with_do_block_allowed(false, Value::parse)?;
+        let do_t = DoT::parse(parser)?;
+        let body = Compstmt::parse(parser)?;
+        let end_t = parser.expect_token(TokenKind::kEND)?;
+        Ok(Builder::for_(
+            for_t, iterator, in_t, iteratee, do_t, body, end_t,
+        ))
+    }
+}
+
+// `a` or `a, b` or `(a, b)`
+struct ForVar;
+impl Rule for ForVar {
+    type Output = Box<Node>;
+
+    fn starts_now(parser: &mut Parser) -> bool {
+        MLHS::starts_now(parser)
+    }
+
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
+        let mlhs = MLHS::parse(parser)?;
+        match *mlhs {
+            // a single variable is not a list
+            Node::Mlhs(Mlhs {
+                mut items,
+                begin_l: None,
+                ..
+            }) if items.len() == 1 && !matches!(items[0], Node::Splat(_)) => {
+                Ok(Box::new(items.remove(0)))
+            }
+            other => Ok(Box::new(other)),
+        }
     }
 }
 
@@ -25,14 +61,12 @@ mod tests {
 
     #[test]
     fn test_for_lhs() {
-        debug_assert!(false, "implement me");
-
         assert_parses_rule!(
             ForLoop,
             b"for a in 1 do; 2; end",
             r#"
 s(:for,
-  s(:lvar, "a"),
+  s(:lvasgn, "a"),
   s(:int, "1"),
   s(:int, "2"))
             "#
@@ -41,19 +75,34 @@ s(:for,
 
     #[test]
     fn test_for_mlhs() {
-        debug_assert!(false, "implement me");
-
         assert_parses_rule!(
             ForLoop,
             b"for (a, b) in 1 do; 2; end",
             r#"
 s(:for,
-  s(:begin,
-    s(:lvar, "a"),
-    s(:lvar, "b")),
+  s(:mlhs,
+    s(:lvasgn, "a"),
+    s(:lvasgn, "b")),
   s(:int, "1"),
   s(:int, "2"))
             "#
         )
     }
+
+    #[test]
+    fn test_for_mlhs_without_parens() {
+        assert_parses_rule!(
+            ForLoop,
+            b"for a, *b in foo\n  a\nend",
+            r#"
+s(:for,
+  s(:mlhs,
+    s(:lvasgn, "a"),
+    s(:splat,
+      s(:lvasgn, "b"))),
+  s(:send, nil, "foo"),
+  s(:lvar, "a"))
+            "#
+        )
+    }
 }
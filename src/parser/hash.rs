@@ -1,4 +1,13 @@
-use crate::{builder::Builder, parser::base::Rule, token::TokenKind, Node, Parser};
+use crate::{
+    builder::Builder,
+    error::{ParseError, ParseResult},
+    parser::{
+        base::{any_is_true, ExactToken, Maybe1, Rule, SeparatedBy},
+        Value,
+    },
+    token::TokenKind,
+    Node, Parser,
+};
 
 pub(crate) struct Hash;
 impl Rule for Hash {
@@ -8,8 +17,37 @@ impl Rule for Hash {
         parser.current_token().is(TokenKind::tLCURLY)
     }
 
-    fn parse(parser: &mut Parser) -> Self::Output {
-        todo!()
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
+        let lcurly_t = parser.take_token();
+        let pairs = parser
+            .with_do_block_allowed(true, Maybe1::<Assocs>::parse)?
+            .unwrap_or_default();
+        let rcurly_t = parser.expect_token(TokenKind::tRCURLY)?;
+
+        Ok(Builder::associate(
+            Some(lcurly_t),
+            pairs,
+            Some(rcurly_t),
+            parser.diagnostics(),
+        ))
+    }
+}
+
+struct Assocs;
+impl Rule for Assocs {
+    type Output = Vec<Node>;
+
+    fn starts_now(parser: &mut Parser) -> bool {
+        Assoc::starts_now(parser)
+    }
+
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
+        type CommaT = ExactToken<{ TokenKind::tCOMMA as u8 }>;
+
+        let (pairs, _commas) = SeparatedBy::<Assoc, CommaT>::parse(parser)?;
+        let _trailing_comma = Maybe1::<CommaT>::parse(parser)?;
+
+        Ok(pairs)
     }
 }
 
@@ -18,32 +56,155 @@ impl Rule for Assoc {
     type Output = Box<Node>;
 
     fn starts_now(parser: &mut Parser) -> bool {
-        todo!()
+        let token = parser.current_token();
+        any_is_true([
+            token.is(TokenKind::tLABEL),
+            token.is(TokenKind::tDSTAR),
+            Value::starts_now(parser),
+        ])
     }
 
-    fn parse(parser: &mut Parser) -> Self::Output {
-        todo!()
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
+        if parser.current_token().is(TokenKind::tLABEL) {
+            let key_t = parser.take_token();
+            if Value::starts_now(parser) && parser.newline_before_current_token().is_none() {
+                // key: value
+                let value = Value::parse_arg(parser)?;
+                Ok(Builder::pair_keyword(key_t, value, parser.buffer()))
+            } else {
+                // key:, value is taken from the local variable or method call `key`
+                Ok(Builder::pair_label(
+                    key_t,
+                    parser.buffer(),
+                    parser.static_env(),
+                    parser.current_arg_stack(),
+                    parser.max_numparam_stack(),
+                    parser.diagnostics(),
+                ))
+            }
+        } else if parser.current_token().is(TokenKind::tDSTAR) {
+            let dstar_t = parser.take_token();
+            if Value::starts_now(parser) {
+                // **value
+                let value = Value::parse_arg(parser)?;
+                Ok(Builder::kwsplat(dstar_t, value))
+            } else {
+                // anonymous keyword rest argument
+                Ok(Builder::forwarded_kwrestarg(dstar_t))
+            }
+        } else if Value::starts_now(parser) {
+            let key = Value::parse_arg(parser)?;
+            if parser.current_token().is(TokenKind::tCOLON)
+                && matches!(&*key, Node::Str(_) | Node::Dstr(_))
+                && parser.current_token().loc.start == key.expression().end
+            {
+                // "key": value
+                let colon_t = parser.take_token();
+                let value = Value::parse_arg(parser)?;
+                Ok(Builder::pair_quoted(key, colon_t, value))
+            } else {
+                // key => value
+                let assoc_t = parser.expect_token(TokenKind::tASSOC)?;
+                let value = Value::parse_arg(parser)?;
+                Ok(Builder::pair(key, assoc_t, value))
+            }
+        } else {
+            Err(ParseError::unexpected_token(None, parser.current_token()))
+        }
     }
 }
 
-#[test]
-fn test_hash() {
-    use crate::testing::assert_parses_rule;
+#[cfg(test)]
+mod tests {
+    use super::Hash;
+    use crate::{
+        loc::loc, parser::base::Rule, testing::assert_parses_rule, Diagnostic, DiagnosticLevel,
+        DiagnosticMessage, Parser,
+    };
 
-    assert_parses_rule!(
-        Hash,
-        b"{ a: 1, :b => 2, c => 3 }",
-        r#"
+    #[test]
+    fn test_hash() {
+        assert_parses_rule!(
+            Hash,
+            b"{ a: 1, :b => 2, c => 3 }",
+            r#"
 s(:hash,
   s(:pair,
-    s(:sym, "a:"),
+    s(:sym, "a"),
     s(:int, "1")),
   s(:pair,
     s(:sym, "b"),
     s(:int, "2")),
   s(:pair,
-    s(:lvar, "c"),
+    s(:send, nil, "c"),
     s(:int, "3")))
-        "#
-    );
+            "#
+        );
+    }
+
+    #[test]
+    fn test_hash_all_pair_forms() {
+        assert_parses_rule!(
+            Hash,
+            b"{ a: 1, \"b\": 2, :c => 3, **d, e:, }",
+            r#"
+s(:hash,
+  s(:pair,
+    s(:sym, "a"),
+    s(:int, "1")),
+  s(:pair,
+    s(:sym, "b"),
+    s(:int, "2")),
+  s(:pair,
+    s(:sym, "c"),
+    s(:int, "3")),
+  s(:kwsplat,
+    s(:send, nil, "d")),
+  s(:pair,
+    s(:sym, "e"),
+    s(:send, nil, "e")))
+            "#
+        );
+    }
+
+    #[test]
+    fn test_empty_hash() {
+        assert_parses_rule!(Hash, b"{}", "s(:hash)");
+    }
+
+    #[test]
+    fn test_nested_hash_locations() {
+        let mut parser = Parser::new(b"{ a: { b: 1 } }");
+        let node = Hash::parse(&mut parser).unwrap();
+        let crate::Node::Hash(hash) = *node else {
+            panic!("expected a hash")
+        };
+        assert_eq!(hash.begin_l, Some(loc!(0, 1)));
+        assert_eq!(hash.end_l, Some(loc!(14, 15)));
+        assert_eq!(hash.expression_l, loc!(0, 15));
+
+        let crate::Node::Pair(pair) = &hash.pairs[0] else {
+            panic!("expected a pair")
+        };
+        let crate::Node::Hash(inner) = &*pair.value else {
+            panic!("expected a nested hash")
+        };
+        assert_eq!(inner.begin_l, Some(loc!(5, 6)));
+        assert_eq!(inner.end_l, Some(loc!(12, 13)));
+        assert_eq!(inner.expression_l, loc!(5, 13));
+    }
+
+    #[test]
+    fn test_duplicate_key_warning() {
+        let mut parser = Parser::new(b"{ a: 1, a: 2 }");
+        Hash::parse(&mut parser).unwrap();
+        assert_eq!(
+            parser.diagnostics().take_inner(),
+            vec![Diagnostic {
+                level: DiagnosticLevel::Warning,
+                message: DiagnosticMessage::DuplicateHashKey,
+                loc: loc!(8, 9)
+            }]
+        );
+    }
 }
@@ -1,8 +1,9 @@
 use crate::{
     builder::Builder,
+    error::{ParseError, ParseResult},
     parser::{
         base::{ExactToken, Maybe1, Maybe2, Rule},
-        Compstmt, TermT,
+        Compstmt, TermT, Value,
     },
     token::{Token, TokenKind},
     Node, Parser,
@@ -16,8 +17,24 @@ impl Rule for IfStmt {
         parser.current_token().is(TokenKind::kIF)
     }
 
-    fn parse(parser: &mut Parser) -> Self::Output {
-        todo!()
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
+        let if_t = parser.take_token();
+        let (cond, then_t, body) = parse_cond_and_body(parser)?;
+        let (else_t, else_body) = match IfTail::parse(parser)? {
+            Some((else_t, else_body)) => (Some(else_t), else_body),
+            None => (None, None),
+        };
+        let end_t = parser.expect_token(TokenKind::kEND)?;
+
+        Ok(Builder::condition(
+            if_t,
+            cond,
+            then_t,
+            body,
+            else_t,
+            else_body,
+            Some(end_t),
+        ))
     }
 }
 
@@ -29,11 +46,64 @@ impl Rule for UnlessStmt {
         parser.current_token().is(TokenKind::kUNLESS)
     }
 
-    fn parse(parser: &mut Parser) -> Self::Output {
-        todo!()
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
+        let unless_t = parser.take_token();
+        let (cond, then_t, body) = parse_cond_and_body(parser)?;
+        let (else_t, else_body) = match OptElse::parse(parser)? {
+            Some((else_t, else_body)) => (Some(else_t), else_body),
+            None => (None, None),
+        };
+        let end_t = parser.expect_token(TokenKind::kEND)?;
+
+        // `unless` is an `if` with swapped branches
+        Ok(Builder::condition(
+            unless_t,
+            cond,
+            then_t,
+            else_body,
+            else_t,
+            body,
+            Some(end_t),
+        ))
     }
 }
 
+// `elsif cond then body` (that is a nested `if` without `end`) or `else body`
+struct IfTail;
+impl Rule for IfTail {
+    type Output = Option<(Token, Option<Box<Node>>)>;
+
+    fn starts_now(_parser: &mut Parser) -> bool {
+        true
+    }
+
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
+        if parser.current_token().is(TokenKind::kELSIF) {
+            let elsif_t = parser.take_token();
+            let (cond, then_t, body) = parse_cond_and_body(parser)?;
+            let (else_t, else_body) = match IfTail::parse(parser)? {
+                Some((else_t, else_body)) => (Some(else_t), else_body),
+                None => (None, None),
+            };
+            let elsif = Builder::condition(elsif_t, cond, then_t, body, else_t, else_body, None);
+            Ok(Some((elsif_t, Some(elsif))))
+        } else {
+            OptElse::parse(parser)
+        }
+    }
+}
+
+// Parses `cond then body` part that is shared by `if`, `unless` and `elsif`
+fn parse_cond_and_body(parser: &mut Parser) -> ParseResult<(Box<Node>, Token, Option<Box<Node>>)> {
+    let cond = Value::parse_expr_value(parser)?;
+    let then_t = match Then::parse(parser)? {
+        Some(then_t) => then_t,
+        None => return Err(ParseError::unexpected_token(None, parser.current_token())),
+    };
+    let body = Compstmt::parse(parser)?;
+    Ok((cond, then_t, body))
+}
+
 type ElseT = ExactToken<{ TokenKind::kELSE as u8 }>;
 pub(crate) type OptElse = Maybe2<ElseT, Compstmt>;
 
@@ -45,14 +115,16 @@ impl Rule for Then {
         true
     }
 
-    fn parse(parser: &mut Parser) -> Self::Output {
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
         type MaybeTermT = Maybe1<TermT>;
         type ThenT = ExactToken<{ TokenKind::kTHEN as u8 }>;
         type MaybeThenT = Maybe1<ThenT>;
 
-        let _term = MaybeTermT::parse(parser);
-        let then_t = MaybeThenT::parse(parser);
-        then_t
+        // newlines are skipped by the lexer, but they terminate the condition too
+        let newline_t = parser.newline_before_current_token();
+        let term_t = MaybeTermT::parse(parser)?.or(newline_t);
+        let then_t = MaybeThenT::parse(parser)?;
+        Ok(then_t.or(term_t))
     }
 }
 
@@ -63,8 +135,6 @@ mod tests {
 
     #[test]
     fn test_if() {
-        debug_assert!(false, "implement me");
-
         assert_parses_rule!(
             IfStmt,
             b"if 1; 2; else; 3; end",
@@ -79,8 +149,6 @@ s(:if,
 
     #[test]
     fn test_unless() {
-        debug_assert!(false, "implement me");
-
         assert_parses_rule!(
             UnlessStmt,
             b"unless 1; 2; else; 3; end",
@@ -92,4 +160,61 @@ s(:if,
             "#
         )
     }
+
+    #[test]
+    fn test_if_elsif_chain() {
+        assert_parses_rule!(
+            IfStmt,
+            b"if a then 1\nelsif b\n  2\nelsif c; 3\nelse 4\nend",
+            r#"
+s(:if,
+  s(:send, nil, "a"),
+  s(:int, "1"),
+  s(:if,
+    s(:send, nil, "b"),
+    s(:int, "2"),
+    s(:if,
+      s(:send, nil, "c"),
+      s(:int, "3"),
+      s(:int, "4"))))
+            "#
+        )
+    }
+
+    #[test]
+    fn test_if_without_else() {
+        assert_parses_rule!(
+            IfStmt,
+            b"if a\nend",
+            r#"
+s(:if,
+  s(:send, nil, "a"), nil, nil)
+            "#
+        )
+    }
+
+    #[test]
+    fn test_if_keyword_locations() {
+        use crate::{loc::loc, parser::base::Rule, Node, Parser};
+
+        let mut parser = Parser::new(b"if a then 1 elsif b then 2 else 3 end");
+        let node = IfStmt::parse(&mut parser).unwrap();
+        let Node::If(if_) = *node else {
+            panic!("expected if")
+        };
+        assert_eq!(if_.keyword_l, loc!(0, 2));
+        assert_eq!(if_.begin_l, loc!(5, 9));
+        assert_eq!(if_.else_l, Some(loc!(12, 17)));
+        assert_eq!(if_.end_l, Some(loc!(34, 37)));
+        assert_eq!(if_.expression_l, loc!(0, 37));
+
+        let Some(Node::If(elsif)) = if_.if_false.as_deref() else {
+            panic!("expected elsif")
+        };
+        assert_eq!(elsif.keyword_l, loc!(12, 17));
+        assert_eq!(elsif.begin_l, loc!(20, 24));
+        assert_eq!(elsif.else_l, Some(loc!(27, 31)));
+        assert_eq!(elsif.end_l, None);
+        assert_eq!(elsif.expression_l, loc!(12, 33));
+    }
 }
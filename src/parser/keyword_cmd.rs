@@ -1,6 +1,7 @@
 use crate::{
     builder::Builder,
-    parser::{base::Rule, Args, Value},
+    error::ParseResult,
+    parser::{base::Rule, CallArgs, MaybeBlock, Value},
     Node, Parser, TokenKind,
 };
 
@@ -9,19 +10,21 @@ impl Rule for KeywordCmd {
     type Output = Box<Node>;
 
     fn starts_now(parser: &mut Parser) -> bool {
-        parser.current_token().is_one_of([
-            TokenKind::kBREAK,
-            TokenKind::kNEXT,
-            TokenKind::kREDO,
-            TokenKind::kRETRY,
-            TokenKind::kRETURN,
-            TokenKind::kYIELD,
-            TokenKind::kDEFINED,
-        ])
+        // `defined? foo` without parens is a prefix operator
+        (parser.current_token().is(TokenKind::kDEFINED) && parser.lexer.lookahead_is_lparen())
+            || parser.current_token().is_one_of([
+                TokenKind::kBREAK,
+                TokenKind::kNEXT,
+                TokenKind::kREDO,
+                TokenKind::kRETRY,
+                TokenKind::kRETURN,
+                TokenKind::kSUPER,
+                TokenKind::kYIELD,
+            ])
     }
 
     // TODO: double-check it after merging primary/expr/stmt
-    fn parse(parser: &mut Parser) -> Self::Output {
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
         let keyword_t = parser.take_token();
 
         let node = match keyword_t.kind {
@@ -31,38 +34,44 @@ impl Rule for KeywordCmd {
             TokenKind::kRETRY => Builder::retry(keyword_t),
             TokenKind::kRETURN => Builder::return_(keyword_t, vec![]),
             TokenKind::kYIELD => {
-                let lparen_t = if parser.current_token().is(TokenKind::tLPAREN) {
-                    Some(parser.take_token())
-                } else {
-                    None
-                };
-
-                let args = if Args::starts_now(parser) {
-                    Args::parse(parser)
-                } else {
-                    vec![]
-                };
+                let (lparen_t, args, rparen_t) = CallArgs::parse(parser)?;
+                let node = Builder::yield_(keyword_t, lparen_t, args, rparen_t);
 
-                let rparen_t = if lparen_t.is_some() {
-                    Some(parser.expect_token(TokenKind::tRPAREN))
+                // `yield { }` is invalid, but the block is parsed to report it
+                match MaybeBlock::parse(parser)? {
+                    Some((begin_t, args, body, end_t)) => {
+                        Builder::block(node, begin_t, args, body, end_t, parser.diagnostics())
+                    }
+                    None => node,
+                }
+            }
+            TokenKind::kSUPER => {
+                let (lparen_t, args, rparen_t) = CallArgs::parse(parser)?;
+                // `super` without parens and args passes arguments of the current method
+                let node = if lparen_t.is_none() && args.is_empty() {
+                    Builder::zsuper(keyword_t)
                 } else {
-                    None
+                    Builder::super_(keyword_t, lparen_t, args, rparen_t)
                 };
 
-                Builder::yield_(keyword_t, lparen_t, args, rparen_t)
+                match MaybeBlock::parse(parser)? {
+                    Some((begin_t, args, body, end_t)) => {
+                        Builder::block(node, begin_t, args, body, end_t, parser.diagnostics())
+                    }
+                    None => node,
+                }
             }
             TokenKind::kDEFINED => {
-                dbg!(parser.current_token());
-                let lparen_t = parser.expect_token(TokenKind::tLPAREN);
-                let value = Value::parse(parser);
-                let rparen_t = parser.expect_token(TokenKind::tRPAREN);
+                let lparen_t = parser.expect_token(TokenKind::tLPAREN)?;
+                let value = Value::parse(parser)?;
+                let rparen_t = parser.expect_token(TokenKind::tRPAREN)?;
 
                 Builder::defined(keyword_t, Some(lparen_t), value, Some(rparen_t))
             }
             _ => unreachable!(),
         };
 
-        node
+        Ok(node)
     }
 }
 #[test]
@@ -74,6 +83,32 @@ fn test_keyword_cmd() {
     assert_parses_rule!(KeywordCmd, b"redo", "s(:redo)");
     assert_parses_rule!(KeywordCmd, b"retry", "s(:retry)");
     assert_parses_rule!(KeywordCmd, b"return", "s(:return)");
-    // assert_parses_rule!(KeywordCmd, b"yield", "s(:yield)");
-    // assert_parses_rule!(KeywordCmd, b"defined?(42)", "s(:defined, s(:int, \"42\""))");
+    assert_parses_rule!(KeywordCmd, b"yield", "s(:yield)");
+    assert_parses_rule!(KeywordCmd, b"super", "s(:zsuper)");
+    assert_parses_rule!(KeywordCmd, b"super()", "s(:super)");
+    assert_parses_rule!(KeywordCmd, b"super(1)", "s(:super,\n  s(:int, \"1\"))");
+    assert_parses_rule!(KeywordCmd, b"super 1", "s(:super,\n  s(:int, \"1\"))");
+    assert_parses_rule!(
+        KeywordCmd,
+        b"defined?(42)",
+        "s(:defined?,\n  s(:int, \"42\"))"
+    );
+}
+#[test]
+fn test_yield_with_block() {
+    use crate::{loc::loc, Diagnostic, DiagnosticLevel, DiagnosticMessage};
+
+    let result = Parser::new(b"yield { }").parse().unwrap();
+    assert_eq!(
+        result.ast.map(|ast| ast.inspect(0)).as_deref(),
+        Some("s(:block,\n  s(:yield), nil, nil)")
+    );
+    assert_eq!(
+        result.diagnostics,
+        vec![Diagnostic {
+            level: DiagnosticLevel::Error,
+            message: DiagnosticMessage::BlockGivenToYield,
+            loc: loc!(0, 5)
+        }]
+    );
 }
@@ -1,4 +1,9 @@
-use crate::{parser::base::Rule, Node, Parser, TokenKind};
+use crate::{
+    builder::{ArgsType, Builder},
+    error::ParseResult,
+    parser::{base::Rule, Bodystmt, Compstmt, LambdaParams},
+    Node, Parser, Token, TokenKind,
+};
 
 pub(crate) struct Lambda;
 impl Rule for Lambda {
@@ -8,7 +13,113 @@ impl Rule for Lambda {
         parser.current_token().is(TokenKind::tLAMBDA)
     }
 
-    fn parse(parser: &mut Parser) -> Self::Output {
-        todo!()
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
+        let lambda_t = parser.take_token();
+
+        parser.static_env().extend_dynamic();
+        parser.max_numparam_stack().push_dynamic();
+
+        let result = parse_params_and_body(parser);
+
+        let numargs = parser.max_numparam_stack().pop();
+        parser.static_env().unextend();
+
+        let (args, (begin_t, body, end_t)) = result?;
+        let args = match (args, numargs) {
+            (None, Some(numargs)) => ArgsType::Numargs(numargs),
+            (args, _) => ArgsType::Args(args),
+        };
+
+        Ok(Builder::block(
+            Builder::call_lambda(lambda_t),
+            begin_t,
+            args,
+            body,
+            end_t,
+            parser.diagnostics(),
+        ))
+    }
+}
+
+// (begin_t, body, end_t)
+type LambdaBody = (Token, Option<Box<Node>>, Token);
+
+fn parse_params_and_body(parser: &mut Parser) -> ParseResult<(Option<Box<Node>>, LambdaBody)> {
+    let args = if LambdaParams::starts_now(parser) {
+        parser.max_numparam_stack().set_has_ordinary_params();
+        Some(LambdaParams::parse(parser)?)
+    } else {
+        None
+    };
+
+    // `do` always belongs to the lambda, even in command args
+    let body = if parser.current_token().is(TokenKind::kDO) {
+        let begin_t = parser.take_token();
+        let body = parser.with_do_block_allowed(true, Bodystmt::parse)?;
+        let end_t = parser.expect_token(TokenKind::kEND)?;
+        (begin_t, body, end_t)
+    } else {
+        let begin_t = parser.expect_token(TokenKind::tLCURLY)?;
+        let body = Compstmt::parse(parser)?;
+        let end_t = parser.expect_token(TokenKind::tRCURLY)?;
+        (begin_t, body, end_t)
+    };
+
+    Ok((args, body))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Lambda;
+    use crate::testing::assert_parses_rule;
+
+    #[test]
+    fn test_lambda_with_parenthesized_params() {
+        assert_parses_rule!(
+            Lambda,
+            b"->(x, y = 1) { x }",
+            r#"
+s(:block,
+  s(:lambda),
+  s(:args,
+    s(:arg, "x"),
+    s(:optarg, "y",
+      s(:int, "1"))),
+  s(:lvar, "x"))
+            "#
+        )
+    }
+
+    #[test]
+    fn test_lambda_with_bare_params_and_do() {
+        assert_parses_rule!(
+            Lambda,
+            b"-> x do x end",
+            r#"
+s(:block,
+  s(:lambda),
+  s(:args,
+    s(:arg, "x")),
+  s(:lvar, "x"))
+            "#
+        )
+    }
+
+    #[test]
+    fn test_lambda_without_params() {
+        assert_parses_rule!(Lambda, b"-> {}", "s(:block,\n  s(:lambda), nil, nil)")
+    }
+
+    #[test]
+    fn test_lambda_with_numparams() {
+        assert_parses_rule!(
+            Lambda,
+            b"-> { _1 }",
+            r#"
+s(:numblock,
+  s(:lambda), 1,
+  s(:lvar, "_1"))
+            "#
+        )
     }
 }
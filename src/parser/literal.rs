@@ -1,11 +1,12 @@
 use crate::{
     builder::Builder,
+    error::{ParseError, ParseResult},
     lexer::strings::{
         literal::StringLiteral,
         types::{Interpolation, Regexp as RegexpLiteral, StringInterp},
     },
     parser::{
-        base::{at_most_one_is_true, AtLeastOnce, ExactToken, Repeat1, Rule, SeparatedBy},
+        base::{any_is_true, AtLeastOnce, ExactToken, Repeat1, Rule, SeparatedBy},
         BackRef, Compstmt, Cvar, FnameT, Gvar, Ivar, SimpleNumeric,
     },
     token::token,
@@ -17,7 +18,7 @@ impl Rule for Literal {
     type Output = Box<Node>;
 
     fn starts_now(parser: &mut Parser) -> bool {
-        at_most_one_is_true([
+        any_is_true([
             Numeric::starts_now(parser),
             Symbol::starts_now(parser),
             Strings::starts_now(parser),
@@ -30,7 +31,7 @@ impl Rule for Literal {
         ])
     }
 
-    fn parse(parser: &mut Parser) -> Self::Output {
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
         if Numeric::starts_now(parser) {
             Numeric::parse(parser)
         } else if Symbol::starts_now(parser) {
@@ -50,7 +51,7 @@ impl Rule for Literal {
         } else if QSymbols::starts_now(parser) {
             QSymbols::parse(parser)
         } else {
-            unreachable!()
+            Err(ParseError::unexpected_token(None, parser.current_token()))
         }
     }
 }
@@ -60,30 +61,29 @@ impl Rule for Numeric {
     type Output = Box<Node>;
 
     fn starts_now(parser: &mut Parser) -> bool {
-        at_most_one_is_true([
+        any_is_true([
             parser.current_token().is(TokenKind::tUMINUS_NUM),
             SimpleNumeric::starts_now(parser),
         ])
     }
 
-    fn parse(parser: &mut Parser) -> Self::Output {
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
         let unary_t = if parser.current_token().is(TokenKind::tUMINUS_NUM) {
             Some(parser.take_token())
         } else {
             None
         };
 
-        let mut number = if SimpleNumeric::starts_now(parser) {
-            SimpleNumeric::parse(parser)
-        } else {
-            panic!("expected numeric literal")
-        };
+        if !SimpleNumeric::starts_now(parser) {
+            return Err(ParseError::unexpected_token(None, parser.current_token()));
+        }
+        let mut number = SimpleNumeric::parse(parser)?;
 
         if let Some(unary_t) = unary_t {
             number = Builder::unary_num(unary_t, number, parser.buffer());
         }
 
-        number
+        Ok(number)
     }
 }
 
@@ -120,19 +120,19 @@ impl Rule for Symbol {
     type Output = Box<Node>;
 
     fn starts_now(parser: &mut Parser) -> bool {
-        at_most_one_is_true([
+        any_is_true([
             SimpleSymbol::starts_now(parser),
             QuotedSymbol::starts_now(parser),
         ])
     }
 
-    fn parse(parser: &mut Parser) -> Self::Output {
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
         if SimpleSymbol::starts_now(parser) {
             SimpleSymbol::parse(parser)
         } else if QuotedSymbol::starts_now(parser) {
             QuotedSymbol::parse(parser)
         } else {
-            unreachable!()
+            Err(ParseError::unexpected_token(None, parser.current_token()))
         }
     }
 }
@@ -142,19 +142,19 @@ impl Rule for SimpleSymbol {
     type Output = Box<Node>;
 
     fn starts_now(parser: &mut Parser) -> bool {
-        parser.current_token().is(TokenKind::tCOLON)
+        // `a ? b : c` has a standalone `:`
+        parser.current_token().is(TokenKind::tCOLON) && !parser.lexer.lookahead_is_whitespace()
     }
 
-    fn parse(parser: &mut Parser) -> Self::Output {
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
         let colon_t = parser.take_token();
 
-        let sym_t = if SymT::starts_now(parser) {
-            SymT::parse(parser)
-        } else {
-            panic!("wrong token")
-        };
+        if !SymT::starts_now(parser) {
+            return Err(ParseError::unexpected_token(None, parser.current_token()));
+        }
+        let sym_t = SymT::parse(parser)?;
 
-        Builder::symbol(colon_t, sym_t, parser.buffer())
+        Ok(Builder::symbol(colon_t, sym_t, parser.buffer()))
     }
 }
 
@@ -177,18 +177,14 @@ impl Rule for QuotedSymbol {
             .is_one_of([TokenKind::tSYMBEG, TokenKind::tDSYMBEG])
     }
 
-    fn parse(parser: &mut Parser) -> Self::Output {
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
         let begin_t = parser.take_token();
 
-        let parts = StringContents::parse(parser);
+        let parts = StringContents::parse(parser)?;
 
-        let end_t = if parser.current_token().is(TokenKind::tSTRING_END) {
-            parser.take_token()
-        } else {
-            panic!("wrong token type")
-        };
+        let end_t = parser.expect_token(TokenKind::tSTRING_END)?;
 
-        Builder::symbol_compose(begin_t, parts, end_t)
+        Ok(Builder::symbol_compose(begin_t, parts, end_t))
     }
 }
 
@@ -205,19 +201,19 @@ impl Rule for Strings {
     type Output = Box<Node>;
 
     fn starts_now(parser: &mut Parser) -> bool {
-        at_most_one_is_true([
+        any_is_true([
             parser.current_token().is(TokenKind::tCHAR),
             String1::starts_now(parser),
         ])
     }
 
-    fn parse(parser: &mut Parser) -> Self::Output {
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
         if parser.current_token().is(TokenKind::tCHAR) {
             let char_t = parser.take_token();
-            Builder::character(char_t)
+            Ok(Builder::character(char_t))
         } else {
-            let parts = AtLeastOnce::<String1>::parse(parser);
-            Builder::string_compose(None, parts, None)
+            let parts = AtLeastOnce::<String1>::parse(parser)?;
+            Ok(Builder::string_compose(None, parts, None))
         }
     }
 }
@@ -240,15 +236,22 @@ impl Rule for String1 {
     type Output = Box<Node>;
 
     fn starts_now(parser: &mut Parser) -> bool {
-        parser.current_token().is(TokenKind::tSTRING_BEG)
-            || parser.current_token().is(TokenKind::tDSTRING_BEG)
+        parser.current_token().is_one_of([
+            TokenKind::tSTRING_BEG,
+            TokenKind::tDSTRING_BEG,
+            TokenKind::tHEREDOC_BEG,
+        ]) || parser.lexer.lookahead_heredoc_id() == Some(TokenKind::tHEREDOC_BEG)
     }
 
-    fn parse(parser: &mut Parser) -> Self::Output {
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
+        if parser.current_token().is(TokenKind::tLSHFT) {
+            parser.lexer.relex_as_heredoc();
+        }
         let begin_t = parser.take_token();
-        let parts = StringContents::parse(parser);
-        let end_t = parser.expect_token(TokenKind::tSTRING_END);
-        Builder::string_compose(Some(begin_t), parts, Some(end_t))
+        let parts = StringContents::parse(parser)?;
+        let end_t = parser.expect_token(TokenKind::tSTRING_END)?;
+        let parts = Builder::heredoc_dedent(&begin_t, parts, parser.buffer());
+        Ok(Builder::string_compose(Some(begin_t), parts, Some(end_t)))
     }
 }
 #[test]
@@ -261,6 +264,74 @@ fn test_string1_interp() {
     use crate::testing::assert_parses_rule;
     assert_parses_rule!(String1, b"\"foo\"", r#"s(:str, "foo")"#);
 }
+#[test]
+fn test_string1_heredoc() {
+    use crate::{parser::Value, testing::assert_parses_rule};
+    assert_parses_rule!(
+        Value,
+        b"<<-HERE\n  foo\n  bar\n  HERE\n",
+        r#"
+s(:dstr,
+  s(:str, "  foo\n"),
+  s(:str, "  bar\n"))
+        "#
+    );
+    assert_parses_rule!(Value, b"<<HERE\nHERE\n", r#"s(:dstr)"#);
+}
+#[test]
+fn test_string1_heredoc_squiggly() {
+    use crate::{parser::Value, testing::assert_parses_rule};
+    assert_parses_rule!(
+        Value,
+        b"<<~HERE\n    foo\n\n  \n  \tbar\n  #{1}\n  HERE\n",
+        r#"
+s(:dstr,
+  s(:str, "  foo\n"),
+  s(:str, "\n"),
+  s(:str, "\n"),
+  s(:str, "\tbar\n"),
+  s(:begin,
+    s(:int, "1")),
+  s(:str, "\n"))
+        "#
+    );
+}
+#[test]
+fn test_string1_heredoc_locs() {
+    use crate::{loc::loc, nodes::Heredoc};
+
+    let mut parser = Parser::new(b"<<~HERE\n  foo\n  HERE\n");
+    assert!(String1::starts_now(&mut parser));
+    let node = String1::parse(&mut parser).unwrap();
+    match *node {
+        Node::Heredoc(Heredoc {
+            heredoc_body_l,
+            heredoc_end_l,
+            expression_l,
+            ..
+        }) => {
+            assert_eq!(expression_l, loc!(0, 7));
+            assert_eq!(heredoc_body_l, loc!(8, 14));
+            assert_eq!(heredoc_end_l, loc!(16, 20));
+        }
+        other => panic!("expected heredoc, got {:?}", other),
+    }
+}
+#[test]
+fn test_strings_two_heredocs_on_one_line() {
+    use crate::{parser::Value, testing::assert_parses_rule};
+    assert_parses_rule!(
+        Value,
+        b"<<A + <<B\na\nA\nb\nB\n",
+        r#"
+s(:send,
+  s(:dstr,
+    s(:str, "a\n")), "+",
+  s(:dstr,
+    s(:str, "b\n")))
+        "#
+    );
+}
 
 struct XString;
 impl Rule for XString {
@@ -269,7 +340,10 @@ impl Rule for XString {
     fn starts_now(parser: &mut Parser) -> bool {
         let current_token = parser.current_token();
 
-        if current_token.is(TokenKind::tXSTRING_BEG) {
+        if current_token.is_one_of([TokenKind::tXSTRING_BEG, TokenKind::tXHEREDOC_BEG]) {
+            return true;
+        }
+        if parser.lexer.lookahead_heredoc_id() == Some(TokenKind::tXHEREDOC_BEG) {
             return true;
         }
         if current_token.is(TokenKind::tIDENTIFIER)
@@ -285,7 +359,10 @@ impl Rule for XString {
         }
     }
 
-    fn parse(parser: &mut Parser) -> Self::Output {
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
+        if parser.current_token().is(TokenKind::tLSHFT) {
+            parser.lexer.relex_as_heredoc();
+        }
         let begin_t = parser.current_token();
 
         if begin_t.is(TokenKind::tIDENTIFIER) {
@@ -305,12 +382,27 @@ impl Rule for XString {
         }
         parser.skip_token();
 
-        let parts = StringContents::parse(parser);
-        let end_t = parser.expect_token(TokenKind::tSTRING_END);
-        Builder::xstring_compose(begin_t, parts, end_t)
+        let parts = StringContents::parse(parser)?;
+        let end_t = parser.expect_token(TokenKind::tSTRING_END)?;
+        let parts = Builder::heredoc_dedent(&begin_t, parts, parser.buffer());
+        Ok(Builder::xstring_compose(begin_t, parts, end_t))
     }
 }
 #[test]
+fn test_xstring_heredoc() {
+    use crate::{parser::Value, testing::assert_parses_rule};
+    // heredoc body goes after the rest of the line,
+    // so that's the parent rule that consumes it
+    assert_parses_rule!(
+        Value,
+        b"<<~`HERE`\n  ls\nHERE\n",
+        r#"
+s(:xstr,
+  s(:str, "ls\n"))
+        "#
+    );
+}
+#[test]
 fn test_xstring() {
     use crate::testing::assert_parses_rule;
     assert_parses_rule!(
@@ -341,7 +433,7 @@ impl Rule for Regexp {
             .is_one_of([TokenKind::tREGEXP_BEG, TokenKind::tDIVIDE])
     }
 
-    fn parse(parser: &mut Parser) -> Self::Output {
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
         let begin_t = parser.current_token();
 
         if begin_t.is(TokenKind::tDIVIDE) {
@@ -361,11 +453,11 @@ impl Rule for Regexp {
         }
         parser.skip_token();
 
-        let parts = StringContents::parse(parser);
-        let end_t = parser.expect_token(TokenKind::tSTRING_END);
+        let parts = StringContents::parse(parser)?;
+        let end_t = parser.expect_token(TokenKind::tSTRING_END)?;
 
         let options = Builder::regexp_options(&end_t, parser.buffer());
-        Builder::regexp_compose(begin_t, parts, end_t, options)
+        Ok(Builder::regexp_compose(begin_t, parts, end_t, options))
     }
 }
 #[test]
@@ -416,13 +508,13 @@ impl Rule for Words {
         parser.current_token().is(TokenKind::tWORDS_BEG)
     }
 
-    fn parse(parser: &mut Parser) -> Self::Output {
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
         let begin_t = parser.take_token();
         type SpToken = ExactToken<{ TokenKind::tSP as u8 }>;
-        let (elements, _spaces) = SeparatedBy::<Word, SpToken>::parse(parser);
-        let end_t = parser.expect_token(TokenKind::tSTRING_END);
+        let (elements, _spaces) = SeparatedBy::<Word, SpToken>::parse(parser)?;
+        let end_t = parser.expect_token(TokenKind::tSTRING_END)?;
 
-        Builder::words_compose(begin_t, elements, end_t)
+        Ok(Builder::words_compose(begin_t, elements, end_t))
     }
 }
 #[test]
@@ -448,9 +540,9 @@ impl Rule for Word {
         StringContent::starts_now(parser)
     }
 
-    fn parse(parser: &mut Parser) -> Self::Output {
-        let parts = AtLeastOnce::<StringContent>::parse(parser);
-        Builder::word(parts)
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
+        let parts = AtLeastOnce::<StringContent>::parse(parser)?;
+        Ok(Builder::word(parts))
     }
 }
 
@@ -462,20 +554,20 @@ impl Rule for Symbols {
         parser.current_token().is(TokenKind::tSYMBOLS_BEG)
     }
 
-    fn parse(parser: &mut Parser) -> Self::Output {
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
         let begin_t = parser.take_token();
 
         type StringToken = ExactToken<{ TokenKind::tSTRING_CONTENT as u8 }>;
         type SpToken = ExactToken<{ TokenKind::tSP as u8 }>;
-        let (elements, _spaces) = SeparatedBy::<StringToken, SpToken>::parse(parser);
+        let (elements, _spaces) = SeparatedBy::<StringToken, SpToken>::parse(parser)?;
         let elements = elements
             .into_iter()
             .map(|token| *Builder::string_internal(token, parser.buffer()))
             .collect::<Vec<_>>();
 
-        let end_t = parser.expect_token(TokenKind::tSTRING_END);
+        let end_t = parser.expect_token(TokenKind::tSTRING_END)?;
 
-        Builder::symbols_compose(begin_t, elements, end_t)
+        Ok(Builder::symbols_compose(begin_t, elements, end_t))
     }
 }
 
@@ -502,20 +594,20 @@ impl Rule for QWords {
         parser.current_token().is(TokenKind::tQWORDS_BEG)
     }
 
-    fn parse(parser: &mut Parser) -> Self::Output {
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
         let begin_t = parser.take_token();
 
         type StringToken = ExactToken<{ TokenKind::tSTRING_CONTENT as u8 }>;
         type SpToken = ExactToken<{ TokenKind::tSP as u8 }>;
-        let (elements, _spaces) = SeparatedBy::<StringToken, SpToken>::parse(parser);
+        let (elements, _spaces) = SeparatedBy::<StringToken, SpToken>::parse(parser)?;
         let elements = elements
             .into_iter()
             .map(|token| *Builder::string_internal(token, parser.buffer()))
             .collect::<Vec<_>>();
 
-        let end_t = parser.expect_token(TokenKind::tSTRING_END);
+        let end_t = parser.expect_token(TokenKind::tSTRING_END)?;
 
-        Builder::words_compose(begin_t, elements, end_t)
+        Ok(Builder::words_compose(begin_t, elements, end_t))
     }
 }
 #[test]
@@ -541,20 +633,20 @@ impl Rule for QSymbols {
         parser.current_token().is(TokenKind::tQSYMBOLS_BEG)
     }
 
-    fn parse(parser: &mut Parser) -> Self::Output {
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
         let begin_t = parser.take_token();
 
         type StringToken = ExactToken<{ TokenKind::tSTRING_CONTENT as u8 }>;
         type SpToken = ExactToken<{ TokenKind::tSP as u8 }>;
-        let (elements, _spaces) = SeparatedBy::<StringToken, SpToken>::parse(parser);
+        let (elements, _spaces) = SeparatedBy::<StringToken, SpToken>::parse(parser)?;
         let elements = elements
             .into_iter()
             .map(|token| *Builder::symbol_internal(token, parser.buffer()))
             .collect::<Vec<_>>();
 
-        let end_t = parser.expect_token(TokenKind::tSTRING_END);
+        let end_t = parser.expect_token(TokenKind::tSTRING_END)?;
 
-        Builder::symbols_compose(begin_t, elements, end_t)
+        Ok(Builder::symbols_compose(begin_t, elements, end_t))
     }
 }
 #[test]
@@ -579,14 +671,14 @@ impl Rule for StringContent {
     type Output = Box<Node>;
 
     fn starts_now(parser: &mut Parser) -> bool {
-        at_most_one_is_true([
+        any_is_true([
             PlainStringContent::starts_now(parser),
             StringDvarContent::starts_now(parser),
             InterpolatedStringContent::starts_now(parser),
         ])
     }
 
-    fn parse(parser: &mut Parser) -> Self::Output {
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
         if PlainStringContent::starts_now(parser) {
             PlainStringContent::parse(parser)
         } else if StringDvarContent::starts_now(parser) {
@@ -594,7 +686,7 @@ impl Rule for StringContent {
         } else if InterpolatedStringContent::starts_now(parser) {
             InterpolatedStringContent::parse(parser)
         } else {
-            unreachable!()
+            Err(ParseError::unexpected_token(None, parser.current_token()))
         }
     }
 }
@@ -607,9 +699,9 @@ impl Rule for PlainStringContent {
         parser.current_token().is(TokenKind::tSTRING_CONTENT)
     }
 
-    fn parse(parser: &mut Parser) -> Self::Output {
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
         let string_t = parser.take_token();
-        Builder::string_internal(string_t, parser.buffer())
+        Ok(Builder::string_internal(string_t, parser.buffer()))
     }
 }
 
@@ -621,7 +713,7 @@ impl Rule for StringDvarContent {
         parser.current_token().is(TokenKind::tSTRING_DVAR)
     }
 
-    fn parse(parser: &mut Parser) -> Self::Output {
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
         let _string_dvar_t = parser.take_token();
         StringDvar::parse(parser)
     }
@@ -635,14 +727,10 @@ impl Rule for InterpolatedStringContent {
         parser.current_token().is(TokenKind::tSTRING_DBEG)
     }
 
-    fn parse(parser: &mut Parser) -> Self::Output {
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
         let begin_t = parser.take_token();
-        let compstmt = Compstmt::parse(parser);
-        let end_t = if parser.current_token().is(TokenKind::tSTRING_DEND) {
-            parser.take_token()
-        } else {
-            panic!("wrong token type")
-        };
+        let compstmt = Compstmt::parse(parser)?;
+        let end_t = parser.expect_token(TokenKind::tSTRING_DEND)?;
 
         let stmts = if let Some(compstmt) = compstmt {
             vec![*compstmt]
@@ -650,7 +738,7 @@ impl Rule for InterpolatedStringContent {
             vec![]
         };
 
-        Builder::begin(begin_t, stmts, end_t)
+        Ok(Builder::begin(begin_t, stmts, end_t))
     }
 }
 
@@ -659,7 +747,7 @@ impl Rule for StringDvar {
     type Output = Box<Node>;
 
     fn starts_now(parser: &mut Parser) -> bool {
-        at_most_one_is_true([
+        any_is_true([
             Ivar::starts_now(parser),
             Gvar::starts_now(parser),
             Cvar::starts_now(parser),
@@ -667,7 +755,7 @@ impl Rule for StringDvar {
         ])
     }
 
-    fn parse(parser: &mut Parser) -> Self::Output {
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
         if Ivar::starts_now(parser) {
             Ivar::parse(parser)
         } else if Gvar::starts_now(parser) {
@@ -677,7 +765,7 @@ impl Rule for StringDvar {
         } else if BackRef::starts_now(parser) {
             BackRef::parse(parser)
         } else {
-            unreachable!()
+            Err(ParseError::unexpected_token(None, parser.current_token()))
         }
     }
 }
@@ -687,7 +775,7 @@ impl Rule for SymT {
     type Output = Token;
 
     fn starts_now(parser: &mut Parser) -> bool {
-        at_most_one_is_true([
+        any_is_true([
             FnameT::starts_now(parser),
             parser.current_token().is(TokenKind::tIVAR),
             parser.current_token().is(TokenKind::tCVAR),
@@ -695,11 +783,11 @@ impl Rule for SymT {
         ])
     }
 
-    fn parse(parser: &mut Parser) -> Self::Output {
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
         if Self::starts_now(parser) {
-            parser.take_token()
+            Ok(parser.take_token())
         } else {
-            unreachable!()
+            Err(ParseError::unexpected_token(None, parser.current_token()))
         }
     }
 }
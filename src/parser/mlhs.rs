@@ -1,18 +1,267 @@
-use crate::{builder::Builder, parser::base::Rule, token::TokenKind, Node, Parser};
+use crate::{
+    builder::Builder,
+    error::{ParseError, ParseErrorKind, ParseResult},
+    nodes::{
+        Begin, CSend, Casgn, Cvasgn, Gvasgn, IndexAsgn, Ivasgn, Lvar, Lvasgn, Mlhs, Send, Splat,
+    },
+    parser::{base::Rule, Mrhs, Value},
+    token::TokenKind,
+    Node, Parser,
+};
 
+// Targets of the multiple assignment: `a, (b, *c), d`, `(a, b)` or `*`
 pub(crate) struct MLHS;
 impl Rule for MLHS {
     type Output = Box<Node>;
 
     fn starts_now(parser: &mut Parser) -> bool {
-        todo!()
+        MlhsItem::starts_now(parser)
     }
 
-    fn parse(parser: &mut Parser) -> Self::Output {
-        todo!()
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
+        let head = MlhsItem::parse(parser)?;
+        Self::parse_tail(parser, *head)
     }
 }
 
+impl MLHS {
+    /// Parses the rest of the list after its first item,
+    /// the item itself can be a value parsed before it's clear that it's an assignment
+    pub(crate) fn parse_with_head(parser: &mut Parser, head: Box<Node>) -> ParseResult<Box<Node>> {
+        let head = into_assignable(parser, head)?;
+        Self::parse_tail(parser, *head)
+    }
+
+    fn parse_tail(parser: &mut Parser, head: Node) -> ParseResult<Box<Node>> {
+        let mut items = vec![head];
+        let mut has_commas = false;
+
+        while parser.current_token().is(TokenKind::tCOMMA) {
+            // `a, = 1` is valid
+            parser.skip_token();
+            has_commas = true;
+            if !MlhsItem::starts_now(parser) {
+                break;
+            }
+            items.push(*MlhsItem::parse(parser)?);
+        }
+
+        // `(a, b)` is the whole list, not an item of it
+        if !has_commas
+            && matches!(
+                items[0],
+                Node::Mlhs(Mlhs {
+                    begin_l: Some(_),
+                    ..
+                })
+            )
+        {
+            return Ok(Box::new(items.remove(0)));
+        }
+        Ok(Builder::multi_lhs(None, items, None))
+    }
+}
+
+// Statement that starts with a list of assignment targets: `a, b = 1, 2`.
+// `head` is the first item that has been parsed by the caller as a value (if any).
+//
+// When the list is followed by `)` it's returned as is,
+// so `(a, b) = 1, 2` becomes an assignment to parenthesized mlhs.
+pub(crate) fn parse_masgn_stmt(
+    parser: &mut Parser,
+    head: Option<Box<Node>>,
+) -> ParseResult<Box<Node>> {
+    let lhs = match head {
+        Some(head) => MLHS::parse_with_head(parser, head)?,
+        None => MLHS::parse(parser)?,
+    };
+    if parser.current_token().is(TokenKind::tRPAREN) {
+        return Ok(lhs);
+    }
+    let eql_t = parser.expect_token(TokenKind::tEQL)?;
+    let rhs = parse_masgn_rhs(parser)?;
+    // `a, b = raise rescue [1, 2]` rescues the right side only
+    let rhs = Value::parse_stmt_modifiers(parser, rhs, [TokenKind::kRESCUE])?;
+    Ok(Builder::multi_assign(lhs, eql_t, rhs))
+}
+
+// Statement that assigns a list of values to a single target: `a = 1, 2`.
+// `asgn` is the assignment of the first value that has been parsed by the caller.
+pub(crate) fn parse_mrhs_assign(parser: &mut Parser, asgn: Box<Node>) -> ParseResult<Box<Node>> {
+    parser.expect_token(TokenKind::tCOMMA)?;
+    if !Mrhs::starts_now(parser) {
+        return Err(ParseError::unexpected_token(None, parser.current_token()));
+    }
+    let rest = Mrhs::parse(parser)?;
+    Ok(Builder::assign_mrhs(asgn, rest))
+}
+
+// Returns true for `a = 1`, `a.b = 1` or `a[1] = 1` that can take more values after a comma
+pub(crate) fn is_single_assignment(node: &Node) -> bool {
+    match node {
+        Node::Lvasgn(Lvasgn { value, .. })
+        | Node::Ivasgn(Ivasgn { value, .. })
+        | Node::Gvasgn(Gvasgn { value, .. })
+        | Node::Cvasgn(Cvasgn { value, .. })
+        | Node::Casgn(Casgn { value, .. })
+        | Node::IndexAsgn(IndexAsgn { value, .. }) => value.is_some(),
+        Node::Send(Send {
+            operator_l, args, ..
+        })
+        | Node::CSend(CSend {
+            operator_l, args, ..
+        }) => operator_l.is_some() && args.len() == 1,
+        _ => false,
+    }
+}
+
+// Right side of the multiple assignment: `1`, `1, 2` or `*list`
+pub(crate) fn parse_masgn_rhs(parser: &mut Parser) -> ParseResult<Box<Node>> {
+    if !Mrhs::starts_now(parser) {
+        return Err(ParseError::unexpected_token(None, parser.current_token()));
+    }
+    let mut items = Mrhs::parse(parser)?;
+    if items.len() == 1 && !matches!(items[0], Node::Splat(_)) {
+        Ok(Box::new(items.remove(0)))
+    } else {
+        Ok(Builder::array(None, items, None))
+    }
+}
+
+struct MlhsItem;
+impl Rule for MlhsItem {
+    type Output = Box<Node>;
+
+    fn starts_now(parser: &mut Parser) -> bool {
+        let token = parser.current_token();
+        token.is(TokenKind::tSTAR) || token.is(TokenKind::tLPAREN) || Value::starts_now(parser)
+    }
+
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
+        if parser.current_token().is(TokenKind::tSTAR) {
+            // *a or just *
+            let star_t = parser.take_token();
+            if Value::starts_now(parser) {
+                let value = Value::parse_primary(parser)?;
+                let value = into_assignable(parser, value)?;
+                Ok(Builder::splat(star_t, value))
+            } else {
+                Ok(Builder::nameless_splat(star_t))
+            }
+        } else if parser.current_token().is(TokenKind::tLPAREN) {
+            // (a, b)
+            let begin_t = parser.take_token();
+            let mut items = vec![*MlhsItem::parse(parser)?];
+            while parser.current_token().is(TokenKind::tCOMMA) {
+                parser.skip_token();
+                if !MlhsItem::starts_now(parser) {
+                    break;
+                }
+                items.push(*MlhsItem::parse(parser)?);
+            }
+            let end_t = parser.expect_token(TokenKind::tRPAREN)?;
+            Ok(Builder::multi_lhs(Some(begin_t), items, Some(end_t)))
+        } else {
+            // a, @a, A, a.b, a[1] etc
+            // values can't take binary operators, `a, b = 1` is not `a, (b = 1)`
+            let value = Value::parse_primary(parser)?;
+            into_assignable(parser, value)
+        }
+    }
+}
+
+// Converts a node that has been parsed as a value into a target of the assignment
+pub(crate) fn into_assignable(parser: &Parser, node: Box<Node>) -> ParseResult<Box<Node>> {
+    match *node {
+        Node::Mlhs(_) => Ok(node),
+
+        Node::Splat(Splat {
+            value,
+            operator_l,
+            expression_l,
+        }) => {
+            let value = match value {
+                Some(value) => Some(into_assignable(parser, value)?),
+                None => None,
+            };
+            Ok(Box::new(Node::Splat(Splat {
+                value,
+                operator_l,
+                expression_l,
+            })))
+        }
+
+        // ((a)), b = 1
+        Node::Begin(Begin {
+            mut statements,
+            begin_l: begin_l @ Some(_),
+            end_l: end_l @ Some(_),
+            expression_l,
+        }) if statements.len() == 1 => {
+            let item = into_assignable(parser, Box::new(statements.remove(0)))?;
+            Ok(Box::new(Node::Mlhs(Mlhs {
+                items: vec![*item],
+                begin_l,
+                end_l,
+                expression_l,
+            })))
+        }
+
+        // undeclared local variable that is parsed as a method call
+        Node::Send(Send {
+            recv: None,
+            method_name,
+            args,
+            begin_l: None,
+            expression_l,
+            ..
+        }) if args.is_empty() && is_lvar_name(method_name.as_bytes()) => {
+            let lvar = Node::Lvar(Lvar {
+                name: method_name,
+                expression_l,
+            });
+            Builder::assignable(lvar, parser.static_env(), parser.diagnostics())
+        }
+
+        Node::Send(Send {
+            recv: Some(_),
+            dot_l: Some(_),
+            ref args,
+            begin_l: None,
+            ..
+        }) if args.is_empty() => Builder::call_assignable(*node),
+        Node::CSend(ref csend) if csend.args.is_empty() && csend.begin_l.is_none() => {
+            Builder::call_assignable(*node)
+        }
+        Node::Index(_) => Builder::call_assignable(*node),
+
+        Node::Lvar(_)
+        | Node::Ivar(_)
+        | Node::Gvar(_)
+        | Node::Cvar(_)
+        | Node::Const(_)
+        | Node::Self_(_)
+        | Node::Nil(_)
+        | Node::True(_)
+        | Node::False(_)
+        | Node::File(_)
+        | Node::Line(_)
+        | Node::Encoding(_)
+        | Node::BackRef(_)
+        | Node::NthRef(_) => Builder::assignable(*node, parser.static_env(), parser.diagnostics()),
+
+        other => Err(ParseError::new(
+            ParseErrorKind::InvalidAssignmentTarget,
+            *other.expression(),
+        )),
+    }
+}
+
+fn is_lvar_name(name: &[u8]) -> bool {
+    matches!(name.first(), Some(b'a'..=b'z' | b'_' | 0x80..))
+        && !matches!(name.last(), Some(b'?' | b'!'))
+}
+
 #[cfg(test)]
 mod tests {
     use super::MLHS;
@@ -20,73 +269,79 @@ mod tests {
 
     #[test]
     fn test_lhs_user_variable() {
-        debug_assert!(false, "implement me");
-
         assert_parses_rule!(
             MLHS,
             b"a, b",
             r#"
-s(:begin,
-  s(:lvar, "a"),
-  s(:lvar, "b"))
+s(:mlhs,
+  s(:lvasgn, "a"),
+  s(:lvasgn, "b"))
             "#
         )
     }
 
     #[test]
     fn test_lhs_parenthesized() {
-        debug_assert!(false, "implement me");
-
         assert_parses_rule!(
             MLHS,
             b"((a))",
             r#"
-s(:begin,
-  s(:begin,
-    s(:lvar, "a")))
+s(:mlhs,
+  s(:mlhs,
+    s(:lvasgn, "a")))
             "#
         )
     }
 
     #[test]
     fn test_mlhs_without_parens() {
-        debug_assert!(false, "implement me");
-
         assert_parses_rule!(
             MLHS,
             b"a, *b, c",
             r#"
-s(:begin,
-  s(:lvar, "a"),
+s(:mlhs,
+  s(:lvasgn, "a"),
   s(:splat,
-    s(:lvar, "b")),
-  s(:lvar, "c"))
+    s(:lvasgn, "b")),
+  s(:lvasgn, "c"))
             "#
         )
     }
 
     #[test]
     fn test_mlhs_with_parens() {
-        debug_assert!(false, "implement me");
-
         assert_parses_rule!(
             MLHS,
             b"((*a), @b, $c)",
             r#"
-s(:begin,
-  s(:begin,
+s(:mlhs,
+  s(:mlhs,
     s(:splat,
-      s(:lvar, "a"))),
-  s(:ivar, "@b"),
-  s(:gvar, "$c"))
+      s(:lvasgn, "a"))),
+  s(:ivasgn, "@b"),
+  s(:gvasgn, "$c"))
             "#
         );
     }
 
     #[test]
     fn test_nameless_splat() {
-        debug_assert!(false, "implement me");
+        assert_parses_rule!(MLHS, b"*", "s(:mlhs,\n  s(:splat))");
+    }
 
-        assert_parses_rule!(MLHS, b"*", "s(:splat)");
+    #[test]
+    fn test_attr_and_index_targets() {
+        assert_parses_rule!(
+            MLHS,
+            b"self.x, h[:k]",
+            r#"
+s(:mlhs,
+  s(:send,
+    s(:self), "x="),
+  s(:indexasgn,
+    s(:send, nil, "h"),
+    s(:sym, "k")))
+            "#
+        );
     }
 }
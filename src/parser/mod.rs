@@ -1,18 +1,26 @@
 use crate::buffer::Buffer;
+use crate::comment::Comment;
+use crate::current_arg_stack::CurrentArgStack;
+use crate::diagnostic::{Diagnostic, Diagnostics};
+use crate::error::{ParseError, ParseResult};
 use crate::lexer::Lexer;
+use crate::magic_comment::MagicComment;
+use crate::max_numparam_stack::MaxNumparamStack;
 use crate::nodes::Node;
+use crate::static_environment::StaticEnvironment;
 use crate::token::{Token, TokenKind};
+use crate::Loc;
 
 mod alias;
 pub(crate) use alias::Alias;
 
 mod args;
-pub(crate) use args::{Args, CallArgs, Mrhs, ParenArgs};
+pub(crate) use args::{is_signed_number, Args, CallArgs, Mrhs, ParenArgs};
 
 mod array;
 pub(crate) use array::Array;
 
-mod base;
+pub(crate) mod base;
 
 mod block;
 pub(crate) use block::MaybeBlock;
@@ -21,7 +29,7 @@ mod case;
 pub(crate) use case::Case;
 
 mod def_method;
-pub(crate) use def_method::{EndlessMethodDef, MethodDef};
+pub(crate) use def_method::MethodDef;
 
 mod def_module_class;
 pub(crate) use def_module_class::{Class, Module};
@@ -45,13 +53,16 @@ mod literal;
 pub(crate) use literal::{Literal, StringContents, Symbol};
 
 mod mlhs;
-pub(crate) use mlhs::MLHS;
+pub(crate) use mlhs::{
+    into_assignable, is_single_assignment, parse_masgn_rhs, parse_masgn_stmt, parse_mrhs_assign,
+    MLHS,
+};
 
 mod params;
-pub(crate) use params::Params;
+pub(crate) use params::{BlockParams, LambdaParams, Params};
 
 mod pattern_matching;
-// pub(crate) use pattern_matching::{PCaseBody, PTopExprBody};
+pub(crate) use pattern_matching::{PCaseBody, PTopExprBody};
 
 mod postexe;
 pub(crate) use postexe::Postexe;
@@ -80,8 +91,35 @@ pub(crate) use undef::{Fitem, Undef};
 mod value;
 pub(crate) use value::Value;
 
+/// Output of the `Parser::parse`
+#[derive(Debug)]
+pub struct ParserResult {
+    /// AST, `None` if input is empty
+    pub ast: Option<Box<Node>>,
+
+    /// Warnings and non-fatal errors reported by the lexer and the builder
+    pub diagnostics: Vec<Diagnostic>,
+
+    /// Comments in the order of appearance
+    pub comments: Vec<Comment>,
+
+    /// Magic comments like `# frozen_string_literal: true`
+    pub magic_comments: Vec<MagicComment>,
+
+    /// Location of the data after `__END__` (if any)
+    pub data_section_l: Option<Loc>,
+
+    /// Source code that has been parsed,
+    /// can be used to convert locations to lines and columns
+    pub input: Buffer,
+}
+
 pub struct Parser {
     lexer: Lexer,
+    static_env: StaticEnvironment,
+    current_arg_stack: CurrentArgStack,
+    max_numparam_stack: MaxNumparamStack,
+    do_block_allowed: bool,
     debug: bool,
 }
 
@@ -89,6 +127,10 @@ impl Parser {
     pub fn new(input: &[u8]) -> Self {
         Self {
             lexer: Lexer::new(input),
+            static_env: StaticEnvironment::new(),
+            current_arg_stack: CurrentArgStack::new(),
+            max_numparam_stack: MaxNumparamStack::new(),
+            do_block_allowed: true,
             debug: false,
         }
     }
@@ -106,14 +148,18 @@ impl Parser {
         self.lexer.skip_token()
     }
 
-    pub(crate) fn expect_token(&mut self, expected: TokenKind) -> Token {
+    pub(crate) fn newline_before_current_token(&mut self) -> Option<Token> {
+        self.lexer.newline_before_current_token()
+    }
+
+    pub(crate) fn expect_token(&mut self, expected: TokenKind) -> ParseResult<Token> {
         let token = self.current_token();
         self.skip_token();
 
         if token.is(expected) {
-            token
+            Ok(token)
         } else {
-            panic!("Expected token {:?}, got {:?}", expected, token)
+            Err(ParseError::unexpected_token(Some(expected), token))
         }
     }
 
@@ -123,12 +169,85 @@ impl Parser {
         token
     }
 
-    pub fn parse(&mut self) -> Option<Box<Node>> {
+    pub fn parse(&mut self) -> Result<ParserResult, ParseError> {
         use base::Rule;
-        Program::parse(self)
+        let result = Program::parse(self);
+
+        // lexer emits EOF when it fails,
+        // so its error is more precise than whatever parser got
+        let result = match self.lexer.error.take() {
+            Some(error) => Err(error),
+            None => result,
+        };
+        let ast = match result {
+            Ok(ast) => ast,
+            Err(mut error) => {
+                error.diagnostics = self.lexer.diagnostics.take_inner();
+                return Err(error);
+            }
+        };
+
+        Ok(ParserResult {
+            ast,
+            diagnostics: self.lexer.diagnostics.take_inner(),
+            comments: self.take_comments(),
+            magic_comments: std::mem::take(&mut self.lexer.magic_comments),
+            data_section_l: self.lexer.data_section_l,
+            input: self.lexer.buffer.take_buffer(),
+        })
     }
 
     pub(crate) fn buffer(&self) -> &Buffer {
         self.lexer.buffer.for_lookahead()
     }
+
+    pub(crate) fn diagnostics(&self) -> &Diagnostics {
+        &self.lexer.diagnostics
+    }
+
+    pub(crate) fn take_comments(&mut self) -> Vec<Comment> {
+        std::mem::take(&mut self.lexer.comments)
+    }
+
+    pub(crate) fn static_env(&self) -> &StaticEnvironment {
+        &self.static_env
+    }
+
+    pub(crate) fn current_arg_stack(&self) -> &CurrentArgStack {
+        &self.current_arg_stack
+    }
+
+    pub(crate) fn max_numparam_stack(&self) -> &MaxNumparamStack {
+        &self.max_numparam_stack
+    }
+
+    // `do` after a command argument (`foo bar do end`) or a loop condition (`while foo do end`)
+    // belongs to the command (or to the loop), so such values can't take `do` blocks.
+    // Anything in parentheses/brackets/braces and any nested statements can take them again.
+    pub(crate) fn with_do_block_allowed<T>(
+        &mut self,
+        allowed: bool,
+        f: impl FnOnce(&mut Self) -> T,
+    ) -> T {
+        let prev = std::mem::replace(&mut self.do_block_allowed, allowed);
+        let result = f(self);
+        self.do_block_allowed = prev;
+        result
+    }
+
+    pub(crate) fn do_block_allowed(&self) -> bool {
+        self.do_block_allowed
+    }
+}
+
+#[test]
+fn test_expect_token_mismatch() {
+    use crate::{error::ParseErrorKind, loc::loc};
+
+    let mut parser = Parser::new(b"foo");
+    let error = parser.expect_token(TokenKind::kEND).unwrap_err();
+    assert_eq!(error.kind, ParseErrorKind::UnexpectedToken);
+    assert_eq!(error.loc, loc!(0, 3));
+    assert_eq!(error.expected, Some(TokenKind::kEND));
+    assert_eq!(error.found, Some(TokenKind::tIDENTIFIER));
 }
@@ -1,10 +1,11 @@
 use crate::{
     builder::Builder,
+    error::{ParseError, ParseResult},
     parser::{
-        base::{at_most_one_is_true, ExactToken, Rule, SeparatedBy},
+        base::{any_is_true, ExactToken, Rule, SeparatedBy},
         Value,
     },
-    Node, Parser, TokenKind,
+    Node, Parser, Token, TokenKind,
 };
 
 pub(crate) struct Params;
@@ -15,16 +16,38 @@ impl Rule for Params {
         true
     }
 
-    fn parse(parser: &mut Parser) -> Self::Output {
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
         type CommaT = ExactToken<{ TokenKind::tCOMMA as u8 }>;
-        type R = SeparatedBy<Param, CommaT>;
+        type R = SeparatedBy<MethodParam, CommaT>;
 
-        let (args, _commas) = R::parse(parser);
+        let (args, _commas) = R::parse(parser)?;
         // TODO: There must be runtime validations:
         // 1. params are ordered
         //    req -> opt -> (single) rest -> post -> kw[req/opt/rest] -> block
 
-        args
+        Ok(args)
+    }
+}
+
+// Method params can also forward all arguments with `...`
+struct MethodParam;
+impl Rule for MethodParam {
+    type Output = Box<Node>;
+
+    fn starts_now(parser: &mut Parser) -> bool {
+        any_is_true([
+            parser.current_token().is(TokenKind::tDOT3),
+            Param::starts_now(parser),
+        ])
+    }
+
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
+        if parser.current_token().is(TokenKind::tDOT3) {
+            let dots_t = parser.take_token();
+            Ok(Builder::forward_arg(dots_t))
+        } else {
+            Param::parse(parser)
+        }
     }
 }
 
@@ -35,7 +58,7 @@ impl Rule for Param {
     fn starts_now(parser: &mut Parser) -> bool {
         let token = parser.current_token();
 
-        at_most_one_is_true([
+        any_is_true([
             Restarg::starts_now(parser),
             Kwrestarg::starts_now(parser),
             Blockarg::starts_now(parser),
@@ -45,39 +68,50 @@ impl Rule for Param {
         ])
     }
 
-    fn parse(parser: &mut Parser) -> Self::Output {
-        if Restarg::starts_now(parser) {
-            Restarg::parse(parser)
-        } else if Kwrestarg::starts_now(parser) {
-            Kwrestarg::parse(parser)
-        } else if Blockarg::starts_now(parser) {
-            Blockarg::parse(parser)
-        } else if ParenthesizedMultiArg::starts_now(parser) {
-            ParenthesizedMultiArg::parse(parser)
-        } else if parser.current_token().is(TokenKind::tIDENTIFIER) {
-            let name_t = parser.take_token();
-            if parser.current_token().is(TokenKind::tEQL) {
-                // (a = 1)
-                let eql_t = parser.take_token();
-                let default = Value::parse(parser);
-                Builder::optarg(name_t, eql_t, default, parser.buffer())
-            } else {
-                // just (a)
-                Builder::arg(name_t, parser.buffer())
-            }
-        } else if parser.current_token().is(TokenKind::tLABEL) {
-            let name_t = parser.take_token();
-            if Value::starts_now(parser) {
-                // (a: 1)
-                let default = Value::parse(parser);
-                Builder::kwoptarg(name_t, default, parser.buffer())
-            } else {
-                // just (a:)
-                Builder::kwarg(name_t, parser.buffer())
-            }
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
+        parse_param(parser, Value::parse)
+    }
+}
+
+// Block params share everything with method params except default values:
+// `|a = 1 | 2|` is invalid, so they are parsed by `parse_default`
+fn parse_param(
+    parser: &mut Parser,
+    parse_default: fn(&mut Parser) -> ParseResult<Box<Node>>,
+) -> ParseResult<Box<Node>> {
+    if Restarg::starts_now(parser) {
+        Restarg::parse(parser)
+    } else if Kwrestarg::starts_now(parser) {
+        Kwrestarg::parse(parser)
+    } else if Blockarg::starts_now(parser) {
+        Blockarg::parse(parser)
+    } else if ParenthesizedMultiArg::starts_now(parser) {
+        ParenthesizedMultiArg::parse(parser)
+    } else if parser.current_token().is(TokenKind::tIDENTIFIER) {
+        let name_t = parser.take_token();
+        declare_arg(parser, name_t);
+        if parser.current_token().is(TokenKind::tEQL) {
+            // (a = 1)
+            let eql_t = parser.take_token();
+            let default = parse_default_value(parser, name_t, parse_default)?;
+            Ok(Builder::optarg(name_t, eql_t, default, parser.buffer()))
+        } else {
+            // just (a)
+            Ok(Builder::arg(name_t, parser.buffer()))
+        }
+    } else if parser.current_token().is(TokenKind::tLABEL) {
+        let name_t = parser.take_token();
+        declare_arg(parser, name_t);
+        if Value::starts_now(parser) {
+            // (a: 1)
+            let default = parse_default_value(parser, name_t, parse_default)?;
+            Ok(Builder::kwoptarg(name_t, default, parser.buffer()))
         } else {
-            unreachable!()
+            // just (a:)
+            Ok(Builder::kwarg(name_t, parser.buffer()))
         }
+    } else {
+        Err(ParseError::unexpected_token(None, parser.current_token()))
     }
 }
 #[test]
@@ -123,6 +157,14 @@ fn test_blockarg() {
     crate::testing::assert_parses_rule!(Param, b"&a", "s(:blockarg, \"a\")")
 }
 #[test]
+fn test_anonymous_blockarg() {
+    crate::testing::assert_parses_rule!(Param, b"&", "s(:blockarg)")
+}
+#[test]
+fn test_forward_arg() {
+    crate::testing::assert_parses_rule!(MethodParam, b"...", "s(:forward_arg)")
+}
+#[test]
 fn test_multi_arg() {
     crate::testing::assert_parses_rule!(
         Param,
@@ -138,6 +180,226 @@ s(:mlhs,
     )
 }
 
+fn declare_arg(parser: &Parser, name_t: Token) {
+    let name = parser
+        .buffer()
+        .slice(name_t.loc.start, name_t.loc.end)
+        .expect("bug: token is out of bounds");
+    parser.static_env().declare(name);
+}
+
+// Default value of the argument can't reference the argument itself
+fn parse_default_value(
+    parser: &mut Parser,
+    name_t: Token,
+    parse_default: fn(&mut Parser) -> ParseResult<Box<Node>>,
+) -> ParseResult<Box<Node>> {
+    let name = parser
+        .buffer()
+        .slice(name_t.loc.start, name_t.loc.end)
+        .expect("bug: token is out of bounds");
+    parser.current_arg_stack().push(name);
+    let default = parse_default(parser);
+    parser.current_arg_stack().pop();
+    default
+}
+#[test]
+fn test_circular_argument_reference() {
+    use crate::diagnostic::{Diagnostic, DiagnosticLevel, DiagnosticMessage};
+    use crate::loc::loc;
+
+    let mut parser = Parser::new(b"a = a");
+    assert!(Param::starts_now(&mut parser));
+    let ast = Param::parse(&mut parser).unwrap();
+    assert_eq!(ast.inspect(0), "s(:optarg, \"a\",\n  s(:lvar, \"a\"))");
+    assert_eq!(
+        parser.diagnostics().take_inner(),
+        vec![Diagnostic {
+            level: DiagnosticLevel::Error,
+            message: DiagnosticMessage::CircularArgumentReference {
+                arg_name: "a".to_string()
+            },
+            loc: loc!(4, 5)
+        }]
+    );
+}
+#[test]
+fn test_args_are_declared() {
+    let mut parser = Parser::new(b"a, b = a");
+    let args = Params::parse(&mut parser).unwrap();
+    assert_eq!(args[1].inspect(0), "s(:optarg, \"b\",\n  s(:lvar, \"a\"))");
+    assert!(parser.diagnostics().take_inner().is_empty());
+}
+
+pub(crate) struct BlockParams;
+impl Rule for BlockParams {
+    type Output = Box<Node>;
+
+    fn starts_now(parser: &mut Parser) -> bool {
+        let token = parser.current_token();
+        token.is(TokenKind::tPIPE) || token.is(TokenKind::tOROP)
+    }
+
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
+        type CommaT = ExactToken<{ TokenKind::tCOMMA as u8 }>;
+
+        if parser.current_token().is(TokenKind::tOROP) {
+            // `||` is a single token
+            let pipes_t = parser.take_token();
+            return Ok(block_args(pipes_t, vec![], pipes_t));
+        }
+
+        let begin_t = parser.take_token();
+        let (mut args, commas) = SeparatedBy::<BlockParam, CommaT>::parse(parser)?;
+
+        // `|a|` and `|(a, b)|` auto-splat a single array argument, `|a,|` doesn't
+        if args.len() == 1 && commas.is_empty() && matches!(args[0], Node::Arg(_) | Node::Mlhs(_)) {
+            let arg = args.pop().unwrap();
+            args.push(*Builder::procarg0(Box::new(arg)));
+        }
+
+        args.extend(parse_shadowargs(parser)?);
+
+        let end_t = parser.expect_token(TokenKind::tPIPE)?;
+        Ok(block_args(begin_t, args, end_t))
+    }
+}
+
+fn block_args(begin_t: Token, args: Vec<Node>, end_t: Token) -> Box<Node> {
+    Builder::args(Some(begin_t), args, Some(end_t)).expect("bug: args with pipes can't be omitted")
+}
+#[test]
+fn test_block_params() {
+    crate::testing::assert_parses_rule!(
+        BlockParams,
+        b"|a, (b, c), *d, k:, &blk; e|",
+        r#"
+s(:args,
+  s(:arg, "a"),
+  s(:mlhs,
+    s(:arg, "b"),
+    s(:arg, "c")),
+  s(:restarg, "d"),
+  s(:kwarg, "k"),
+  s(:blockarg, "blk"),
+  s(:shadowarg, "e"))
+        "#
+    )
+}
+#[test]
+fn test_block_params_procarg0() {
+    crate::testing::assert_parses_rule!(
+        BlockParams,
+        b"|a|",
+        r#"
+s(:args,
+  s(:procarg0,
+    s(:arg, "a")))
+        "#
+    )
+}
+#[test]
+fn test_block_params_trailing_comma() {
+    crate::testing::assert_parses_rule!(BlockParams, b"|a,|", "s(:args,\n  s(:arg, \"a\"))")
+}
+#[test]
+fn test_block_params_empty() {
+    crate::testing::assert_parses_rule!(BlockParams, b"||", "s(:args)")
+}
+#[test]
+fn test_block_params_optarg_is_primary() {
+    crate::testing::assert_parses_rule!(
+        BlockParams,
+        b"|a = 1|",
+        r#"
+s(:args,
+  s(:optarg, "a",
+    s(:int, "1")))
+        "#
+    )
+}
+
+pub(crate) struct LambdaParams;
+impl Rule for LambdaParams {
+    type Output = Box<Node>;
+
+    fn starts_now(parser: &mut Parser) -> bool {
+        parser.current_token().is(TokenKind::tLPAREN) || Param::starts_now(parser)
+    }
+
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
+        type CommaT = ExactToken<{ TokenKind::tCOMMA as u8 }>;
+
+        if parser.current_token().is(TokenKind::tLPAREN) {
+            // ->(a, b; shadow) {}
+            let begin_t = parser.take_token();
+            let (mut args, _commas) = SeparatedBy::<Param, CommaT>::parse(parser)?;
+            args.extend(parse_shadowargs(parser)?);
+            let end_t = parser.expect_token(TokenKind::tRPAREN)?;
+            Ok(block_args(begin_t, args, end_t))
+        } else {
+            // -> a, b {}
+            let (args, _commas) = SeparatedBy::<Param, CommaT>::parse(parser)?;
+            Ok(Builder::args(None, args, None).expect("bug: lambda params can't be empty here"))
+        }
+    }
+}
+#[test]
+fn test_lambda_params() {
+    crate::testing::assert_parses_rule!(
+        LambdaParams,
+        b"(a, b = 1; c)",
+        r#"
+s(:args,
+  s(:arg, "a"),
+  s(:optarg, "b",
+    s(:int, "1")),
+  s(:shadowarg, "c"))
+        "#
+    )
+}
+
+// Block-local variables: `; a, b`
+fn parse_shadowargs(parser: &mut Parser) -> ParseResult<Vec<Node>> {
+    type CommaT = ExactToken<{ TokenKind::tCOMMA as u8 }>;
+
+    if parser.current_token().is(TokenKind::tSEMI) {
+        parser.skip_token();
+        let (shadowargs, _commas) = SeparatedBy::<Shadowarg, CommaT>::parse(parser)?;
+        Ok(shadowargs)
+    } else {
+        Ok(vec![])
+    }
+}
+
+struct BlockParam;
+impl Rule for BlockParam {
+    type Output = Box<Node>;
+
+    fn starts_now(parser: &mut Parser) -> bool {
+        Param::starts_now(parser)
+    }
+
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
+        parse_param(parser, Value::parse_primary)
+    }
+}
+
+struct Shadowarg;
+impl Rule for Shadowarg {
+    type Output = Box<Node>;
+
+    fn starts_now(parser: &mut Parser) -> bool {
+        parser.current_token().is(TokenKind::tIDENTIFIER)
+    }
+
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
+        let name_t = parser.take_token();
+        declare_arg(parser, name_t);
+        Ok(Builder::shadowarg(name_t, parser.buffer()))
+    }
+}
+
 struct Restarg;
 impl Rule for Restarg {
     type Output = Box<Node>;
@@ -146,14 +408,16 @@ impl Rule for Restarg {
         parser.current_token().is(TokenKind::tSTAR)
     }
 
-    fn parse(parser: &mut Parser) -> Self::Output {
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
         let star_t = parser.take_token();
         let name_t = if parser.current_token().is(TokenKind::tIDENTIFIER) {
-            Some(parser.take_token())
+            let name_t = parser.take_token();
+            declare_arg(parser, name_t);
+            Some(name_t)
         } else {
             None
         };
-        Builder::restarg(star_t, name_t, parser.buffer())
+        Ok(Builder::restarg(star_t, name_t, parser.buffer()))
     }
 }
 
@@ -165,14 +429,16 @@ impl Rule for Kwrestarg {
         parser.current_token().is(TokenKind::tDSTAR)
     }
 
-    fn parse(parser: &mut Parser) -> Self::Output {
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
         let dstar_t = parser.take_token();
         let name_t = if parser.current_token().is(TokenKind::tIDENTIFIER) {
-            Some(parser.take_token())
+            let name_t = parser.take_token();
+            declare_arg(parser, name_t);
+            Some(name_t)
         } else {
             None
         };
-        Builder::kwrestarg(dstar_t, name_t, parser.buffer())
+        Ok(Builder::kwrestarg(dstar_t, name_t, parser.buffer()))
     }
 }
 
@@ -184,10 +450,17 @@ impl Rule for Blockarg {
         parser.current_token().is(TokenKind::tAMPER)
     }
 
-    fn parse(parser: &mut Parser) -> Self::Output {
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
         let amper_t = parser.take_token();
-        let name_t = parser.expect_token(TokenKind::tIDENTIFIER);
-        Builder::blockarg(amper_t, Some(name_t), parser.buffer())
+        let name_t = if parser.current_token().is(TokenKind::tIDENTIFIER) {
+            let name_t = parser.take_token();
+            declare_arg(parser, name_t);
+            Some(name_t)
+        } else {
+            // anonymous block argument, `&` is forwarded as is
+            None
+        };
+        Ok(Builder::blockarg(amper_t, name_t, parser.buffer()))
     }
 }
 
@@ -199,21 +472,11 @@ impl Rule for ParenthesizedMultiArg {
         parser.current_token().is(TokenKind::tLPAREN)
     }
 
-    fn parse(parser: &mut Parser) -> Self::Output {
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
         let begin_t = parser.take_token();
-        let items = MultiArgs::parse(parser);
-        let end_t = parser.expect_token(TokenKind::tRPAREN);
-
-        // TODO: move to builder
-        use crate::nodes::Mlhs;
-        let begin_l = begin_t.loc;
-        let end_l = end_t.loc;
-        Box::new(Node::Mlhs(Mlhs {
-            items,
-            begin_l: Some(begin_l),
-            end_l: Some(end_l),
-            expression_l: begin_l.join(&end_l),
-        }))
+        let items = MultiArgs::parse(parser)?;
+        let end_t = parser.expect_token(TokenKind::tRPAREN)?;
+        Ok(Builder::multi_lhs(Some(begin_t), items, Some(end_t)))
     }
 }
 
@@ -225,12 +488,12 @@ impl Rule for MultiArgs {
         true
     }
 
-    fn parse(parser: &mut Parser) -> Self::Output {
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
         type CommaT = ExactToken<{ TokenKind::tCOMMA as u8 }>;
         type R = SeparatedBy<MultiArg, CommaT>;
 
-        let (args, _commas) = R::parse(parser);
-        args
+        let (args, _commas) = R::parse(parser)?;
+        Ok(args)
     }
 }
 
@@ -239,23 +502,24 @@ impl Rule for MultiArg {
     type Output = Box<Node>;
 
     fn starts_now(parser: &mut Parser) -> bool {
-        at_most_one_is_true([
+        any_is_true([
             parser.current_token().is(TokenKind::tIDENTIFIER),
             Restarg::starts_now(parser),
             ParenthesizedMultiArg::starts_now(parser),
         ])
     }
 
-    fn parse(parser: &mut Parser) -> Self::Output {
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
         if parser.current_token().is(TokenKind::tIDENTIFIER) {
             let name_t = parser.take_token();
-            Builder::arg(name_t, parser.buffer())
+            declare_arg(parser, name_t);
+            Ok(Builder::arg(name_t, parser.buffer()))
         } else if Restarg::starts_now(parser) {
             Restarg::parse(parser)
         } else if ParenthesizedMultiArg::starts_now(parser) {
             ParenthesizedMultiArg::parse(parser)
         } else {
-            unreachable!()
+            Err(ParseError::unexpected_token(None, parser.current_token()))
         }
     }
 }
@@ -1 +1,837 @@
+use crate::{
+    builder::{Builder, PKwLabel},
+    error::{ParseError, ParseResult},
+    parser::{base::Rule, Compstmt, KeywordVariable, Lambda, Literal, Then, Value},
+    token::{Token, TokenKind},
+    Node, Parser,
+};
 
+pub(crate) struct PCaseBody;
+impl Rule for PCaseBody {
+    type Output = Box<Node>;
+
+    fn starts_now(parser: &mut Parser) -> bool {
+        parser.current_token().is(TokenKind::kIN)
+    }
+
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
+        let in_t = parser.take_token();
+        let pattern = PTopExprBody::parse(parser)?;
+        let guard = PGuard::parse(parser)?;
+        let then_t = match Then::parse(parser)? {
+            Some(then_t) => then_t,
+            None => return Err(ParseError::unexpected_token(None, parser.current_token())),
+        };
+        let body = Compstmt::parse(parser)?;
+        Ok(Builder::in_pattern(in_t, pattern, guard, then_t, body))
+    }
+}
+#[test]
+fn test_p_case_body() {
+    crate::testing::assert_parses_rule!(
+        PCaseBody,
+        b"in 1 then 2",
+        r#"
+s(:in_pattern,
+  s(:int, "1"), nil,
+  s(:int, "2"))
+        "#
+    )
+}
+#[test]
+fn test_p_case_body_without_body() {
+    crate::testing::assert_parses_rule!(
+        PCaseBody,
+        b"in a;",
+        r#"
+s(:in_pattern,
+  s(:match_var, "a"), nil, nil)
+        "#
+    )
+}
+
+struct PGuard;
+impl Rule for PGuard {
+    type Output = Option<Box<Node>>;
+
+    fn starts_now(_parser: &mut Parser) -> bool {
+        true
+    }
+
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
+        let token = parser.current_token();
+        let guard = match token.kind {
+            TokenKind::kIF => {
+                parser.skip_token();
+                let cond = Value::parse_expr_value(parser)?;
+                Builder::if_guard(token, cond)
+            }
+            TokenKind::kUNLESS => {
+                parser.skip_token();
+                let cond = Value::parse_expr_value(parser)?;
+                Builder::unless_guard(token, cond)
+            }
+            _ => return Ok(None),
+        };
+        Ok(Some(guard))
+    }
+}
+#[test]
+fn test_p_case_body_with_if_guard() {
+    crate::testing::assert_parses_rule!(
+        PCaseBody,
+        b"in a if true then 1",
+        r#"
+s(:in_pattern,
+  s(:match_var, "a"),
+  s(:if_guard,
+    s(:true)),
+  s(:int, "1"))
+        "#
+    )
+}
+#[test]
+fn test_p_case_body_with_unless_guard() {
+    crate::testing::assert_parses_rule!(
+        PCaseBody,
+        b"in a unless false; 1",
+        r#"
+s(:in_pattern,
+  s(:match_var, "a"),
+  s(:unless_guard,
+    s(:false)),
+  s(:int, "1"))
+        "#
+    )
+}
+
+/// Top-level pattern of `in` clause and one-line pattern matching,
+/// unlike nested patterns it can be a list of elements without brackets
+pub(crate) struct PTopExprBody;
+impl Rule for PTopExprBody {
+    type Output = Box<Node>;
+
+    fn starts_now(parser: &mut Parser) -> bool {
+        PItem::starts_now(parser)
+    }
+
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
+        let elements = PElements::parse(parser)?;
+        let node = match elements {
+            // a single pattern, not a list
+            PElements::Array {
+                mut elements,
+                trailing_comma: None,
+            } if elements.len() == 1 && !matches!(elements[0], Node::MatchRest(_)) => {
+                Box::new(elements.pop().unwrap())
+            }
+            elements => elements.build(None, None),
+        };
+        Ok(node)
+    }
+}
+#[test]
+fn test_p_top_expr_body_array() {
+    crate::testing::assert_parses_rule!(
+        PTopExprBody,
+        b"a, *b, c",
+        r#"
+s(:array_pattern,
+  s(:match_var, "a"),
+  s(:match_rest,
+    s(:match_var, "b")),
+  s(:match_var, "c"))
+        "#
+    )
+}
+#[test]
+fn test_p_top_expr_body_array_with_tail() {
+    crate::testing::assert_parses_rule!(
+        PTopExprBody,
+        b"a,",
+        r#"
+s(:array_pattern_with_tail,
+  s(:match_var, "a"))
+        "#
+    )
+}
+#[test]
+fn test_p_top_expr_body_rest() {
+    crate::testing::assert_parses_rule!(
+        PTopExprBody,
+        b"*",
+        r#"
+s(:array_pattern,
+  s(:match_rest))
+        "#
+    )
+}
+#[test]
+fn test_p_top_expr_body_find() {
+    crate::testing::assert_parses_rule!(
+        PTopExprBody,
+        b"*, 1, *post",
+        r#"
+s(:find_pattern,
+  s(:match_rest),
+  s(:int, "1"),
+  s(:match_rest,
+    s(:match_var, "post")))
+        "#
+    )
+}
+#[test]
+fn test_p_top_expr_body_hash() {
+    crate::testing::assert_parses_rule!(
+        PTopExprBody,
+        b"a: 1, b:, **rest",
+        r#"
+s(:hash_pattern,
+  s(:pair,
+    s(:sym, "a"),
+    s(:int, "1")),
+  s(:match_var, "b"),
+  s(:match_rest,
+    s(:match_var, "rest")))
+        "#
+    )
+}
+#[test]
+fn test_p_top_expr_body_hash_quoted_label() {
+    crate::testing::assert_parses_rule!(
+        PTopExprBody,
+        b"\"a\": 1, \"b\":",
+        r#"
+s(:hash_pattern,
+  s(:pair,
+    s(:sym, "a"),
+    s(:int, "1")),
+  s(:match_var, "b"))
+        "#
+    )
+}
+#[test]
+fn test_p_top_expr_body_hash_nil_rest() {
+    crate::testing::assert_parses_rule!(
+        PTopExprBody,
+        b"a:, **nil",
+        r#"
+s(:hash_pattern,
+  s(:match_var, "a"),
+  s(:match_nil_pattern))
+        "#
+    )
+}
+
+// p_as
+struct PExpr;
+impl Rule for PExpr {
+    type Output = Box<Node>;
+
+    fn starts_now(parser: &mut Parser) -> bool {
+        PExprBasic::starts_now(parser)
+    }
+
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
+        let mut lhs = PExprBasic::parse(parser)?;
+
+        // p_alt
+        while parser.current_token().is(TokenKind::tPIPE) {
+            let pipe_t = parser.take_token();
+            let rhs = PExprBasic::parse(parser)?;
+            lhs = Builder::match_alt(lhs, pipe_t, rhs);
+        }
+
+        while parser.current_token().is(TokenKind::tASSOC) {
+            let assoc_t = parser.take_token();
+            let name_t = parser.expect_token(TokenKind::tIDENTIFIER)?;
+            let as_ = Builder::match_var(name_t, parser.buffer(), parser.static_env());
+            lhs = Builder::match_as(lhs, assoc_t, as_);
+        }
+
+        Ok(lhs)
+    }
+}
+#[test]
+fn test_p_alt() {
+    crate::testing::assert_parses_rule!(
+        PExpr,
+        b"1 | 2 | 3",
+        r#"
+s(:match_alt,
+  s(:match_alt,
+    s(:int, "1"),
+    s(:int, "2")),
+  s(:int, "3"))
+        "#
+    )
+}
+#[test]
+fn test_p_as() {
+    crate::testing::assert_parses_rule!(
+        PExpr,
+        b"Integer | Float => num",
+        r#"
+s(:match_as,
+  s(:match_alt,
+    s(:const, nil, "Integer"),
+    s(:const, nil, "Float")),
+  s(:match_var, "num"))
+        "#
+    )
+}
+
+struct PExprBasic;
+impl Rule for PExprBasic {
+    type Output = Box<Node>;
+
+    fn starts_now(parser: &mut Parser) -> bool {
+        parser.current_token().is_one_of([
+            TokenKind::tIDENTIFIER,
+            TokenKind::tCONSTANT,
+            TokenKind::tCOLON2,
+            TokenKind::tCARET,
+            TokenKind::tLBRACK,
+            TokenKind::tLCURLY,
+            TokenKind::tLPAREN,
+            TokenKind::tDOT2,
+            TokenKind::tDOT3,
+        ]) || PPrimitive::starts_now(parser)
+    }
+
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
+        let token = parser.current_token();
+        match token.kind {
+            TokenKind::tIDENTIFIER => {
+                parser.skip_token();
+                Ok(Builder::match_var(
+                    token,
+                    parser.buffer(),
+                    parser.static_env(),
+                ))
+            }
+            TokenKind::tCONSTANT | TokenKind::tCOLON2 => {
+                let const_ = PConst::parse(parser)?;
+                parse_const_pattern(parser, const_)
+            }
+            TokenKind::tCARET => PPin::parse(parser),
+            TokenKind::tLBRACK => {
+                let lbrack_t = parser.take_token();
+                let elements = PElements::parse_until(parser, TokenKind::tRBRACK)?;
+                let rbrack_t = parser.expect_token(TokenKind::tRBRACK)?;
+                Ok(elements.build(Some(lbrack_t), Some(rbrack_t)))
+            }
+            TokenKind::tLCURLY => {
+                let lbrace_t = parser.take_token();
+                let first_t = parser.current_token();
+                let elements = match PElements::parse_until(parser, TokenKind::tRCURLY)? {
+                    PElements::Hash(elements) => elements,
+                    PElements::Array { elements, .. } if elements.is_empty() => vec![],
+                    // `{ 1 }` is not a hash pattern
+                    _ => return Err(ParseError::unexpected_token(None, first_t)),
+                };
+                let rbrace_t = parser.expect_token(TokenKind::tRCURLY)?;
+                Ok(Builder::hash_pattern(
+                    Some(lbrace_t),
+                    elements,
+                    Some(rbrace_t),
+                ))
+            }
+            TokenKind::tLPAREN => {
+                let lparen_t = parser.take_token();
+                let pattern = PExpr::parse(parser)?;
+                let rparen_t = parser.expect_token(TokenKind::tRPAREN)?;
+                Ok(Builder::begin(lparen_t, vec![*pattern], rparen_t))
+            }
+            TokenKind::tDOT2 | TokenKind::tDOT3 => {
+                // beginless range
+                let op_t = parser.take_token();
+                let right = PPrimitive::parse(parser)?;
+                Ok(build_range(None, op_t, Some(right)))
+            }
+            _ => {
+                let left = PPrimitive::parse(parser)?;
+                if parser
+                    .current_token()
+                    .is_one_of([TokenKind::tDOT2, TokenKind::tDOT3])
+                {
+                    let op_t = parser.take_token();
+                    let right = if PPrimitive::starts_now(parser) {
+                        Some(PPrimitive::parse(parser)?)
+                    } else {
+                        // endless range
+                        None
+                    };
+                    Ok(build_range(Some(left), op_t, right))
+                } else {
+                    Ok(left)
+                }
+            }
+        }
+    }
+}
+#[test]
+fn test_p_array() {
+    crate::testing::assert_parses_rule!(
+        PExprBasic,
+        b"[1, [a, *]]",
+        r#"
+s(:array_pattern,
+  s(:int, "1"),
+  s(:array_pattern,
+    s(:match_var, "a"),
+    s(:match_rest)))
+        "#
+    )
+}
+#[test]
+fn test_p_array_empty() {
+    crate::testing::assert_parses_rule!(PExprBasic, b"[]", r#"s(:array_pattern)"#)
+}
+#[test]
+fn test_p_array_with_tail() {
+    crate::testing::assert_parses_rule!(
+        PExprBasic,
+        b"[a,]",
+        r#"
+s(:array_pattern_with_tail,
+  s(:match_var, "a"))
+        "#
+    )
+}
+#[test]
+fn test_p_find() {
+    crate::testing::assert_parses_rule!(
+        PExprBasic,
+        b"[*, 42, *]",
+        r#"
+s(:find_pattern,
+  s(:match_rest),
+  s(:int, "42"),
+  s(:match_rest))
+        "#
+    )
+}
+#[test]
+fn test_p_hash() {
+    crate::testing::assert_parses_rule!(
+        PExprBasic,
+        b"{a: 1, **}",
+        r#"
+s(:hash_pattern,
+  s(:pair,
+    s(:sym, "a"),
+    s(:int, "1")),
+  s(:match_rest))
+        "#
+    )
+}
+#[test]
+fn test_p_hash_empty() {
+    crate::testing::assert_parses_rule!(PExprBasic, b"{}", r#"s(:hash_pattern)"#)
+}
+#[test]
+fn test_p_parens() {
+    crate::testing::assert_parses_rule!(
+        PExprBasic,
+        b"(1 | 2)",
+        r#"
+s(:begin,
+  s(:match_alt,
+    s(:int, "1"),
+    s(:int, "2")))
+        "#
+    )
+}
+#[test]
+fn test_p_ranges() {
+    crate::testing::assert_parses_rule!(
+        PExprBasic,
+        b"1..2",
+        r#"
+s(:irange,
+  s(:int, "1"),
+  s(:int, "2"))
+        "#
+    );
+    crate::testing::assert_parses_rule!(
+        PExprBasic,
+        b"...2",
+        r#"
+s(:erange, nil,
+  s(:int, "2"))
+        "#
+    );
+}
+#[test]
+fn test_p_const_pattern() {
+    crate::testing::assert_parses_rule!(
+        PExprBasic,
+        b"Foo::Bar(a, *)",
+        r#"
+s(:const_pattern,
+  s(:const,
+    s(:const, nil, "Foo"), "Bar"),
+  s(:array_pattern,
+    s(:match_var, "a"),
+    s(:match_rest)))
+        "#
+    )
+}
+#[test]
+fn test_p_const_pattern_hash() {
+    crate::testing::assert_parses_rule!(
+        PExprBasic,
+        b"Point[x:, y: 0]",
+        r#"
+s(:const_pattern,
+  s(:const, nil, "Point"),
+  s(:hash_pattern,
+    s(:match_var, "x"),
+    s(:pair,
+      s(:sym, "y"),
+      s(:int, "0"))))
+        "#
+    )
+}
+#[test]
+fn test_p_const_pattern_empty() {
+    crate::testing::assert_parses_rule!(
+        PExprBasic,
+        b"Foo()",
+        r#"
+s(:const_pattern,
+  s(:const, nil, "Foo"),
+  s(:array_pattern))
+        "#
+    )
+}
+
+fn build_range(left: Option<Box<Node>>, op_t: Token, right: Option<Box<Node>>) -> Box<Node> {
+    if op_t.is(TokenKind::tDOT2) {
+        Builder::range_inclusive(left, op_t, right)
+    } else {
+        Builder::range_exclusive(left, op_t, right)
+    }
+}
+
+// `Const(...)` and `Const[...]`, or just a constant
+fn parse_const_pattern(parser: &mut Parser, const_: Box<Node>) -> ParseResult<Box<Node>> {
+    let rdelim = match parser.current_token().kind {
+        TokenKind::tLPAREN => TokenKind::tRPAREN,
+        TokenKind::tLBRACK => TokenKind::tRBRACK,
+        _ => return Ok(const_),
+    };
+    let ldelim_t = parser.take_token();
+
+    if parser.current_token().is(rdelim) {
+        let rdelim_t = parser.take_token();
+        let pattern = Builder::array_pattern(Some(ldelim_t), vec![], None, Some(rdelim_t));
+        return Ok(Builder::const_pattern(const_, ldelim_t, pattern, rdelim_t));
+    }
+
+    let elements = PElements::parse_until(parser, rdelim)?;
+    let rdelim_t = parser.expect_token(rdelim)?;
+    let pattern = elements.build(None, None);
+    Ok(Builder::const_pattern(const_, ldelim_t, pattern, rdelim_t))
+}
+
+struct PConst;
+impl Rule for PConst {
+    type Output = Box<Node>;
+
+    fn starts_now(parser: &mut Parser) -> bool {
+        parser
+            .current_token()
+            .is_one_of([TokenKind::tCONSTANT, TokenKind::tCOLON2])
+    }
+
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
+        let mut const_ = if parser.current_token().is(TokenKind::tCOLON2) {
+            let colon2_t = parser.take_token();
+            let name_t = parser.expect_token(TokenKind::tCONSTANT)?;
+            Builder::const_global(colon2_t, name_t, parser.buffer())
+        } else {
+            let name_t = parser.expect_token(TokenKind::tCONSTANT)?;
+            Builder::const_(name_t, parser.buffer())
+        };
+
+        while parser.current_token().is(TokenKind::tCOLON2) {
+            let colon2_t = parser.take_token();
+            let name_t = parser.expect_token(TokenKind::tCONSTANT)?;
+            const_ = Builder::const_fetch(const_, colon2_t, name_t, parser.buffer());
+        }
+
+        Ok(const_)
+    }
+}
+
+// `^var` or `^(expr)`
+struct PPin;
+impl Rule for PPin {
+    type Output = Box<Node>;
+
+    fn starts_now(parser: &mut Parser) -> bool {
+        parser.current_token().is(TokenKind::tCARET)
+    }
+
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
+        let pin_t = parser.take_token();
+        let token = parser.current_token();
+        let var = match token.kind {
+            TokenKind::tIDENTIFIER => {
+                parser.skip_token();
+                Builder::lvar(token, parser.buffer())
+            }
+            TokenKind::tIVAR => {
+                parser.skip_token();
+                Builder::ivar(token, parser.buffer())
+            }
+            TokenKind::tGVAR => {
+                parser.skip_token();
+                Builder::gvar(token, parser.buffer())
+            }
+            TokenKind::tCVAR => {
+                parser.skip_token();
+                Builder::cvar(token, parser.buffer())
+            }
+            TokenKind::tLPAREN => {
+                let lparen_t = parser.take_token();
+                let expr = Value::parse(parser)?;
+                let rparen_t = parser.expect_token(TokenKind::tRPAREN)?;
+                Builder::begin(lparen_t, vec![*expr], rparen_t)
+            }
+            _ => return Err(ParseError::unexpected_token(None, token)),
+        };
+        Ok(Builder::pin(pin_t, var))
+    }
+}
+#[test]
+fn test_p_pin() {
+    crate::testing::assert_parses_rule!(
+        PExprBasic,
+        b"^@a",
+        r#"
+s(:pin,
+  s(:ivar, "@a"))
+        "#
+    )
+}
+#[test]
+fn test_p_pin_expr() {
+    crate::testing::assert_parses_rule!(
+        PExprBasic,
+        b"^(1 + 2)",
+        r#"
+s(:pin,
+  s(:begin,
+    s(:send,
+      s(:int, "1"), "+",
+      s(:int, "2"))))
+        "#
+    )
+}
+
+struct PPrimitive;
+impl Rule for PPrimitive {
+    type Output = Box<Node>;
+
+    fn starts_now(parser: &mut Parser) -> bool {
+        Literal::starts_now(parser)
+            || KeywordVariable::starts_now(parser)
+            || Lambda::starts_now(parser)
+    }
+
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
+        if Literal::starts_now(parser) {
+            Literal::parse(parser)
+        } else if KeywordVariable::starts_now(parser) {
+            KeywordVariable::parse(parser)
+        } else if Lambda::starts_now(parser) {
+            Lambda::parse(parser)
+        } else {
+            Err(ParseError::unexpected_token(None, parser.current_token()))
+        }
+    }
+}
+
+// A comma-separated list of array, find or hash pattern elements.
+// MRI has different rules for them,
+// but they can't be told apart until the first element is parsed.
+enum PElements {
+    Array {
+        elements: Vec<Node>,
+        trailing_comma: Option<Token>,
+    },
+    Find(Vec<Node>),
+    Hash(Vec<Node>),
+}
+
+impl PElements {
+    fn parse(parser: &mut Parser) -> ParseResult<Self> {
+        let first = PItem::parse(parser)?;
+        let is_hash = matches!(first, PItem::Kw(_));
+        let mut elements = vec![first.into_node()];
+        let mut rest_tokens = vec![];
+        let mut trailing_comma = None;
+
+        while parser.current_token().is(TokenKind::tCOMMA) {
+            let comma_t = parser.take_token();
+            if !PItem::starts_now(parser) {
+                trailing_comma = Some(comma_t);
+                break;
+            }
+            let item_t = parser.current_token();
+            match (is_hash, PItem::parse(parser)?) {
+                (true, PItem::Kw(node)) | (false, PItem::Value(node)) => elements.push(*node),
+                (false, PItem::Rest(node)) => {
+                    rest_tokens.push(item_t);
+                    elements.push(*node)
+                }
+                // array and hash pattern elements can't be mixed
+                _ => return Err(ParseError::unexpected_token(None, item_t)),
+            }
+        }
+
+        if is_hash {
+            return Ok(Self::Hash(elements));
+        }
+
+        let first_is_rest = matches!(elements[0], Node::MatchRest(_));
+        let last_is_rest =
+            elements.len() > 1 && matches!(elements.last(), Some(Node::MatchRest(_)));
+        match (first_is_rest, rest_tokens.len()) {
+            (_, 0) | (false, 1) => Ok(Self::Array {
+                elements,
+                trailing_comma,
+            }),
+            // `*pre, pattern, *post`
+            (true, 1) if last_is_rest && trailing_comma.is_none() => Ok(Self::Find(elements)),
+            // only one rest is allowed in array pattern, and find pattern has exactly two
+            _ => Err(ParseError::unexpected_token(
+                None,
+                *rest_tokens.last().unwrap(),
+            )),
+        }
+    }
+
+    fn parse_until(parser: &mut Parser, closing: TokenKind) -> ParseResult<Self> {
+        if parser.current_token().is(closing) {
+            Ok(Self::Array {
+                elements: vec![],
+                trailing_comma: None,
+            })
+        } else {
+            Self::parse(parser)
+        }
+    }
+
+    fn build(self, begin_t: Option<Token>, end_t: Option<Token>) -> Box<Node> {
+        match self {
+            Self::Array {
+                elements,
+                trailing_comma,
+            } => Builder::array_pattern(begin_t, elements, trailing_comma, end_t),
+            Self::Find(elements) => Builder::find_pattern(begin_t, elements, end_t),
+            Self::Hash(elements) => Builder::hash_pattern(begin_t, elements, end_t),
+        }
+    }
+}
+
+enum PItem {
+    // p_expr
+    Value(Box<Node>),
+    // `*` or `*name`
+    Rest(Box<Node>),
+    // `label: p_expr`, `label:`, `**name`, `**` or `**nil`
+    Kw(Box<Node>),
+}
+
+impl PItem {
+    fn into_node(self) -> Node {
+        match self {
+            Self::Value(node) | Self::Rest(node) | Self::Kw(node) => *node,
+        }
+    }
+}
+
+impl Rule for PItem {
+    type Output = Self;
+
+    fn starts_now(parser: &mut Parser) -> bool {
+        parser
+            .current_token()
+            .is_one_of([TokenKind::tSTAR, TokenKind::tDSTAR, TokenKind::tLABEL])
+            || PExpr::starts_now(parser)
+    }
+
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
+        let token = parser.current_token();
+        match token.kind {
+            TokenKind::tSTAR => {
+                parser.skip_token();
+                let name = parse_rest_name(parser);
+                Ok(Self::Rest(Builder::match_rest(token, name)))
+            }
+            TokenKind::tDSTAR => {
+                parser.skip_token();
+                if parser.current_token().is(TokenKind::kNIL) {
+                    let nil_t = parser.take_token();
+                    return Ok(Self::Kw(Builder::match_nil_pattern(token, nil_t)));
+                }
+                let name = parse_rest_name(parser);
+                Ok(Self::Kw(Builder::match_rest(token, name)))
+            }
+            TokenKind::tLABEL => {
+                parser.skip_token();
+                parse_kw_value(parser, PKwLabel::PlainLabel(token)).map(Self::Kw)
+            }
+            _ => {
+                let value = PExpr::parse(parser)?;
+                if matches!(&*value, Node::Str(_) | Node::Dstr(_))
+                    && parser.current_token().is(TokenKind::tCOLON)
+                {
+                    // "label": p_expr
+                    let colon_t = parser.take_token();
+                    parse_kw_value(parser, PKwLabel::QuotedLabel(value, colon_t)).map(Self::Kw)
+                } else {
+                    Ok(Self::Value(value))
+                }
+            }
+        }
+    }
+}
+
+fn parse_rest_name(parser: &mut Parser) -> Option<Box<Node>> {
+    let token = parser.current_token();
+    if token.is(TokenKind::tIDENTIFIER) {
+        parser.skip_token();
+        Some(Builder::match_var(
+            token,
+            parser.buffer(),
+            parser.static_env(),
+        ))
+    } else {
+        None
+    }
+}
+
+fn parse_kw_value(parser: &mut Parser, label: PKwLabel) -> ParseResult<Box<Node>> {
+    // `in a:` followed by a body on the next line has no value
+    if PExpr::starts_now(parser) && parser.newline_before_current_token().is_none() {
+        let value = PExpr::parse(parser)?;
+        Ok(Builder::match_pair(
+            label,
+            value,
+            parser.buffer(),
+            parser.diagnostics(),
+        ))
+    } else {
+        Ok(Builder::match_label(
+            label,
+            parser.buffer(),
+            parser.static_env(),
+            parser.diagnostics(),
+        ))
+    }
+}
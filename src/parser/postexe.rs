@@ -1,4 +1,9 @@
-use crate::{parser::base::Rule, Node, Parser, TokenKind};
+use crate::{
+    builder::Builder,
+    error::ParseResult,
+    parser::{base::Rule, Compstmt},
+    Node, Parser, TokenKind,
+};
 
 pub(crate) struct Postexe;
 impl Rule for Postexe {
@@ -8,8 +13,12 @@ impl Rule for Postexe {
         parser.current_token().is(TokenKind::klEND)
     }
 
-    fn parse(parser: &mut Parser) -> Self::Output {
-        todo!()
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
+        let keyword_t = parser.take_token();
+        let lcurly_t = parser.expect_token(TokenKind::tLCURLY)?;
+        let body = Compstmt::parse(parser)?;
+        let rcurly_t = parser.expect_token(TokenKind::tRCURLY)?;
+        Ok(Builder::postexe(keyword_t, lcurly_t, body, rcurly_t))
     }
 }
 
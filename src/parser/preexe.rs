@@ -1,4 +1,9 @@
-use crate::{parser::base::Rule, Node, Parser, TokenKind};
+use crate::{
+    builder::Builder,
+    error::ParseResult,
+    parser::{base::Rule, TopStmts},
+    Node, Parser, TokenKind,
+};
 
 pub(crate) struct Preexe;
 impl Rule for Preexe {
@@ -8,8 +13,12 @@ impl Rule for Preexe {
         parser.current_token().is(TokenKind::klBEGIN)
     }
 
-    fn parse(parser: &mut Parser) -> Self::Output {
-        todo!()
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
+        let keyword_t = parser.take_token();
+        let lcurly_t = parser.expect_token(TokenKind::tLCURLY)?;
+        let body = TopStmts::parse(parser)?;
+        let rcurly_t = parser.expect_token(TokenKind::tRCURLY)?;
+        Ok(Builder::preexe(keyword_t, lcurly_t, body, rcurly_t))
     }
 }
 
@@ -1,14 +1,169 @@
-use crate::{parser::base::Rule, Node, Parser};
+use crate::{
+    error::ParseResult,
+    parser::{base::Rule, TopStmts},
+    token::TokenKind,
+    Node, Parser,
+};
 
 pub(crate) struct Program;
 impl Rule for Program {
     type Output = Option<Box<Node>>;
 
-    fn starts_now(parser: &mut Parser) -> bool {
-        todo!()
+    fn starts_now(_parser: &mut Parser) -> bool {
+        true
     }
 
-    fn parse(parser: &mut Parser) -> Self::Output {
-        todo!()
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
+        let top_stmts = TopStmts::parse(parser)?;
+        // everything must be consumed, `1 )` is not a valid program
+        parser.expect_token(TokenKind::tEOF)?;
+        Ok(top_stmts)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{loc::loc, ParseError, ParseErrorKind, Parser, TokenKind};
+
+    fn parse(input: &[u8]) -> Result<Option<String>, ParseError> {
+        let result = Parser::new(input).parse()?;
+        Ok(result.ast.map(|ast| ast.inspect(0)))
+    }
+
+    #[test]
+    fn test_empty_program() {
+        assert_eq!(parse(b""), Ok(None));
+        assert_eq!(parse(b"\n  # comment\n;;\n"), Ok(None));
+    }
+
+    #[test]
+    fn test_single_statement() {
+        assert_eq!(
+            parse(b"foo(1)\n"),
+            Ok(Some(
+                r#"
+s(:send, nil, "foo",
+  s(:int, "1"))
+                "#
+                .trim()
+                .to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn test_multiple_statements() {
+        assert_eq!(
+            parse(b"BEGIN { 1 }\na = 2\nif a then a end"),
+            Ok(Some(
+                r#"
+s(:begin,
+  s(:preexe,
+    s(:int, "1")),
+  s(:lvasgn, "a",
+    s(:int, "2")),
+  s(:if,
+    s(:lvar, "a"),
+    s(:lvar, "a"), nil))
+                "#
+                .trim()
+                .to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn test_multiline_def_without_parens() {
+        assert_eq!(
+            parse(b"def foo\n  items.each\nend\n"),
+            Ok(Some(
+                r#"
+s(:def, "foo", nil,
+  s(:send,
+    s(:send, nil, "items"), "each"))
+                "#
+                .trim()
+                .to_string()
+            ))
+        );
+        assert_eq!(
+            parse(b"def foo a, b\n  a\nend\n"),
+            Ok(Some(
+                r#"
+s(:def, "foo",
+  s(:args,
+    s(:arg, "a"),
+    s(:arg, "b")),
+  s(:lvar, "a"))
+                "#
+                .trim()
+                .to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn test_super_in_def() {
+        assert_eq!(
+            parse(b"def foo\n  super\nend\n\ndef []=(k, v)\n  super(k, v)\nend\n"),
+            Ok(Some(
+                r#"
+s(:begin,
+  s(:def, "foo", nil,
+    s(:zsuper)),
+  s(:def, "[]=",
+    s(:args,
+      s(:arg, "k"),
+      s(:arg, "v")),
+    s(:super,
+      s(:lvar, "k"),
+      s(:lvar, "v"))))
+                "#
+                .trim()
+                .to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn test_unexpected_token_after_statements() {
+        assert_eq!(
+            parse(b"1 )"),
+            Err(ParseError {
+                kind: ParseErrorKind::UnexpectedToken,
+                loc: loc!(2, 3),
+                expected: Some(TokenKind::tEOF),
+                found: Some(TokenKind::tRPAREN),
+                diagnostics: vec![],
+            })
+        );
+    }
+
+    #[test]
+    fn test_parser_result() {
+        let result = Parser::new(b"# frozen_string_literal: true\n{ a: 1, a: 2 }\n")
+            .parse()
+            .unwrap();
+        assert!(result.ast.is_some());
+        assert_eq!(result.comments.len(), 1);
+        assert_eq!(result.magic_comments.len(), 1);
+        assert_eq!(result.diagnostics.len(), 1);
+    }
+
+    #[test]
+    fn test_diagnostics_are_kept_on_error() {
+        let error = Parser::new(b"{ a: 1, a: 2 }\n1 )").parse().unwrap_err();
+        assert_eq!(error.kind, ParseErrorKind::UnexpectedToken);
+        assert_eq!(error.diagnostics.len(), 1);
+        assert_eq!(error.diagnostics[0].loc, loc!(8, 9));
+    }
+
+    #[test]
+    fn test_diagnostic_line_col() {
+        let result = Parser::new(b"x = 1\ny = { a: 1, a: 2 }\n").parse().unwrap();
+        let loc = result.diagnostics[0].loc;
+        let line_col = loc.start_line_col(&result.input).unwrap();
+        assert_eq!((line_col.line, line_col.column), (2, 12));
+        assert_eq!(loc.source(&result.input).as_deref(), Some("a"));
     }
 }
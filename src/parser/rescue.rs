@@ -1,8 +1,9 @@
 use crate::{
     builder::Builder,
+    error::ParseResult,
     parser::{
         base::{Maybe1, Repeat1, Rule},
-        Compstmt, Mrhs, Then, Value,
+        into_assignable, Compstmt, Mrhs, Then, Value,
     },
     Node, Parser, Token, TokenKind,
 };
@@ -17,26 +18,43 @@ impl Rule for Rescue {
         parser.current_token().is(TokenKind::kRESCUE)
     }
 
-    fn parse(parser: &mut Parser) -> Self::Output {
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
         let rescue_t = parser.take_token();
-        let exc_list = ExcList::parse(parser);
-        let assoc_t_and_exc_var = Maybe1::<ExcVar>::parse(parser);
-        let then_t = Then::parse(parser);
-        let body = Compstmt::parse(parser);
-        Builder::rescue_body(rescue_t, exc_list, assoc_t_and_exc_var, then_t, body)
+        let exc_list = ExcList::parse(parser)?;
+        let assoc_t_and_exc_var = Maybe1::<ExcVar>::parse(parser)?;
+        let then_t = Then::parse(parser)?;
+        let body = Compstmt::parse(parser)?;
+        Ok(Builder::rescue_body(
+            rescue_t,
+            exc_list,
+            assoc_t_and_exc_var,
+            then_t,
+            body,
+        ))
     }
 }
 #[test]
 fn test_rescue() {
     crate::testing::assert_parses_rule!(
         Rescue,
-        b"rescue Foo => e2; 'body2'",
+        b"rescue Foo => e2; e2",
         r#"
 s(:resbody,
   s(:array,
     s(:const, nil, "Foo")),
-  s(:lvar, "e2"),
-  s(:str, "body2"))
+  s(:lvasgn, "e2"),
+  s(:lvar, "e2"))
+        "#
+    )
+}
+#[test]
+fn test_rescue_without_exc_list() {
+    crate::testing::assert_parses_rule!(
+        Rescue,
+        b"rescue\n  bar",
+        r#"
+s(:resbody, nil, nil,
+  s(:send, nil, "bar"))
         "#
     )
 }
@@ -49,11 +67,13 @@ impl Rule for ExcList {
         true
     }
 
-    fn parse(parser: &mut Parser) -> Self::Output {
-        if Mrhs::starts_now(parser) {
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
+        // `rescue` followed by a newline has no exception classes,
+        // the next line is already a body
+        if parser.newline_before_current_token().is_none() && Mrhs::starts_now(parser) {
             Mrhs::parse(parser)
         } else {
-            vec![]
+            Ok(vec![])
         }
     }
 }
@@ -66,9 +86,12 @@ impl Rule for ExcVar {
         parser.current_token().is(TokenKind::tASSOC)
     }
 
-    fn parse(parser: &mut Parser) -> Self::Output {
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
         let assoc_t = parser.take_token();
-        let value = Value::parse(parser);
-        (assoc_t, value)
+        // `=> e`, `=> @e` or `=> self.e` is a target of the assignment,
+        // the variable is declared before the body is parsed
+        let value = Value::parse_primary(parser)?;
+        let value = into_assignable(parser, value)?;
+        Ok((assoc_t, value))
     }
 }
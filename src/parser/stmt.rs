@@ -1,8 +1,10 @@
 use crate::{
     builder::Builder,
+    error::{ParseError, ParseErrorKind, ParseResult},
     parser::{
-        base::{at_most_one_is_true, ExactToken, Maybe2, Rule, SeparatedBy},
-        OptElse, OptRescue, Preexe, TermT, Value,
+        base::{any_is_true, ExactToken, Maybe2, Rule, SeparatedBy},
+        is_single_assignment, parse_masgn_stmt, parse_mrhs_assign, OptElse, OptRescue, Preexe,
+        TermT, Value,
     },
     Node, Parser, TokenKind,
 };
@@ -15,12 +17,12 @@ impl Rule for TopStmts {
         Stmts::starts_now(parser)
     }
 
-    fn parse(parser: &mut Parser) -> Self::Output {
-        let stmts = Stmts::parse(parser);
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
+        let stmts = parser.with_do_block_allowed(true, |parser| parse_stmts(parser, true))?;
         if stmts.is_empty() {
-            None
+            Ok(None)
         } else {
-            Some(Builder::group(stmts))
+            Ok(Some(Builder::group(stmts)))
         }
     }
 }
@@ -33,13 +35,13 @@ impl Rule for Compstmt {
         Stmts::starts_now(parser)
     }
 
-    fn parse(parser: &mut Parser) -> Self::Output {
-        let stmts = Stmts::parse(parser);
-        let _opt_terms = OptTerms::parse(parser);
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
+        let stmts = parser.with_do_block_allowed(true, Stmts::parse)?;
+        OptTerms::parse(parser)?;
         if stmts.is_empty() {
-            None
+            Ok(None)
         } else {
-            Some(Builder::group(stmts))
+            Ok(Some(Builder::group(stmts)))
         }
     }
 }
@@ -52,13 +54,13 @@ impl Rule for Bodystmt {
         Compstmt::starts_now(parser)
     }
 
-    fn parse(parser: &mut Parser) -> Self::Output {
-        let compound_stmt = Compstmt::parse(parser);
-        let rescue_bodies = OptRescue::parse(parser);
-        let opt_else = OptElse::parse(parser);
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
+        let compound_stmt = Compstmt::parse(parser)?;
+        let rescue_bodies = OptRescue::parse(parser)?;
+        let opt_else = OptElse::parse(parser)?;
         type OptEnsure = Maybe2<ExactToken<{ TokenKind::kENSURE as u8 }>, Compstmt>;
-        let opt_ensure = OptEnsure::parse(parser);
-        Some(Builder::begin_body(
+        let opt_ensure = OptEnsure::parse(parser)?;
+        Ok(Builder::begin_body(
             compound_stmt,
             rescue_bodies,
             opt_else,
@@ -76,8 +78,8 @@ fn test_bodystmt() {
             true // irrelevant
         }
 
-        fn parse(parser: &mut Parser) -> Self::Output {
-            Bodystmt::parse(parser).unwrap()
+        fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
+            Ok(Bodystmt::parse(parser)?.unwrap())
         }
     }
     crate::testing::assert_parses_rule!(
@@ -103,8 +105,9 @@ impl Rule for OptTerms {
         true
     }
 
-    fn parse(parser: &mut Parser) -> Self::Output {
-        Terms::parse(parser);
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
+        Terms::parse(parser)?;
+        Ok(())
     }
 }
 
@@ -116,21 +119,45 @@ impl Rule for Stmts {
         true
     }
 
-    fn parse(parser: &mut Parser) -> Self::Output {
-        let mut stmts = vec![];
-        loop {
-            match ValueOrPreexe::parse(parser) {
-                ValueOrPreexe::Value(stmt) => stmts.push(*stmt),
-                ValueOrPreexe::Term => continue,
-                ValueOrPreexe::None => break,
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
+        parse_stmts(parser, false)
+    }
+}
+
+// `BEGIN { }` is a statement too, but only at the top level of the program
+fn parse_stmts(parser: &mut Parser, top_level: bool) -> ParseResult<Vec<Node>> {
+    let mut stmts = vec![];
+    // statements are separated by `;` or a newline, `1 2` is invalid
+    let mut needs_term = false;
+    loop {
+        if needs_term
+            && parser.newline_before_current_token().is_none()
+            && (Stmt::starts_now(parser) || Preexe::starts_now(parser))
+        {
+            return Err(ParseError::unexpected_token(None, parser.current_token()));
+        }
+
+        let stmt = ValueOrPreexe::parse(parser)?;
+        needs_term = matches!(stmt, ValueOrPreexe::Value(_) | ValueOrPreexe::Preexe(_));
+        match stmt {
+            ValueOrPreexe::Value(stmt) => stmts.push(*stmt),
+            ValueOrPreexe::Preexe(preexe) if top_level => stmts.push(*preexe),
+            ValueOrPreexe::Preexe(preexe) => {
+                return Err(ParseError::new(
+                    ParseErrorKind::BeginNotAtTopLevel,
+                    *preexe.expression(),
+                ))
             }
+            ValueOrPreexe::Term => continue,
+            ValueOrPreexe::None => break,
         }
-        stmts
     }
+    Ok(stmts)
 }
 
 enum ValueOrPreexe {
     Value(Box<Node>),
+    Preexe(Box<Node>),
     Term,
     None,
 }
@@ -138,27 +165,291 @@ impl Rule for ValueOrPreexe {
     type Output = Self;
 
     fn starts_now(parser: &mut Parser) -> bool {
-        at_most_one_is_true([
-            Value::starts_now(parser),
+        any_is_true([
+            Stmt::starts_now(parser),
             Preexe::starts_now(parser),
             Terms::starts_now(parser),
         ])
     }
 
-    fn parse(parser: &mut Parser) -> Self::Output {
-        if Value::starts_now(parser) {
-            Self::Value(Value::parse(parser))
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
+        if Stmt::starts_now(parser) {
+            Ok(Self::Value(Stmt::parse(parser)?))
         } else if Preexe::starts_now(parser) {
-            Self::Value(Preexe::parse(parser))
+            Ok(Self::Preexe(Preexe::parse(parser)?))
         } else if Terms::starts_now(parser) {
             parser.skip_token();
-            Self::Term
+            Ok(Self::Term)
         } else {
-            Self::None
+            Ok(Self::None)
         }
     }
 }
 
+// A value or a multiple assignment that can't be a part of other values
+pub(crate) struct Stmt;
+impl Rule for Stmt {
+    type Output = Box<Node>;
+
+    fn starts_now(parser: &mut Parser) -> bool {
+        any_is_true([
+            parser.current_token().is(TokenKind::tSTAR),
+            Value::starts_now(parser),
+        ])
+    }
+
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
+        let stmt = if parser.current_token().is(TokenKind::tSTAR) {
+            // *a, b = list
+            parse_masgn_stmt(parser, None)?
+        } else {
+            let value = Value::parse(parser)?;
+            if !parser.current_token().is(TokenKind::tCOMMA) {
+                return Ok(value);
+            } else if is_single_assignment(&value) {
+                // a = 1, 2
+                parse_mrhs_assign(parser, value)?
+            } else {
+                // a, b = list
+                parse_masgn_stmt(parser, Some(value))?
+            }
+        };
+
+        Value::parse_stmt_modifiers(
+            parser,
+            stmt,
+            [
+                TokenKind::kIF,
+                TokenKind::kUNLESS,
+                TokenKind::kWHILE,
+                TokenKind::kUNTIL,
+                TokenKind::kRESCUE,
+                TokenKind::kAND,
+                TokenKind::kOR,
+            ],
+        )
+    }
+}
+#[test]
+fn test_masgn() {
+    crate::testing::assert_parses_rule!(
+        Stmt,
+        b"a, (b, *c), d = x",
+        r#"
+s(:masgn,
+  s(:mlhs,
+    s(:lvasgn, "a"),
+    s(:mlhs,
+      s(:lvasgn, "b"),
+      s(:splat,
+        s(:lvasgn, "c"))),
+    s(:lvasgn, "d")),
+  s(:send, nil, "x"))
+        "#
+    )
+}
+#[test]
+fn test_masgn_with_splat_head() {
+    crate::testing::assert_parses_rule!(
+        Stmt,
+        b"*a, b = 1, 2",
+        r#"
+s(:masgn,
+  s(:mlhs,
+    s(:splat,
+      s(:lvasgn, "a")),
+    s(:lvasgn, "b")),
+  s(:array,
+    s(:int, "1"),
+    s(:int, "2")))
+        "#
+    )
+}
+#[test]
+fn test_masgn_parenthesized() {
+    crate::testing::assert_parses_rule!(
+        Stmt,
+        b"(a, b), c = *list",
+        r#"
+s(:masgn,
+  s(:mlhs,
+    s(:mlhs,
+      s(:lvasgn, "a"),
+      s(:lvasgn, "b")),
+    s(:lvasgn, "c")),
+  s(:array,
+    s(:splat,
+      s(:send, nil, "list"))))
+        "#
+    )
+}
+#[test]
+fn test_masgn_with_modifiers() {
+    crate::testing::assert_parses_rule!(
+        Stmt,
+        b"a, b = 1 if c",
+        r#"
+s(:if,
+  s(:send, nil, "c"),
+  s(:masgn,
+    s(:mlhs,
+      s(:lvasgn, "a"),
+      s(:lvasgn, "b")),
+    s(:int, "1")), nil)
+        "#
+    );
+    crate::testing::assert_parses_rule!(
+        Stmt,
+        b"a, b = 1 while c",
+        r#"
+s(:while,
+  s(:send, nil, "c"),
+  s(:masgn,
+    s(:mlhs,
+      s(:lvasgn, "a"),
+      s(:lvasgn, "b")),
+    s(:int, "1")))
+        "#
+    );
+    crate::testing::assert_parses_rule!(
+        Stmt,
+        b"a, b = 1 and c",
+        r#"
+s(:and,
+  s(:masgn,
+    s(:mlhs,
+      s(:lvasgn, "a"),
+      s(:lvasgn, "b")),
+    s(:int, "1")),
+  s(:send, nil, "c"))
+        "#
+    );
+}
+#[test]
+fn test_masgn_rescue_mod() {
+    crate::testing::assert_parses_rule!(
+        Stmt,
+        b"a, b = raise rescue c",
+        r#"
+s(:masgn,
+  s(:mlhs,
+    s(:lvasgn, "a"),
+    s(:lvasgn, "b")),
+  s(:rescue,
+    s(:send, nil, "raise"),
+    s(:resbody, nil, nil,
+      s(:send, nil, "c")), nil))
+        "#
+    );
+}
+#[test]
+fn test_masgn_scoped_const() {
+    crate::testing::assert_parses_rule!(
+        Stmt,
+        b"a, B::C = 1",
+        r#"
+s(:masgn,
+  s(:mlhs,
+    s(:lvasgn, "a"),
+    s(:casgn,
+      s(:const, nil, "B"), "C")),
+  s(:int, "1"))
+        "#
+    );
+}
+#[test]
+fn test_asgn_mrhs() {
+    crate::testing::assert_parses_rule!(
+        Stmt,
+        b"a = 1, 2",
+        r#"
+s(:lvasgn, "a",
+  s(:array,
+    s(:int, "1"),
+    s(:int, "2")))
+        "#
+    );
+    crate::testing::assert_parses_rule!(
+        Stmt,
+        b"a.b = 1, *c if d",
+        r#"
+s(:if,
+  s(:send, nil, "d"),
+  s(:send,
+    s(:send, nil, "a"), "b=",
+    s(:array,
+      s(:int, "1"),
+      s(:splat,
+        s(:send, nil, "c")))), nil)
+        "#
+    );
+}
+#[test]
+fn test_masgn_declares_variables() {
+    use crate::parser::Compstmt;
+
+    let mut parser = Parser::new(b"a, b = 1, 2; a");
+    let ast = Compstmt::parse(&mut parser).unwrap().unwrap();
+    assert_eq!(
+        ast.inspect(0),
+        r#"
+s(:begin,
+  s(:masgn,
+    s(:mlhs,
+      s(:lvasgn, "a"),
+      s(:lvasgn, "b")),
+    s(:array,
+      s(:int, "1"),
+      s(:int, "2"))),
+  s(:lvar, "a"))
+        "#
+        .trim()
+    );
+}
+#[test]
+fn test_preexe_at_top_level() {
+    let mut parser = Parser::new(b"BEGIN { 1 }; 2");
+    let ast = TopStmts::parse(&mut parser).unwrap().unwrap();
+    assert_eq!(
+        ast.inspect(0),
+        r#"
+s(:begin,
+  s(:preexe,
+    s(:int, "1")),
+  s(:int, "2"))
+        "#
+        .trim()
+    );
+}
+#[test]
+fn test_preexe_not_at_top_level() {
+    use crate::{error::ParseErrorKind, loc::loc};
+
+    let mut parser = Parser::new(b"foo { BEGIN {} }");
+    let error = TopStmts::parse(&mut parser).unwrap_err();
+    assert_eq!(error.kind, ParseErrorKind::BeginNotAtTopLevel);
+    assert_eq!(error.loc, loc!(6, 14));
+}
+#[test]
+fn test_stmts_require_terms() {
+    use crate::{error::ParseErrorKind, loc::loc};
+
+    for (src, loc) in [(&b"1 2"[..], loc!(2, 3)), (b"foo(1) 2", loc!(7, 8))] {
+        let mut parser = Parser::new(src);
+        let error = TopStmts::parse(&mut parser).unwrap_err();
+        assert_eq!(error.kind, ParseErrorKind::UnexpectedToken);
+        assert_eq!(error.loc, loc);
+    }
+}
+#[test]
+fn test_stmts_loc() {
+    use crate::loc::loc;
+
+    let mut parser = Parser::new(b"1\n2");
+    let ast = TopStmts::parse(&mut parser).unwrap().unwrap();
+    assert_eq!(ast.expression(), &loc!(0, 3));
+}
+
 struct Terms;
 impl Rule for Terms {
     type Output = ();
@@ -167,8 +458,9 @@ impl Rule for Terms {
         TermT::starts_now(parser)
     }
 
-    fn parse(parser: &mut Parser) -> Self::Output {
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
         type SemiT = ExactToken<{ TokenKind::tSEMI as u8 }>;
-        let _ = SeparatedBy::<TermT, SemiT>::parse(parser);
+        let _ = SeparatedBy::<TermT, SemiT>::parse(parser)?;
+        Ok(())
     }
 }
@@ -1,6 +1,7 @@
 use crate::{
     builder::Builder,
-    parser::base::{at_most_one_is_true, Rule},
+    error::{ParseError, ParseResult},
+    parser::base::{any_is_true, Rule},
     Node, Parser, Token, TokenKind,
 };
 
@@ -14,13 +15,14 @@ impl Rule for BackRef {
             .is_one_of([TokenKind::tNTH_REF, TokenKind::tBACK_REF])
     }
 
-    fn parse(parser: &mut Parser) -> Self::Output {
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
         let token = parser.take_token();
-        match token.kind {
-            TokenKind::tNTH_REF => Builder::nth_ref(token, parser.buffer()),
+        let node = match token.kind {
+            TokenKind::tNTH_REF => Builder::nth_ref(token, parser.buffer(), parser.diagnostics()),
             TokenKind::tBACK_REF => Builder::back_ref(token, parser.buffer()),
             _ => unreachable!(),
-        }
+        };
+        Ok(node)
     }
 }
 
@@ -29,19 +31,19 @@ impl Rule for DotOrColon2T {
     type Output = Token;
 
     fn starts_now(parser: &mut Parser) -> bool {
-        at_most_one_is_true([
+        any_is_true([
             DotT::starts_now(parser),
             parser.current_token().is(TokenKind::tCOLON2),
         ])
     }
 
-    fn parse(parser: &mut Parser) -> Self::Output {
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
         if DotT::starts_now(parser) {
             DotT::parse(parser)
         } else if parser.current_token().is(TokenKind::tCOLON2) {
-            parser.take_token()
+            Ok(parser.take_token())
         } else {
-            unreachable!()
+            Err(ParseError::unexpected_token(None, parser.current_token()))
         }
     }
 }
@@ -51,17 +53,17 @@ impl Rule for DotT {
     type Output = Token;
 
     fn starts_now(parser: &mut Parser) -> bool {
-        at_most_one_is_true([
+        any_is_true([
             parser.current_token().is(TokenKind::tDOT),
             parser.current_token().is(TokenKind::tANDDOT),
         ])
     }
 
-    fn parse(parser: &mut Parser) -> Self::Output {
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
         if Self::starts_now(parser) {
-            parser.take_token()
+            Ok(parser.take_token())
         } else {
-            unreachable!()
+            Err(ParseError::unexpected_token(None, parser.current_token()))
         }
     }
 }
@@ -74,7 +76,7 @@ impl Rule for CnameT {
         IdOrConstT::starts_now(parser)
     }
 
-    fn parse(parser: &mut Parser) -> Self::Output {
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
         IdOrConstT::parse(parser)
     }
 }
@@ -84,19 +86,25 @@ impl Rule for DoT {
     type Output = Token;
 
     fn starts_now(parser: &mut Parser) -> bool {
-        at_most_one_is_true([
+        any_is_true([
             TermT::starts_now(parser),
             parser.current_token().is(TokenKind::kDO),
         ])
     }
 
-    fn parse(parser: &mut Parser) -> Self::Output {
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
         if TermT::starts_now(parser) {
             TermT::parse(parser)
         } else if parser.current_token().is(TokenKind::kDO) {
-            parser.take_token()
+            Ok(parser.take_token())
+        } else if let Some(newline_t) = parser.newline_before_current_token() {
+            // newlines are skipped by the lexer, but they terminate the condition too
+            Ok(newline_t)
         } else {
-            unreachable!()
+            Err(ParseError::unexpected_token(
+                Some(TokenKind::kDO),
+                parser.current_token(),
+            ))
         }
     }
 }
@@ -106,7 +114,10 @@ impl Rule for FnameT {
     type Output = Token;
 
     fn starts_now(parser: &mut Parser) -> bool {
-        at_most_one_is_true([
+        // `def +@` and `def []=` take an operator that is lexed as multiple tokens
+        parser.lexer.relex_as_operator_method_name();
+
+        any_is_true([
             ReswordsT::starts_now(parser),
             IdOrConstT::starts_now(parser),
             OpT::starts_now(parser),
@@ -114,7 +125,9 @@ impl Rule for FnameT {
         ])
     }
 
-    fn parse(parser: &mut Parser) -> Self::Output {
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
+        parser.lexer.relex_as_operator_method_name();
+
         if ReswordsT::starts_now(parser) {
             ReswordsT::parse(parser)
         } else if IdOrConstT::starts_now(parser) {
@@ -122,9 +135,9 @@ impl Rule for FnameT {
         } else if OpT::starts_now(parser) {
             OpT::parse(parser)
         } else if parser.current_token().is(TokenKind::tFID) {
-            parser.take_token()
+            Ok(parser.take_token())
         } else {
-            unreachable!()
+            Err(ParseError::unexpected_token(None, parser.current_token()))
         }
     }
 }
@@ -145,9 +158,9 @@ impl Rule for KeywordVariable {
         ])
     }
 
-    fn parse(parser: &mut Parser) -> Self::Output {
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
         let token = parser.take_token();
-        match token.kind {
+        let node = match token.kind {
             TokenKind::kNIL => Builder::nil(token),
             TokenKind::kSELF => Builder::self_(token),
             TokenKind::kTRUE => Builder::true_(token),
@@ -156,7 +169,8 @@ impl Rule for KeywordVariable {
             TokenKind::k__LINE__ => Builder::__line__(token),
             TokenKind::k__ENCODING__ => Builder::__encoding__(token),
             _ => unreachable!(),
-        }
+        };
+        Ok(node)
     }
 }
 
@@ -165,14 +179,14 @@ impl Rule for OperationT {
     type Output = Token;
 
     fn starts_now(parser: &mut Parser) -> bool {
-        at_most_one_is_true([
+        any_is_true([
             IdOrConstT::starts_now(parser),
             parser.current_token().is(TokenKind::tFID),
         ])
     }
 
-    fn parse(parser: &mut Parser) -> Self::Output {
-        parser.take_token()
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
+        Ok(parser.take_token())
     }
 }
 
@@ -189,15 +203,16 @@ impl Rule for SimpleNumeric {
         ])
     }
 
-    fn parse(parser: &mut Parser) -> Self::Output {
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
         let numeric_t = parser.take_token();
-        match numeric_t.kind {
+        let node = match numeric_t.kind {
             TokenKind::tINTEGER => Builder::integer(numeric_t, parser.buffer()),
             TokenKind::tFLOAT => Builder::float(numeric_t, parser.buffer()),
             TokenKind::tRATIONAL => Builder::rational(numeric_t, parser.buffer()),
             TokenKind::tIMAGINARY => Builder::complex(numeric_t, parser.buffer()),
             _ => unreachable!(),
-        }
+        };
+        Ok(node)
     }
 }
 
@@ -206,17 +221,17 @@ impl Rule for TermT {
     type Output = Token;
 
     fn starts_now(parser: &mut Parser) -> bool {
-        at_most_one_is_true([
+        any_is_true([
             parser.current_token().is(TokenKind::tSEMI),
             parser.current_token().is(TokenKind::tNL),
         ])
     }
 
-    fn parse(parser: &mut Parser) -> Self::Output {
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
         if Self::starts_now(parser) {
-            parser.take_token()
+            Ok(parser.take_token())
         } else {
-            unreachable!()
+            Err(ParseError::unexpected_token(None, parser.current_token()))
         }
     }
 }
@@ -226,19 +241,19 @@ impl Rule for VarRef {
     type Output = Box<Node>;
 
     fn starts_now(parser: &mut Parser) -> bool {
-        at_most_one_is_true([
+        any_is_true([
             UserVariable::starts_now(parser),
             KeywordVariable::starts_now(parser),
         ])
     }
 
-    fn parse(parser: &mut Parser) -> Self::Output {
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
         if UserVariable::starts_now(parser) {
             UserVariable::parse(parser)
         } else if KeywordVariable::starts_now(parser) {
             KeywordVariable::parse(parser)
         } else {
-            unreachable!()
+            Err(ParseError::unexpected_token(None, parser.current_token()))
         }
     }
 }
@@ -250,7 +265,7 @@ impl Rule for OpT {
     fn starts_now(parser: &mut Parser) -> bool {
         let token = parser.current_token();
 
-        at_most_one_is_true([
+        any_is_true([
             token.is(TokenKind::tPIPE),
             token.is(TokenKind::tCARET),
             token.is(TokenKind::tAMPER),
@@ -283,11 +298,11 @@ impl Rule for OpT {
         ])
     }
 
-    fn parse(parser: &mut Parser) -> Self::Output {
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
         if Self::starts_now(parser) {
-            parser.take_token()
+            Ok(parser.take_token())
         } else {
-            unreachable!()
+            Err(ParseError::unexpected_token(None, parser.current_token()))
         }
     }
 }
@@ -299,7 +314,7 @@ impl Rule for ReswordsT {
     fn starts_now(parser: &mut Parser) -> bool {
         let token = parser.current_token();
 
-        at_most_one_is_true([
+        any_is_true([
             token.is(TokenKind::k__LINE__),
             token.is(TokenKind::k__FILE__),
             token.is(TokenKind::k__ENCODING__),
@@ -344,11 +359,11 @@ impl Rule for ReswordsT {
         ])
     }
 
-    fn parse(parser: &mut Parser) -> Self::Output {
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
         if Self::starts_now(parser) {
-            parser.take_token()
+            Ok(parser.take_token())
         } else {
-            unreachable!()
+            Err(ParseError::unexpected_token(None, parser.current_token()))
         }
     }
 }
@@ -361,9 +376,9 @@ impl Rule for Ivar {
         parser.current_token().is(TokenKind::tIVAR)
     }
 
-    fn parse(parser: &mut Parser) -> Self::Output {
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
         let ivar_t = parser.take_token();
-        Builder::ivar(ivar_t, parser.buffer())
+        Ok(Builder::ivar(ivar_t, parser.buffer()))
     }
 }
 
@@ -375,9 +390,9 @@ impl Rule for Cvar {
         parser.current_token().is(TokenKind::tCVAR)
     }
 
-    fn parse(parser: &mut Parser) -> Self::Output {
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
         let cvar_t = parser.take_token();
-        Builder::cvar(cvar_t, parser.buffer())
+        Ok(Builder::cvar(cvar_t, parser.buffer()))
     }
 }
 
@@ -389,9 +404,9 @@ impl Rule for Gvar {
         parser.current_token().is(TokenKind::tGVAR)
     }
 
-    fn parse(parser: &mut Parser) -> Self::Output {
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
         let gvar_t = parser.take_token();
-        Builder::gvar(gvar_t, parser.buffer())
+        Ok(Builder::gvar(gvar_t, parser.buffer()))
     }
 }
 
@@ -400,20 +415,20 @@ impl Rule for UserVariable {
     type Output = Box<Node>;
 
     fn starts_now(parser: &mut Parser) -> bool {
-        at_most_one_is_true([
+        any_is_true([
             IdOrConstT::starts_now(parser),
             NonLocalVar::starts_now(parser),
         ])
     }
 
-    fn parse(parser: &mut Parser) -> Self::Output {
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
         if IdOrConstT::starts_now(parser) {
-            let token = IdOrConstT::parse(parser);
-            Builder::lvar(token, parser.buffer())
+            let token = IdOrConstT::parse(parser)?;
+            Ok(Builder::lvar(token, parser.buffer()))
         } else if NonLocalVar::starts_now(parser) {
             NonLocalVar::parse(parser)
         } else {
-            unreachable!()
+            Err(ParseError::unexpected_token(None, parser.current_token()))
         }
     }
 }
@@ -423,14 +438,14 @@ impl Rule for NonLocalVar {
     type Output = Box<Node>;
 
     fn starts_now(parser: &mut Parser) -> bool {
-        at_most_one_is_true([
+        any_is_true([
             Ivar::starts_now(parser),
             Cvar::starts_now(parser),
             Gvar::starts_now(parser),
         ])
     }
 
-    fn parse(parser: &mut Parser) -> Self::Output {
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
         if Ivar::starts_now(parser) {
             Ivar::parse(parser)
         } else if Cvar::starts_now(parser) {
@@ -438,7 +453,7 @@ impl Rule for NonLocalVar {
         } else if Gvar::starts_now(parser) {
             Gvar::parse(parser)
         } else {
-            unreachable!()
+            Err(ParseError::unexpected_token(None, parser.current_token()))
         }
     }
 }
@@ -449,14 +464,14 @@ impl Rule for IdOrConstT {
 
     fn starts_now(parser: &mut Parser) -> bool {
         let token = parser.current_token();
-        at_most_one_is_true([
+        any_is_true([
             token.is(TokenKind::tIDENTIFIER),
             token.is(TokenKind::tCONSTANT),
         ])
     }
 
-    fn parse(parser: &mut Parser) -> Self::Output {
-        parser.take_token()
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
+        Ok(parser.take_token())
     }
 }
 
@@ -469,9 +484,30 @@ fn test_id_or_const_t() {
     assert!(IdOrConstT::starts_now(&mut parser));
     assert_eq!(
         IdOrConstT::parse(&mut parser),
-        token!(tIDENTIFIER, loc!(0, 3))
+        Ok(token!(tIDENTIFIER, loc!(0, 3)))
     );
 
     let mut parser = Parser::new(b"42");
     assert!(!IdOrConstT::starts_now(&mut parser));
 }
+
+#[test]
+fn test_nth_ref_is_too_big() {
+    use crate::diagnostic::{Diagnostic, DiagnosticLevel, DiagnosticMessage};
+    use crate::loc::loc;
+
+    let mut parser = Parser::new(b"$99999999999");
+    assert!(BackRef::starts_now(&mut parser));
+    let ast = BackRef::parse(&mut parser).unwrap();
+    assert_eq!(ast.inspect(0), "s(:nth_ref, 99999999999)");
+    assert_eq!(
+        parser.diagnostics().take_inner(),
+        vec![Diagnostic {
+            level: DiagnosticLevel::Warning,
+            message: DiagnosticMessage::NthRefIsTooBig {
+                nth_ref: "99999999999".to_string()
+            },
+            loc: loc!(0, 12)
+        }]
+    );
+}
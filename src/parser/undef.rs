@@ -1,7 +1,8 @@
 use crate::{
     builder::Builder,
+    error::{ParseError, ParseResult},
     parser::{
-        base::{at_most_one_is_true, ExactToken, Rule, SeparatedBy},
+        base::{any_is_true, ExactToken, Rule, SeparatedBy},
         FnameT, Symbol,
     },
     token::TokenKind,
@@ -16,15 +17,18 @@ impl Rule for Undef {
         parser.current_token().is(TokenKind::kUNDEF)
     }
 
-    fn parse(parser: &mut Parser) -> Self::Output {
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
         let undef_t = parser.current_token();
         parser.skip_token();
 
         type CommaTokenRule = ExactToken<{ TokenKind::tCOMMA as u8 }>;
 
-        let (names, _commas) = SeparatedBy::<Fitem, CommaTokenRule>::parse(parser);
+        let (names, _commas) = SeparatedBy::<Fitem, CommaTokenRule>::parse(parser)?;
+        if names.is_empty() {
+            return Err(ParseError::unexpected_token(None, parser.current_token()));
+        }
 
-        Builder::undef(undef_t, names)
+        Ok(Builder::undef(undef_t, names))
     }
 }
 
@@ -34,13 +38,13 @@ impl Rule for Fitem {
     type Output = Box<Node>;
 
     fn starts_now(parser: &mut Parser) -> bool {
-        at_most_one_is_true([FnameT::starts_now(parser), Symbol::starts_now(parser)])
+        any_is_true([FnameT::starts_now(parser), Symbol::starts_now(parser)])
     }
 
-    fn parse(parser: &mut Parser) -> Self::Output {
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
         if FnameT::starts_now(parser) {
-            let fname_t = FnameT::parse(parser);
-            Builder::symbol_internal(fname_t, parser.buffer())
+            let fname_t = FnameT::parse(parser)?;
+            Ok(Builder::symbol_internal(fname_t, parser.buffer()))
         } else {
             Symbol::parse(parser)
         }
@@ -61,3 +65,8 @@ s(:undef,
         "#
     );
 }
+#[test]
+fn test_undef_without_names() {
+    let mut parser = Parser::new(b"undef");
+    assert!(Undef::parse(&mut parser).is_err());
+}
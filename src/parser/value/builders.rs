@@ -1,6 +1,11 @@
 use crate::{
-    builder::{Builder, LoopType},
-    parser::Value,
+    buffer::Buffer,
+    builder::{helpers::string_value, Builder, LoopType},
+    error::ParseResult,
+    loc::loc,
+    nodes::{Complex, Float, Int, Rational},
+    parser::{base::Rule, into_assignable, parse_masgn_rhs, PTopExprBody, Value},
+    token::token,
     Node, Parser, Token, TokenKind,
 };
 
@@ -105,6 +110,20 @@ s(:send,
     )
 }
 #[test]
+fn test_prefix_defined_with_parens() {
+    use crate::testing::assert_parses_rule;
+    assert_parses_rule!(
+        Value,
+        b"defined?(x) && y",
+        r#"
+s(:and,
+  s(:defined?,
+    s(:send, nil, "x")),
+  s(:send, nil, "y"))
+        "#
+    )
+}
+#[test]
 fn test_prefix_defined() {
     use crate::testing::assert_parses_rule;
     assert_parses_rule!(
@@ -122,10 +141,56 @@ pub(crate) fn build_binary_op(
     lhs: Box<Node>,
     parser: &mut Parser,
     r_bp: u8,
-) -> Box<Node> {
-    let rhs = Value::parse_bp(parser, r_bp);
-
+) -> ParseResult<Box<Node>> {
+    // one-line pattern matching has a pattern on the right side, not a value
     match op_t.kind {
+        TokenKind::tASSOC => {
+            let pattern = PTopExprBody::parse(parser)?;
+            return Ok(Builder::match_pattern(lhs, op_t, pattern));
+        }
+        TokenKind::kIN => {
+            let pattern = PTopExprBody::parse(parser)?;
+            return Ok(Builder::match_pattern_p(lhs, op_t, pattern));
+        }
+        // the target is declared before the right side is parsed, so `x = x` assigns a local variable
+        TokenKind::tEQL => {
+            let lhs = into_assignable(parser, lhs)?;
+            if matches!(*lhs, Node::Mlhs(_)) {
+                // (a, b) = 1, 2 or (a) = 1
+                let rhs = parse_masgn_rhs(parser)?;
+                return Ok(Builder::multi_assign(lhs, op_t, rhs));
+            }
+            let rhs = if parser.current_token().is(TokenKind::tSTAR) {
+                // a = *b
+                parse_masgn_rhs(parser)?
+            } else {
+                Value::parse_bp(parser, r_bp)?
+            };
+            return Builder::assign(lhs, op_t, rhs);
+        }
+        TokenKind::tOP_ASGN => {
+            let lhs = match *lhs {
+                // a.b += 1, a&.b += 1, a[1] += 1 and $1 += 1 are handled by the builder
+                Node::Send(ref send) if send.recv.is_some() => lhs,
+                Node::CSend(_) | Node::Index(_) | Node::BackRef(_) | Node::NthRef(_) => lhs,
+                _ => into_assignable(parser, lhs)?,
+            };
+            let rhs = Value::parse_bp(parser, r_bp)?;
+            return Builder::op_assign(lhs, op_t, rhs, parser.buffer(), parser.diagnostics());
+        }
+        // cond ? then : else, `then` is an `arg` and `else` takes the precedence of `?`
+        TokenKind::tEH => {
+            let if_true = Value::parse_arg(parser)?;
+            let colon_t = parser.expect_token(TokenKind::tCOLON)?;
+            let if_false = Value::parse_bp(parser, r_bp)?;
+            return Ok(Builder::ternary(lhs, op_t, if_true, colon_t, if_false));
+        }
+        _ => {}
+    }
+
+    let rhs = Value::parse_bp(parser, r_bp)?;
+
+    let node = match op_t.kind {
         TokenKind::tPLUS
         | TokenKind::tMINUS
         | TokenKind::tSTAR
@@ -139,14 +204,19 @@ pub(crate) fn build_binary_op(
         | TokenKind::tEQQ
         | TokenKind::tNEQ
         | TokenKind::tNMATCH
-        | TokenKind::tMATCH
         | TokenKind::tLSHFT
         | TokenKind::tRSHFT
         | TokenKind::tGT
         | TokenKind::tLT
         | TokenKind::tGEQ
-        | TokenKind::tLEQ
-        | TokenKind::tDSTAR => Builder::binary_op(lhs, op_t, rhs, parser.buffer()),
+        | TokenKind::tLEQ => Builder::binary_op(lhs, op_t, rhs, parser.buffer()),
+
+        // `1 -1` is `1 - 1`
+        TokenKind::tUMINUS_NUM => Builder::binary_op(lhs, op_t, rhs, parser.buffer()),
+
+        TokenKind::tDSTAR => build_pow(lhs, op_t, rhs, parser),
+
+        TokenKind::tMATCH => Builder::match_op(lhs, op_t, rhs, parser.static_env()),
 
         TokenKind::kIF => Builder::condition_mod(Some(lhs), None, op_t, rhs),
         TokenKind::kUNLESS => Builder::condition_mod(None, Some(lhs), op_t, rhs),
@@ -157,6 +227,7 @@ pub(crate) fn build_binary_op(
         TokenKind::kRESCUE => {
             let rescue_body = Builder::rescue_body(op_t, vec![], None, None, Some(rhs));
             Builder::begin_body(Some(lhs), vec![*rescue_body], None, None)
+                .expect("bug: `foo rescue bar` always has a body")
         }
 
         TokenKind::tDOT2 => Builder::range_inclusive(Some(lhs), op_t, Some(rhs)),
@@ -166,28 +237,264 @@ pub(crate) fn build_binary_op(
             Builder::logical_op(lhs, op_t, rhs)
         }
 
-        _ => todo!("{:?} {:?} {:?}", lhs, op_t, rhs),
+        other => unreachable!("{:?} is not a binary operator", other),
+    };
+    Ok(node)
+}
+
+// MRI parses `-2 ** 2` as `-(2 ** 2)`, but the lexer attaches the sign to the number
+fn build_pow(lhs: Box<Node>, op_t: Token, rhs: Box<Node>, parser: &mut Parser) -> Box<Node> {
+    match unsign_numeric(lhs, parser.buffer()) {
+        Ok((minus_t, lhs)) => {
+            let pow = Builder::binary_op(lhs, op_t, rhs, parser.buffer());
+            Builder::unary_op(minus_t, pow, parser.buffer())
+        }
+        Err(lhs) => Builder::binary_op(lhs, op_t, rhs, parser.buffer()),
     }
 }
+
+// Splits a negative numeric literal into a `-` token and a positive number
+fn unsign_numeric(mut node: Box<Node>, buffer: &Buffer) -> Result<(Token, Box<Node>), Box<Node>> {
+    let (value, operator_l, expression_l) = match &mut *node {
+        Node::Int(Int {
+            value,
+            operator_l,
+            expression_l,
+        })
+        | Node::Float(Float {
+            value,
+            operator_l,
+            expression_l,
+        })
+        | Node::Rational(Rational {
+            value,
+            operator_l,
+            expression_l,
+        })
+        | Node::Complex(Complex {
+            value,
+            operator_l,
+            expression_l,
+        }) => (value, operator_l, expression_l),
+        _ => return Err(node),
+    };
+    let minus_l = match operator_l.take() {
+        Some(minus_l) => minus_l,
+        None => return Err(node),
+    };
+    *expression_l = loc!(minus_l.end, expression_l.end);
+    *value = string_value(*expression_l, buffer);
+    Ok((token!(tUMINUS_NUM, minus_l), node))
+}
 #[test]
 fn test_binary_op_asgn() {
-    use crate::testing::assert_parses_rule;
-    assert_parses_rule!(Value, b"true += false", r#""#);
+    use crate::{
+        loc::loc, testing::assert_parses_rule, Diagnostic, DiagnosticLevel, DiagnosticMessage,
+    };
+    assert_parses_rule!(
+        Value,
+        b"true += false",
+        r#"
+s(:op_asgn,
+  s(:true), "+",
+  s(:false))
+        "#
+    );
+
+    let mut parser = Parser::new(b"true += false");
+    Value::parse(&mut parser).unwrap();
+    assert_eq!(
+        parser.diagnostics().take_inner(),
+        vec![Diagnostic {
+            level: DiagnosticLevel::Error,
+            message: DiagnosticMessage::CantAssignToTrue,
+            loc: loc!(0, 4)
+        }]
+    );
 }
 #[test]
 fn test_binary_asgn() {
+    use crate::{loc::loc, Diagnostic, DiagnosticLevel, DiagnosticMessage};
+    let mut parser = Parser::new(b"true = false");
+    Value::parse(&mut parser).unwrap();
+    assert_eq!(
+        parser.diagnostics().take_inner(),
+        vec![Diagnostic {
+            level: DiagnosticLevel::Error,
+            message: DiagnosticMessage::CantAssignToTrue,
+            loc: loc!(0, 4)
+        }]
+    );
+}
+#[test]
+fn test_binary_asgn_is_right_assoc() {
     use crate::testing::assert_parses_rule;
-    assert_parses_rule!(Value, b"true = false", r#""#);
+    assert_parses_rule!(
+        Value,
+        b"a = b = 1",
+        r#"
+s(:lvasgn, "a",
+  s(:lvasgn, "b",
+    s(:int, "1")))
+        "#
+    );
+}
+#[test]
+fn test_binary_asgn_binds_to_the_closest_value() {
+    use crate::testing::assert_parses_rule;
+    assert_parses_rule!(
+        Value,
+        b"a && b = c",
+        r#"
+s(:and,
+  s(:send, nil, "a"),
+  s(:lvasgn, "b",
+    s(:send, nil, "c")))
+        "#
+    );
+}
+#[test]
+fn test_binary_asgn_splat() {
+    use crate::testing::assert_parses_rule;
+    assert_parses_rule!(
+        Value,
+        b"a = *b",
+        r#"
+s(:lvasgn, "a",
+  s(:array,
+    s(:splat,
+      s(:send, nil, "b"))))
+        "#
+    );
+}
+#[test]
+fn test_binary_asgn_declares_lhs_before_rhs() {
+    use crate::testing::assert_parses_rule;
+    assert_parses_rule!(
+        Value,
+        b"x = x",
+        r#"
+s(:lvasgn, "x",
+  s(:lvar, "x"))
+        "#
+    );
+    assert_parses_rule!(
+        Value,
+        b"y ||= y",
+        r#"
+s(:or_asgn,
+  s(:lvasgn, "y"),
+  s(:lvar, "y"))
+        "#
+    );
+}
+#[test]
+fn test_binary_op_asgn_lvar() {
+    use crate::testing::assert_parses_rule;
+    assert_parses_rule!(
+        Value,
+        b"a += 1",
+        r#"
+s(:op_asgn,
+  s(:lvasgn, "a"), "+",
+  s(:int, "1"))
+        "#
+    );
+    assert_parses_rule!(
+        Value,
+        b"a ||= 1",
+        r#"
+s(:or_asgn,
+  s(:lvasgn, "a"),
+  s(:int, "1"))
+        "#
+    );
+}
+#[test]
+fn test_binary_op_asgn_attr() {
+    use crate::testing::assert_parses_rule;
+    assert_parses_rule!(
+        Value,
+        b"a.b &&= 1",
+        r#"
+s(:and_asgn,
+  s(:send,
+    s(:send, nil, "a"), "b"),
+  s(:int, "1"))
+        "#
+    );
+}
+#[test]
+fn test_binary_op_asgn_invalid_target() {
+    use crate::{error::ParseErrorKind, loc::loc};
+
+    let mut parser = Parser::new(b"(a) += 1");
+    let error = Value::parse(&mut parser).unwrap_err();
+    assert_eq!(error.kind, ParseErrorKind::InvalidAssignmentTarget);
+    assert_eq!(error.loc, loc!(0, 3));
+
+    let mut parser = Parser::new(b"a.b(1) += 1");
+    let error = Value::parse(&mut parser).unwrap_err();
+    assert_eq!(error.kind, ParseErrorKind::InvalidAssignmentTarget);
+    assert_eq!(error.loc, loc!(0, 6));
+}
+#[test]
+fn test_binary_asgn_invalid_target() {
+    use crate::{error::ParseErrorKind, loc::loc};
+
+    let mut parser = Parser::new(b"a.b(1) = 2");
+    let error = Value::parse(&mut parser).unwrap_err();
+    assert_eq!(error.kind, ParseErrorKind::InvalidAssignmentTarget);
+    assert_eq!(error.loc, loc!(0, 6));
 }
 #[test]
 fn test_binary_masgn() {
     use crate::testing::assert_parses_rule;
-    assert_parses_rule!(Value, b"true, false = nil, __FILE__", r#""#);
+    assert_parses_rule!(
+        crate::parser::stmt::Stmt,
+        b"true, false = nil, __FILE__",
+        r#"
+s(:masgn,
+  s(:mlhs,
+    s(:true),
+    s(:false)),
+  s(:array,
+    s(:nil),
+    s(:__FILE__)))
+        "#
+    );
 }
 #[test]
 fn test_binary_mlhs_eql_rhs() {
     use crate::testing::assert_parses_rule;
-    assert_parses_rule!(Value, b"true, false = nil", r#""#);
+    assert_parses_rule!(
+        crate::parser::stmt::Stmt,
+        b"true, false = nil",
+        r#"
+s(:masgn,
+  s(:mlhs,
+    s(:true),
+    s(:false)),
+  s(:nil))
+        "#
+    );
+}
+#[test]
+fn test_binary_parenthesized_mlhs_eql_rhs() {
+    use crate::testing::assert_parses_rule;
+    assert_parses_rule!(
+        Value,
+        b"(a, b) = 1, 2",
+        r#"
+s(:masgn,
+  s(:mlhs,
+    s(:lvasgn, "a"),
+    s(:lvasgn, "b")),
+  s(:array,
+    s(:int, "1"),
+    s(:int, "2")))
+        "#
+    );
 }
 #[test]
 fn test_binary_irange() {
@@ -270,7 +577,15 @@ s(:send,
 #[test]
 fn test_binary_mod() {
     use crate::testing::assert_parses_rule;
-    assert_parses_rule!(Value, b"true % false", r#""#);
+    assert_parses_rule!(
+        Value,
+        b"true % false",
+        r#"
+s(:send,
+  s(:true), "%",
+  s(:false))
+        "#
+    );
 }
 #[test]
 fn test_binary_pow() {
@@ -389,6 +704,25 @@ s(:send,
     );
 }
 #[test]
+fn test_binary_match_with_named_captures() {
+    use crate::testing::assert_parses_rule;
+    assert_parses_rule!(
+        Value,
+        b"/(?<foo>bar)/ =~ baz",
+        r#"
+s(:match_with_lvasgn,
+  s(:regexp,
+    s(:str, "(?<foo>bar)"),
+    s(:regopt)),
+  s(:send, nil, "baz"))
+        "#
+    );
+
+    let mut parser = Parser::new(b"/(?<foo>bar)/ =~ baz");
+    Value::parse(&mut parser).unwrap();
+    assert!(parser.static_env().is_declared(b"foo"));
+}
+#[test]
 fn test_binary_nmatch() {
     use crate::testing::assert_parses_rule;
     assert_parses_rule!(
@@ -534,17 +868,153 @@ s(:or,
 #[test]
 fn test_binary_ternary_operator() {
     use crate::testing::assert_parses_rule;
-    assert_parses_rule!(Value, b"true ? false : nil", r#""#);
+    assert_parses_rule!(
+        Value,
+        b"true ? false : nil",
+        r#"
+s(:if,
+  s(:true),
+  s(:false),
+  s(:nil))
+        "#
+    );
+}
+#[test]
+fn test_binary_ternary_operator_is_right_assoc() {
+    use crate::testing::assert_parses_rule;
+    assert_parses_rule!(
+        Value,
+        b"a ? b : c ? d : e",
+        r#"
+s(:if,
+  s(:send, nil, "a"),
+  s(:send, nil, "b"),
+  s(:if,
+    s(:send, nil, "c"),
+    s(:send, nil, "d"),
+    s(:send, nil, "e")))
+        "#
+    );
+}
+#[test]
+fn test_binary_minus_num() {
+    use crate::testing::assert_parses_rule;
+    assert_parses_rule!(
+        Value,
+        b"1 -1",
+        r#"
+s(:send,
+  s(:int, "1"), "-",
+  s(:int, "1"))
+        "#
+    );
+}
+#[test]
+fn test_binary_pow_of_negative_num() {
+    use crate::testing::assert_parses_rule;
+    assert_parses_rule!(
+        Value,
+        b"-2 ** 2",
+        r#"
+s(:send,
+  s(:send,
+    s(:int, "2"), "**",
+    s(:int, "2")), "-@")
+        "#
+    );
+}
+#[test]
+fn test_binary_precedence() {
+    use crate::testing::assert_parses_rule;
+    assert_parses_rule!(
+        Value,
+        b"1 + 2 * 3 == 7 || not x",
+        r#"
+s(:or,
+  s(:send,
+    s(:send,
+      s(:int, "1"), "+",
+      s(:send,
+        s(:int, "2"), "*",
+        s(:int, "3"))), "==",
+    s(:int, "7")),
+  s(:send,
+    s(:send, nil, "x"), "!"))
+        "#
+    );
+}
+#[test]
+fn test_binary_after_send_and_const() {
+    use crate::testing::assert_parses_rule;
+    assert_parses_rule!(
+        Value,
+        b"a+1 - A - 1 * foo.bar",
+        r#"
+s(:send,
+  s(:send,
+    s(:send,
+      s(:send, nil, "a"), "+",
+      s(:int, "1")), "-",
+    s(:const, nil, "A")), "-",
+  s(:send,
+    s(:int, "1"), "*",
+    s(:send,
+      s(:send, nil, "foo"), "bar")))
+        "#
+    );
+    assert_parses_rule!(
+        Value,
+        b"a.b..c",
+        r#"
+s(:irange,
+  s(:send,
+    s(:send, nil, "a"), "b"),
+  s(:send, nil, "c"))
+        "#
+    );
+}
+#[test]
+fn test_binary_non_associative() {
+    use crate::{parser::base::Rule, Parser};
+    for src in [
+        &b"a..b..c"[..],
+        b"1...2..3",
+        b"a == b == c",
+        b"a <=> b <=> c",
+    ] {
+        let mut parser = Parser::new(src);
+        assert!(
+            Value::parse(&mut parser).is_err(),
+            "{:?} must be rejected",
+            src
+        );
+    }
 }
 #[test]
 fn test_binary_match_pattern() {
     use crate::testing::assert_parses_rule;
-    assert_parses_rule!(Value, b"true => false", r#""#);
+    assert_parses_rule!(
+        Value,
+        b"true => false",
+        r#"
+s(:match_pattern,
+  s(:true),
+  s(:false))
+        "#
+    );
 }
 #[test]
 fn test_binary_match_pattern_p() {
     use crate::testing::assert_parses_rule;
-    assert_parses_rule!(Value, b"true in false", r#""#);
+    assert_parses_rule!(
+        Value,
+        b"true in false",
+        r#"
+s(:match_pattern_p,
+  s(:true),
+  s(:false))
+        "#
+    );
 }
 #[test]
 fn test_binary_if_mod() {
@@ -613,6 +1083,39 @@ s(:rescue,
     );
 }
 
-pub(crate) fn build_postfix_op(op_t: Token, arg: Box<Node>, parser: &mut Parser) -> Box<Node> {
-    todo!()
+pub(crate) fn build_postfix_op(
+    op_t: Token,
+    arg: Box<Node>,
+    _parser: &mut Parser,
+) -> ParseResult<Box<Node>> {
+    let node = match op_t.kind {
+        TokenKind::tDOT2 => Builder::range_inclusive(Some(arg), op_t, None),
+        TokenKind::tDOT3 => Builder::range_exclusive(Some(arg), op_t, None),
+        other => unreachable!("{:?} is not a postfix operator", other),
+    };
+    Ok(node)
+}
+#[test]
+fn test_postfix_irange() {
+    use crate::testing::assert_parses_rule;
+    assert_parses_rule!(
+        Value,
+        b"1..",
+        r#"
+s(:irange,
+  s(:int, "1"), nil)
+        "#
+    );
+}
+#[test]
+fn test_postfix_erange() {
+    use crate::testing::assert_parses_rule;
+    assert_parses_rule!(
+        Value,
+        b"1...",
+        r#"
+s(:erange,
+  s(:int, "1"), nil)
+        "#
+    );
 }
@@ -1,6 +1,8 @@
 use crate::{
+    builder::ArgsType,
+    error::{ParseError, ParseResult},
     parser::{
-        base::{at_most_one_is_true, Maybe1, Rule, Unbox},
+        base::{any_is_true, Maybe1, Rule, Unbox},
         Args, CallArgs, DotOrColon2T, DotT, MaybeBlock, OpT, ParenArgs,
     },
     Node, Parser, Token, TokenKind,
@@ -20,7 +22,7 @@ pub(crate) enum CallTail {
         args: Vec<Node>,
         rparen_t: Option<Token>,
 
-        block: Option<(Token, Option<Box<Node>>, Box<Node>, Token)>,
+        block: Option<(Token, ArgsType, Option<Box<Node>>, Token)>,
     },
 
     ArefArgs {
@@ -28,7 +30,7 @@ pub(crate) enum CallTail {
         args: Vec<Node>,
         rbrack_t: Token,
 
-        block: Option<(Token, Option<Box<Node>>, Box<Node>, Token)>,
+        block: Option<(Token, ArgsType, Option<Box<Node>>, Token)>,
     },
 }
 
@@ -44,36 +46,36 @@ impl Rule for CallTail {
     type Output = Self;
 
     fn starts_now(parser: &mut Parser) -> bool {
-        let seen_any_space = parser.lexer.seen_nl || parser.lexer.seen_whitespace;
+        let seen_any_space = parser.lexer.space_before_current_token();
 
-        at_most_one_is_true([
+        any_is_true([
             parser.current_token().is(TokenKind::tCOLON2) && !seen_any_space,
             DotT::starts_now(parser),
             ArefArgs::starts_now(parser) && !seen_any_space,
         ])
     }
 
-    fn parse(parser: &mut Parser) -> Self::Output {
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
         if ArefArgs::starts_now(parser) {
-            let (lbrack_t, args, rbrack_t) = ArefArgs::parse(parser);
-            let block = MaybeBlock::parse(parser);
-            Self::ArefArgs {
+            let (lbrack_t, args, rbrack_t) = ArefArgs::parse(parser)?;
+            let block = MaybeBlock::parse(parser)?;
+            Ok(Self::ArefArgs {
                 lbrack_t,
                 args,
                 rbrack_t,
                 block,
-            }
+            })
         } else {
-            let dot_or_colon2_t = DotOrColon2T::parse(parser);
-            let method_name_t = Maybe1::<MethodNameT>::parse(parser);
+            let dot_or_colon2_t = DotOrColon2T::parse(parser)?;
+            let method_name_t = Maybe1::<MethodNameT>::parse(parser)?;
 
             let (lparen_t, args, rparen_t) = if method_name_t.is_none() {
                 // `foo.()` / `foo::()`, can take only parenthesized args
-                let (lparen_t, args, rparen_t) = ParenArgs::parse(parser);
+                let (lparen_t, args, rparen_t) = ParenArgs::parse(parser)?;
                 (Some(lparen_t), args, Some(rparen_t))
             } else {
                 // normal `foo.bar` call, takes any args
-                CallArgs::parse(parser)
+                CallArgs::parse(parser)?
             };
 
             if dot_or_colon2_t.is(TokenKind::tCOLON2)
@@ -89,21 +91,21 @@ impl Rule for CallTail {
                 && rparen_t.is_none()
             {
                 // ::CONST, without args and parentheses, it can't take any blocks because it's a const access
-                Self::ConstAccess {
+                Ok(Self::ConstAccess {
                     colon2_t: dot_or_colon2_t,
                     name_t: method_name_t.unwrap(),
-                }
+                })
             } else {
                 // some method call, can take any block
-                let block = MaybeBlock::parse(parser);
-                Self::MethodCall {
+                let block = MaybeBlock::parse(parser)?;
+                Ok(Self::MethodCall {
                     dot_t: dot_or_colon2_t,
                     name_t: method_name_t,
                     lparen_t,
                     args,
                     rparen_t,
                     block,
-                }
+                })
             }
         }
     }
@@ -117,14 +119,16 @@ impl Rule for ArefArgs {
         parser.current_token().is(TokenKind::tLBRACK)
     }
 
-    fn parse(parser: &mut Parser) -> Self::Output {
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
         let lbrack_t = parser.take_token();
-        let args = Maybe1::<Args>::parse(parser).unwrap_or_default();
+        let args = parser
+            .with_do_block_allowed(true, Maybe1::<Args>::parse)?
+            .unwrap_or_default();
         if parser.current_token().is(TokenKind::tCOMMA) {
             parser.skip_token()
         }
-        let rbrack_t = parser.expect_token(TokenKind::tRBRACK);
-        (lbrack_t, args, rbrack_t)
+        let rbrack_t = parser.expect_token(TokenKind::tRBRACK)?;
+        Ok((lbrack_t, args, rbrack_t))
     }
 }
 
@@ -137,8 +141,8 @@ impl Rule for AssignmentT {
         token.is(TokenKind::tEQL) || token.is(TokenKind::tOP_ASGN)
     }
 
-    fn parse(parser: &mut Parser) -> Self::Output {
-        parser.take_token()
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
+        Ok(parser.take_token())
     }
 }
 
@@ -149,7 +153,7 @@ impl Rule for MethodNameT {
     fn starts_now(parser: &mut Parser) -> bool {
         let token = parser.current_token();
 
-        at_most_one_is_true([
+        any_is_true([
             token.is(TokenKind::tFID),
             token.is(TokenKind::tIDENTIFIER),
             token.is(TokenKind::tCONSTANT),
@@ -157,11 +161,11 @@ impl Rule for MethodNameT {
         ])
     }
 
-    fn parse(parser: &mut Parser) -> Self::Output {
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
         if Self::starts_now(parser) {
-            parser.take_token()
+            Ok(parser.take_token())
         } else {
-            unreachable!()
+            Err(ParseError::unexpected_token(None, parser.current_token()))
         }
     }
 }
@@ -175,8 +179,7 @@ fn test_call_tail_method() {
         r#"
 s(:send,
   s(:int, "42"), "foo",
-  s(:begin,
-    s(:send, nil, "bar")))
+  s(:send, nil, "bar"))
         "#
     )
 }
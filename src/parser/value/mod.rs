@@ -1,7 +1,13 @@
-use crate::{builder::Builder, parser::base::Rule, Node, Parser, TokenKind};
+use crate::{
+    error::{ParseError, ParseResult},
+    parser::{base::Rule, is_signed_number},
+    Node, Parser, TokenKind,
+};
 
 mod operators;
-use operators::{binary_operator_power, postfix_operator_power, prefix_operator_power};
+use operators::{
+    binary_operator_power, is_non_associative, postfix_operator_power, prefix_operator_power,
+};
 
 mod builders;
 use builders::{build_binary_op, build_postfix_op, build_prefix_op};
@@ -19,65 +25,127 @@ impl Rule for Value {
         Value0::starts_now(parser) || prefix_operator_power(parser.current_token()).is_some()
     }
 
-    fn parse(parser: &mut Parser) -> Self::Output {
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
         Self::parse_bp(parser, 0)
     }
 }
 
 impl Value {
-    fn parse_lhs(parser: &mut Parser) -> Box<Node> {
-        if parser.current_token().is(TokenKind::tLPAREN) {
-            let begin_t = parser.take_token();
-            let lhs = Self::parse_bp(parser, 0);
-            let end_t = parser.expect_token(TokenKind::tRPAREN);
-            Builder::begin(begin_t, vec![*lhs], end_t)
+    /// Parses `arg` in terms of MRI grammar:
+    /// a value that can't be `not`/`and`/`or` expression,
+    /// one-line pattern matching or a modifier statement.
+    /// Used for elements of arrays/hashes/args where `=>` is a pair separator.
+    pub(crate) fn parse_arg(parser: &mut Parser) -> ParseResult<Box<Node>> {
+        let (_, kand_r_bp) = TokenKind::kAND.precedence().unwrap();
+        Self::parse_bp(parser, kand_r_bp)
+    }
+
+    /// Parses a value that can't be one-line pattern matching or a modifier statement,
+    /// like a subject of `case` or a guard of `in` clause.
+    pub(crate) fn parse_expr_value(parser: &mut Parser) -> ParseResult<Box<Node>> {
+        let (kin_l_bp, _) = TokenKind::kIN.precedence().unwrap();
+        Self::parse_bp(parser, kin_l_bp + 1)
+    }
+
+    /// Parses `primary` in terms of MRI grammar: a value without binary operators,
+    /// like a default value of the block parameter where `|` closes the list of params.
+    pub(crate) fn parse_primary(parser: &mut Parser) -> ParseResult<Box<Node>> {
+        Self::parse_bp(parser, u8::MAX)
+    }
+
+    /// Parses modifiers (`if`, `unless`, `while`, `until`, `rescue`) and `and`/`or`
+    /// that follow a statement that is not a value itself, like `a, b = 1 if c`.
+    /// Only operators of the given kinds are accepted.
+    pub(crate) fn parse_stmt_modifiers<const N: usize>(
+        parser: &mut Parser,
+        mut stmt: Box<Node>,
+        modifiers: [TokenKind; N],
+    ) -> ParseResult<Box<Node>> {
+        loop {
+            let op_t = parser.current_token();
+            if parser.newline_before_current_token().is_some() || !op_t.is_one_of(modifiers) {
+                break;
+            }
+            let Some((_, r_bp)) = binary_operator_power(op_t) else {
+                break;
+            };
+            parser.skip_token();
+            stmt = build_binary_op(op_t, stmt, parser, r_bp)?;
+        }
+        Ok(stmt)
+    }
+
+    fn parse_lhs(parser: &mut Parser) -> ParseResult<Box<Node>> {
+        // `(...)` is handled by `Value0`, it can contain statements and multiple assignment targets,
+        // `not(...)` and `defined?(...)` are not prefix operators too
+        if Value0::starts_now(parser) {
+            let value0 = Value0::parse(parser)?;
+            // TODO: repeat CallTail
+            Ok(value0)
         } else if let Some((_, r_bp)) = prefix_operator_power(parser.current_token()) {
             let op_t = parser.take_token();
-            let rhs = Self::parse_bp(parser, r_bp);
-            build_prefix_op(op_t, rhs, parser)
+            let rhs = Self::parse_bp(parser, r_bp)?;
+            Ok(build_prefix_op(op_t, rhs, parser))
         } else {
-            let value0 = Value0::parse(parser);
-            // TODO: repeat CallTail
-            value0
+            Err(ParseError::unexpected_token(None, parser.current_token()))
         }
     }
 
-    fn parse_with_lhs(parser: &mut Parser, mut lhs: Box<Node>, min_bp: u8) -> Box<Node> {
-        loop {
-            let op_t = parser.current_token();
+    fn parse_with_lhs(
+        parser: &mut Parser,
+        mut lhs: Box<Node>,
+        min_bp: u8,
+    ) -> ParseResult<Box<Node>> {
+        // binding power of the last non-associative operator, `a..b..c` and `a == b == c` are invalid
+        let mut non_assoc_bp = None;
 
-            if op_t.is(TokenKind::tEOF) {
+        loop {
+            if parser.current_token().is(TokenKind::tEOF) {
                 break;
             }
 
-            if let Some((l_bp, _)) = postfix_operator_power(op_t) {
-                if l_bp < min_bp {
-                    break;
-                }
-                parser.skip_token();
+            // binary and postfix operators can't start a new line,
+            // it's a new statement (or `in` clause of the `case`)
+            if parser.newline_before_current_token().is_some() {
+                break;
+            }
 
-                lhs = build_postfix_op(op_t, lhs, parser);
-                continue;
+            let token = parser.current_token();
+            if is_signed_number(parser, token) {
+                // `a+1` and `a +1` (after a local variable) is `a + 1`
+                parser.lexer.split_signed_number();
             }
+            let op_t = parser.current_token();
 
             if let Some((l_bp, r_bp)) = binary_operator_power(op_t) {
                 if l_bp < min_bp {
                     break;
                 }
+                if non_assoc_bp == Some(l_bp) {
+                    return Err(ParseError::unexpected_token(None, op_t));
+                }
+                if is_non_associative(op_t) {
+                    non_assoc_bp = Some(l_bp);
+                }
                 parser.skip_token();
 
-                lhs = build_binary_op(op_t, lhs, parser, r_bp);
+                // `1..` is a postfix operator if there's no value after it
+                if postfix_operator_power(op_t).is_some() && !Self::starts_now(parser) {
+                    lhs = build_postfix_op(op_t, lhs, parser)?;
+                } else {
+                    lhs = build_binary_op(op_t, lhs, parser, r_bp)?;
+                }
                 continue;
             }
 
             break;
         }
 
-        lhs
+        Ok(lhs)
     }
 
-    fn parse_bp(parser: &mut Parser, min_bp: u8) -> Box<Node> {
-        let lhs = Self::parse_lhs(parser);
+    fn parse_bp(parser: &mut Parser, min_bp: u8) -> ParseResult<Box<Node>> {
+        let lhs = Self::parse_lhs(parser)?;
 
         Self::parse_with_lhs(parser, lhs, min_bp)
     }
@@ -16,8 +16,14 @@ pub(crate) fn prefix_operator_power(token: Token) -> Option<(u8, u8)> {
 
 pub(crate) fn binary_operator_power(token: Token) -> Option<(u8, u8)> {
     match token.kind {
-        // Assignments that are parsed as "binary operators"
-        TokenKind::tEQL | TokenKind::tOP_ASGN => return token.kind.precedence(),
+        // Assignments that are parsed as "binary operators",
+        // the target is always the closest value on the left: `a && b = c` is `a && (b = c)`
+        TokenKind::tEQL | TokenKind::tOP_ASGN => {
+            let (_, r_bp) = token.kind.precedence()?;
+            return Some((u8::MAX - 1, r_bp));
+        }
+        // `1 -1` is lexed as `1` and `-1`, but the value can't be followed by a number
+        TokenKind::tUMINUS_NUM => return TokenKind::tMINUS.precedence(),
         _ => {}
     }
     match token.kind {
@@ -79,6 +85,26 @@ pub(crate) fn binary_operator_power(token: Token) -> Option<(u8, u8)> {
     }
 }
 
-pub(crate) fn postfix_operator_power(_token: Token) -> Option<(u8, u8)> {
-    None
+pub(crate) fn postfix_operator_power(token: Token) -> Option<(u8, u8)> {
+    match token.kind {
+        // Endless ranges `1..` and `1...`
+        TokenKind::tDOT2 | TokenKind::tDOT3 => token.kind.precedence(),
+        _ => None,
+    }
+}
+
+pub(crate) fn is_non_associative(token: Token) -> bool {
+    matches!(
+        token.kind,
+        // `a..b..c`
+        TokenKind::tDOT2
+            | TokenKind::tDOT3
+            // `a == b == c`
+            | TokenKind::tCMP
+            | TokenKind::tEQ
+            | TokenKind::tEQQ
+            | TokenKind::tNEQ
+            | TokenKind::tMATCH
+            | TokenKind::tNMATCH
+    )
 }
@@ -1,13 +1,15 @@
 use crate::{
-    builder::{ArgsType, Builder, LoopType},
+    builder::{Builder, LoopType},
+    error::{ParseError, ParseResult},
+    nodes::Mlhs,
     parser::{
-        base::{at_most_one_is_true, Maybe1, Repeat1, Rule},
+        base::{any_is_true, Maybe1, Repeat1, Rule},
         value::call_tail::CallTail,
-        Alias, Array, BackRef, Bodystmt, CallArgs, Case, Class, Compstmt, Cvar, DoT,
-        EndlessMethodDef, ForLoop, Gvar, Hash, IfStmt, Ivar, KeywordCmd, KeywordVariable, Lambda,
-        Literal, MaybeBlock, MethodDef, Module, Postexe, Undef, UnlessStmt, Value,
+        Alias, Array, BackRef, Bodystmt, CallArgs, Case, Class, Compstmt, Cvar, DoT, ForLoop, Gvar,
+        Hash, IfStmt, Ivar, KeywordCmd, KeywordVariable, Lambda, Literal, MaybeBlock, MethodDef,
+        Module, Postexe, Undef, UnlessStmt, Value,
     },
-    Node, Parser, TokenKind,
+    Node, Parser, Token, TokenKind,
 };
 
 pub(crate) struct Value0;
@@ -15,7 +17,7 @@ impl Rule for Value0 {
     type Output = Box<Node>;
 
     fn starts_now(parser: &mut Parser) -> bool {
-        at_most_one_is_true([
+        any_is_true([
             Literal::starts_now(parser),
             VarRefOrMethodCall::starts_now(parser),
             Array::starts_now(parser),
@@ -32,7 +34,6 @@ impl Rule for Value0 {
             Module::starts_now(parser),
             MethodDef::starts_now(parser),
             KeywordCmd::starts_now(parser),
-            EndlessMethodDef::starts_now(parser),
             Alias::starts_now(parser),
             Undef::starts_now(parser),
             Postexe::starts_now(parser),
@@ -43,80 +44,78 @@ impl Rule for Value0 {
         ])
     }
 
-    fn parse(parser: &mut Parser) -> Self::Output {
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
         let mut head = if Literal::starts_now(parser) {
-            Literal::parse(parser)
+            Literal::parse(parser)?
         } else if VarRefOrMethodCall::starts_now(parser) {
-            VarRefOrMethodCall::parse(parser)
+            VarRefOrMethodCall::parse(parser)?
         } else if Array::starts_now(parser) {
-            Array::parse(parser)
+            Array::parse(parser)?
         } else if Hash::starts_now(parser) {
-            Hash::parse(parser)
+            Hash::parse(parser)?
         } else if BackRef::starts_now(parser) {
-            BackRef::parse(parser)
+            BackRef::parse(parser)?
         } else if Parenthesized::starts_now(parser) {
-            Parenthesized::parse(parser)
+            Parenthesized::parse(parser)?
         } else if Not::starts_now(parser) {
-            Not::parse(parser)
+            Not::parse(parser)?
         } else if Lambda::starts_now(parser) {
-            Lambda::parse(parser)
+            Lambda::parse(parser)?
         } else if IfStmt::starts_now(parser) {
-            IfStmt::parse(parser)
+            IfStmt::parse(parser)?
         } else if UnlessStmt::starts_now(parser) {
-            UnlessStmt::parse(parser)
+            UnlessStmt::parse(parser)?
         } else if Case::starts_now(parser) {
-            Case::parse(parser)
+            Case::parse(parser)?
         } else if ForLoop::starts_now(parser) {
-            ForLoop::parse(parser)
+            ForLoop::parse(parser)?
         } else if Class::starts_now(parser) {
-            Class::parse(parser)
+            Class::parse(parser)?
         } else if Module::starts_now(parser) {
-            Module::parse(parser)
+            Module::parse(parser)?
         } else if MethodDef::starts_now(parser) {
-            MethodDef::parse(parser)
+            MethodDef::parse(parser)?
         } else if KeywordCmd::starts_now(parser) {
-            KeywordCmd::parse(parser)
-        } else if EndlessMethodDef::starts_now(parser) {
-            EndlessMethodDef::parse(parser)
+            KeywordCmd::parse(parser)?
         } else if Alias::starts_now(parser) {
-            Alias::parse(parser)
+            Alias::parse(parser)?
         } else if Undef::starts_now(parser) {
-            Undef::parse(parser)
+            Undef::parse(parser)?
         } else if Postexe::starts_now(parser) {
-            Postexe::parse(parser)
+            Postexe::parse(parser)?
         } else if parser.current_token().is(TokenKind::kBEGIN) {
             let begin_t = parser.take_token();
-            let body = Bodystmt::parse(parser);
+            let body = Bodystmt::parse(parser)?;
             let statements = if let Some(body) = body {
                 vec![*body]
             } else {
                 vec![]
             };
-            let end_t = parser.expect_token(TokenKind::kEND);
+            let end_t = parser.expect_token(TokenKind::kEND)?;
             Builder::begin(begin_t, statements, end_t)
         } else if parser.current_token().is(TokenKind::tCOLON2) {
             let colon2_t = parser.take_token();
-            let name_t = parser.expect_token(TokenKind::tCONSTANT);
+            let name_t = parser.expect_token(TokenKind::tCONSTANT)?;
             Builder::const_global(colon2_t, name_t, parser.buffer())
         } else if parser.current_token().is(TokenKind::kWHILE) {
             let keyword_t = parser.take_token();
-            let cond = Value::parse(parser);
-            let do_t = DoT::parse(parser);
-            let body = Compstmt::parse(parser);
-            let end_t = parser.expect_token(TokenKind::kEND);
+            let cond = parser.with_do_block_allowed(false, Value::parse)?;
+            let do_t = DoT::parse(parser)?;
+            let body = Compstmt::parse(parser)?;
+            let end_t = parser.expect_token(TokenKind::kEND)?;
             Builder::loop_(LoopType::While, keyword_t, cond, do_t, body, end_t)
         } else if parser.current_token().is(TokenKind::kUNTIL) {
             let keyword_t = parser.take_token();
-            let cond = Value::parse(parser);
-            let do_t = DoT::parse(parser);
-            let body = Compstmt::parse(parser);
-            let end_t = parser.expect_token(TokenKind::kEND);
+            let cond = parser.with_do_block_allowed(false, Value::parse)?;
+            let do_t = DoT::parse(parser)?;
+            let body = Compstmt::parse(parser)?;
+            let end_t = parser.expect_token(TokenKind::kEND)?;
             Builder::loop_(LoopType::Until, keyword_t, cond, do_t, body, end_t)
         } else {
-            unreachable!()
+            return Err(ParseError::unexpected_token(None, parser.current_token()));
         };
 
-        for tail in Repeat1::<CallTail>::parse(parser).into_iter() {
+        for tail in Repeat1::<CallTail>::parse(parser)?.into_iter() {
             match tail {
                 CallTail::ConstAccess { colon2_t, name_t } => {
                     head = Builder::const_fetch(head, colon2_t, name_t, parser.buffer());
@@ -141,7 +140,7 @@ impl Rule for Value0 {
 
                     if let Some((begin_t, args, body, end_t)) = block {
                         head =
-                            Builder::block(head, begin_t, ArgsType::Args(args), Some(body), end_t);
+                            Builder::block(head, begin_t, args, body, end_t, parser.diagnostics());
                     }
                 }
                 CallTail::ArefArgs {
@@ -154,13 +153,13 @@ impl Rule for Value0 {
 
                     if let Some((begin_t, args, body, end_t)) = block {
                         head =
-                            Builder::block(head, begin_t, ArgsType::Args(args), Some(body), end_t);
+                            Builder::block(head, begin_t, args, body, end_t, parser.diagnostics());
                     }
                 }
             }
         }
 
-        head
+        Ok(head)
     }
 }
 #[test]
@@ -215,7 +214,7 @@ impl Rule for VarRefOrMethodCall {
     fn starts_now(parser: &mut Parser) -> bool {
         let token = parser.current_token();
 
-        at_most_one_is_true([
+        any_is_true([
             token.is(TokenKind::tIDENTIFIER),
             token.is(TokenKind::tCONSTANT),
             token.is(TokenKind::tFID),
@@ -226,7 +225,7 @@ impl Rule for VarRefOrMethodCall {
         ])
     }
 
-    fn parse(parser: &mut Parser) -> Self::Output {
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
         if Ivar::starts_now(parser) {
             Ivar::parse(parser)
         } else if Gvar::starts_now(parser) {
@@ -238,8 +237,17 @@ impl Rule for VarRefOrMethodCall {
         } else {
             // tIDENTIFIER/tCONSTANT/tFID
             let name_t = parser.take_token();
-            let (lparen_t, args, rparen_t) = CallArgs::parse(parser);
-            let block = MaybeBlock::parse(parser);
+
+            // known local variable (or numbered parameter), unless it's called like `foo(...)`
+            if name_t.is(TokenKind::tIDENTIFIER)
+                && !parser.current_token().is(TokenKind::tLPAREN)
+                && (is_local_variable(parser, name_t) || is_numparam(parser, name_t))
+            {
+                return Ok(accessible_lvar(parser, name_t));
+            }
+
+            let (lparen_t, args, rparen_t) = CallArgs::parse(parser)?;
+            let block = MaybeBlock::parse(parser)?;
             if lparen_t.is_some() || !args.is_empty() || rparen_t.is_some() || block.is_some() {
                 // method call with args/block/both
                 let mut node = Builder::call_method(
@@ -252,32 +260,59 @@ impl Rule for VarRefOrMethodCall {
                     parser.buffer(),
                 );
                 if let Some((begin_t, args, body, end_t)) = block {
-                    node = Builder::block(node, begin_t, ArgsType::Args(args), Some(body), end_t)
+                    node = Builder::block(node, begin_t, args, body, end_t, parser.diagnostics())
                 }
-                return node;
+                return Ok(node);
             }
 
             match name_t.kind {
-                TokenKind::tIDENTIFIER | TokenKind::tFID => {
-                    // TODO: check for local variable
-                    Builder::call_method(
-                        None,
-                        None,
-                        Some(name_t),
-                        None,
-                        vec![],
-                        None,
-                        parser.buffer(),
-                    )
-                }
+                // undeclared local variable, i.e. a method call without arguments
+                TokenKind::tIDENTIFIER => Ok(accessible_lvar(parser, name_t)),
 
-                TokenKind::tCONSTANT => Builder::const_(name_t, parser.buffer()),
+                TokenKind::tFID => Ok(Builder::call_method(
+                    None,
+                    None,
+                    Some(name_t),
+                    None,
+                    vec![],
+                    None,
+                    parser.buffer(),
+                )),
+
+                TokenKind::tCONSTANT => Ok(Builder::const_(name_t, parser.buffer())),
 
                 _ => unreachable!(),
             }
         }
     }
 }
+
+fn is_local_variable(parser: &Parser, name_t: Token) -> bool {
+    let name = parser
+        .buffer()
+        .slice(name_t.loc.start, name_t.loc.end)
+        .expect("bug: token is out of bounds");
+    parser.static_env().is_declared(name)
+}
+
+// `_1`..`_9` inside a block never take arguments
+fn is_numparam(parser: &Parser, name_t: Token) -> bool {
+    let name = parser
+        .buffer()
+        .slice(name_t.loc.start, name_t.loc.end)
+        .expect("bug: token is out of bounds");
+    matches!(name, [b'_', b'1'..=b'9']) && parser.max_numparam_stack().in_dynamic_block()
+}
+
+fn accessible_lvar(parser: &Parser, name_t: Token) -> Box<Node> {
+    Builder::accessible(
+        Builder::lvar(name_t, parser.buffer()),
+        parser.static_env(),
+        parser.current_arg_stack(),
+        parser.max_numparam_stack(),
+        parser.diagnostics(),
+    )
+}
 #[test]
 fn test_method_call_with_open_args() {
     use crate::testing::assert_parses_rule;
@@ -298,8 +333,7 @@ fn test_method_call_with_paren_args() {
         b"foo(42)",
         r#"
 s(:send, nil, "foo",
-  s(:begin,
-    s(:int, "42")))
+  s(:int, "42"))
         "#
     )
 }
@@ -309,6 +343,20 @@ fn test_method_call_with_no_args() {
     assert_parses_rule!(Value0, b"foo", "s(:send, nil, \"foo\")")
 }
 #[test]
+fn test_local_variable() {
+    let mut parser = Parser::new(b"foo");
+    parser.static_env().declare(b"foo");
+    let ast = Value0::parse(&mut parser).unwrap();
+    assert_eq!(ast.inspect(0), "s(:lvar, \"foo\")");
+}
+#[test]
+fn test_local_variable_called_with_parens() {
+    let mut parser = Parser::new(b"foo(1)");
+    parser.static_env().declare(b"foo");
+    let ast = Value0::parse(&mut parser).unwrap();
+    assert_eq!(ast.inspect(0), "s(:send, nil, \"foo\",\n  s(:int, \"1\"))");
+}
+#[test]
 fn test_value0_tfid() {
     use crate::testing::assert_parses_rule;
     assert_parses_rule!(Value0, b"foo?", "s(:send, nil, \"foo?\")")
@@ -322,16 +370,21 @@ impl Rule for Parenthesized {
         parser.current_token().is(TokenKind::tLPAREN)
     }
 
-    fn parse(parser: &mut Parser) -> Self::Output {
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
         let begin_t = parser.take_token();
-        let body = Compstmt::parse(parser);
-        let statements = if let Some(body) = body {
-            vec![*body]
-        } else {
-            vec![]
-        };
-        let end_t = parser.expect_token(TokenKind::tRPAREN);
-        Builder::begin(begin_t, statements, end_t)
+        let body = Compstmt::parse(parser)?;
+        let end_t = parser.expect_token(TokenKind::tRPAREN)?;
+
+        match body.map(|body| *body) {
+            // (a, b) = list
+            Some(Node::Mlhs(Mlhs {
+                items,
+                begin_l: None,
+                ..
+            })) => Ok(Builder::multi_lhs(Some(begin_t), items, Some(end_t))),
+            Some(body) => Ok(Builder::begin(begin_t, vec![body], end_t)),
+            None => Ok(Builder::begin(begin_t, vec![], end_t)),
+        }
     }
 }
 #[test]
@@ -375,12 +428,12 @@ impl Rule for Not {
         parser.current_token().is(TokenKind::kNOT) && parser.lexer.lookahead_is_lparen()
     }
 
-    fn parse(parser: &mut Parser) -> Self::Output {
+    fn parse(parser: &mut Parser) -> ParseResult<Self::Output> {
         let not_t = parser.take_token();
-        let begin_t = parser.expect_token(TokenKind::tLPAREN);
-        let receiver = Maybe1::<Value>::parse(parser);
-        let end_t = parser.expect_token(TokenKind::tRPAREN);
-        Builder::not_op(not_t, Some(begin_t), receiver, Some(end_t))
+        let begin_t = parser.expect_token(TokenKind::tLPAREN)?;
+        let receiver = Maybe1::<Value>::parse(parser)?;
+        let end_t = parser.expect_token(TokenKind::tRPAREN)?;
+        Ok(Builder::not_op(not_t, Some(begin_t), receiver, Some(end_t)))
     }
 }
 #[test]
@@ -23,7 +23,7 @@ impl TokenKind {
         use TokenKind::*;
 
         match self {
-            kIF | kUNLESS | kWHILE | kUNTIL | kIN => non_assoc!(1),
+            kIF | kUNLESS | kWHILE | kUNTIL | kIN | tASSOC => non_assoc!(1),
             kOR | kAND => left_assoc!(2),
             kNOT => right_assoc!(3),
             kDEFINED => non_assoc!(4),
@@ -0,0 +1,84 @@
+use std::cell::RefCell;
+use std::collections::HashSet;
+
+// Set of local variables that are visible in the current scope,
+// it's the only way to tell `foo` (local variable) from `foo` (method call).
+//
+// `def`/`class`/`module` start a new static scope that doesn't see outer variables,
+// blocks and lambdas start a dynamic scope that inherits them.
+//
+// Just like `Diagnostics` it's used by builder functions that only get `&StaticEnvironment`.
+#[derive(Debug, Default)]
+pub(crate) struct StaticEnvironment {
+    variables: RefCell<HashSet<Vec<u8>>>,
+    stack: RefCell<Vec<HashSet<Vec<u8>>>>,
+}
+
+impl StaticEnvironment {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    pub(crate) fn extend_static(&self) {
+        let variables = std::mem::take(&mut *self.variables.borrow_mut());
+        self.stack.borrow_mut().push(variables);
+    }
+
+    pub(crate) fn extend_dynamic(&self) {
+        let variables = self.variables.borrow().clone();
+        self.stack.borrow_mut().push(variables);
+    }
+
+    pub(crate) fn unextend(&self) {
+        let variables = self
+            .stack
+            .borrow_mut()
+            .pop()
+            .expect("bug: unextend is called without extend");
+        *self.variables.borrow_mut() = variables;
+    }
+
+    pub(crate) fn declare(&self, name: &[u8]) {
+        self.variables.borrow_mut().insert(name.to_vec());
+    }
+
+    pub(crate) fn is_declared(&self, name: &[u8]) -> bool {
+        self.variables.borrow().contains(name)
+    }
+}
+
+#[test]
+fn test_declare() {
+    let env = StaticEnvironment::new();
+    assert!(!env.is_declared(b"foo"));
+    env.declare(b"foo");
+    assert!(env.is_declared(b"foo"));
+}
+
+#[test]
+fn test_extend_static() {
+    let env = StaticEnvironment::new();
+    env.declare(b"foo");
+
+    env.extend_static();
+    assert!(!env.is_declared(b"foo"));
+    env.declare(b"bar");
+
+    env.unextend();
+    assert!(env.is_declared(b"foo"));
+    assert!(!env.is_declared(b"bar"));
+}
+
+#[test]
+fn test_extend_dynamic() {
+    let env = StaticEnvironment::new();
+    env.declare(b"foo");
+
+    env.extend_dynamic();
+    assert!(env.is_declared(b"foo"));
+    env.declare(b"bar");
+
+    env.unextend();
+    assert!(env.is_declared(b"foo"));
+    assert!(!env.is_declared(b"bar"));
+}
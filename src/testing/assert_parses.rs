@@ -5,7 +5,8 @@ macro_rules! assert_parses_rule {
         let mut parser = Parser::new($src).debug();
         type TestRule = $rule;
         assert!(TestRule::starts_now(&mut parser));
-        let ast: Box<Node> = TestRule::parse(&mut parser);
+        let ast: Box<Node> =
+            TestRule::parse(&mut parser).unwrap_or_else(|err| panic!("failed to parse: {}", err));
 
         let expected: &str = $expected;
         dbg!(&ast);
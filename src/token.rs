@@ -182,9 +182,7 @@ pub enum TokenKind {
     tEQQ,          // "==="
     tEH,           // "?"
 
-    tEMBEDDED_COMMENT_START, // "=begin"
-    tCOMMENT,                // comment content
-    tEMBEDDED_COMMENT_END,   // "=end"
+    tCOMMENT, // "# ..." or "=begin ... =end"
 
     tEOF,
 
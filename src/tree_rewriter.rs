@@ -0,0 +1,357 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::{Buffer, Loc};
+
+/// Kind of the error returned when an edit can't be queued
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RewriteErrorKind {
+    /// Location is outside of the buffer
+    OutOfBounds,
+    /// Replaced (or removed) range overlaps with another replaced range
+    OverlappingReplacements,
+    /// Insertion is strictly inside of a replaced range (or vice versa)
+    SwallowedInsertion,
+}
+
+impl RewriteErrorKind {
+    fn message(&self) -> &'static str {
+        match self {
+            Self::OutOfBounds => "location is outside of the buffer",
+            Self::OverlappingReplacements => "replacement overlaps with another replacement",
+            Self::SwallowedInsertion => "insertion is inside of a replaced range",
+        }
+    }
+}
+
+/// An error returned when an edit conflicts with already queued edits
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RewriteError {
+    /// Kind of the error
+    pub kind: RewriteErrorKind,
+
+    /// Location of the rejected edit
+    pub loc: Loc,
+
+    /// Location of the already queued edit that conflicts with it (if any)
+    pub conflicting_loc: Option<Loc>,
+}
+
+impl std::fmt::Display for RewriteError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} at {:?}", self.kind.message(), self.loc)?;
+        if let Some(conflicting_loc) = self.conflicting_loc {
+            write!(f, ", conflicts with {:?}", conflicting_loc)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for RewriteError {}
+
+// Texts inserted at the same offset
+#[derive(Debug, Default)]
+struct Insertions {
+    // texts inserted after a range that ends here, in the order of queueing
+    after: Vec<String>,
+    // texts inserted before a range that starts here, in the order of queueing
+    before: Vec<String>,
+}
+
+/// Queues edits of the source code keyed by `Loc` and applies them all at once,
+/// like `Parser::Source::TreeRewriter` of whitequark/parser.
+///
+/// All locations refer to the original buffer, so edits can be queued in any order.
+/// An edit that conflicts with already queued edits is rejected:
+/// replaced ranges can't overlap (unless it's the same replacement queued twice)
+/// and nothing can be inserted strictly inside of a replaced range.
+///
+/// Repeated insertions at the same place nest like wrapping does:
+/// later `insert_before` goes before earlier ones, later `insert_after` goes after them.
+#[derive(Debug)]
+pub struct TreeRewriter<'a> {
+    input: &'a Buffer,
+    replacements: Vec<(Loc, String)>,
+    insertions: BTreeMap<usize, Insertions>,
+}
+
+impl<'a> TreeRewriter<'a> {
+    /// Constructs a rewriter of the given buffer
+    pub fn new(input: &'a Buffer) -> Self {
+        Self {
+            input,
+            replacements: vec![],
+            insertions: BTreeMap::new(),
+        }
+    }
+
+    /// Replaces source code of `loc` with `text`
+    pub fn replace(&mut self, loc: Loc, text: &str) -> Result<(), RewriteError> {
+        self.check_bounds(loc)?;
+
+        for (other, other_text) in self.replacements.iter() {
+            if *other == loc && other_text == text {
+                // the same edit, nothing to do
+                return Ok(());
+            }
+            if loc.start < other.end && other.start < loc.end {
+                return Err(RewriteError {
+                    kind: RewriteErrorKind::OverlappingReplacements,
+                    loc,
+                    conflicting_loc: Some(*other),
+                });
+            }
+        }
+        if let Some((&pos, _)) = self
+            .insertions
+            .range(loc.start + 1..loc.end.max(loc.start + 1))
+            .next()
+        {
+            return Err(RewriteError {
+                kind: RewriteErrorKind::SwallowedInsertion,
+                loc,
+                conflicting_loc: Some(Loc {
+                    start: pos,
+                    end: pos,
+                }),
+            });
+        }
+
+        self.replacements.push((loc, text.to_string()));
+        Ok(())
+    }
+
+    /// Removes source code of `loc`
+    pub fn remove(&mut self, loc: Loc) -> Result<(), RewriteError> {
+        self.replace(loc, "")
+    }
+
+    /// Inserts `text` right before `loc`
+    pub fn insert_before(&mut self, loc: Loc, text: &str) -> Result<(), RewriteError> {
+        self.check_insertion(loc, loc.start)?;
+        self.insertions
+            .entry(loc.start)
+            .or_default()
+            .before
+            .push(text.to_string());
+        Ok(())
+    }
+
+    /// Inserts `text` right after `loc`
+    pub fn insert_after(&mut self, loc: Loc, text: &str) -> Result<(), RewriteError> {
+        self.check_insertion(loc, loc.end)?;
+        self.insertions
+            .entry(loc.end)
+            .or_default()
+            .after
+            .push(text.to_string());
+        Ok(())
+    }
+
+    /// Inserts `before` right before `loc` and `after` right after it,
+    /// either both insertions are queued or none
+    pub fn wrap(&mut self, loc: Loc, before: &str, after: &str) -> Result<(), RewriteError> {
+        self.check_insertion(loc, loc.start)?;
+        self.check_insertion(loc, loc.end)?;
+        self.insert_before(loc, before)?;
+        self.insert_after(loc, after)
+    }
+
+    /// Returns source code with all queued edits applied
+    pub fn process(&self) -> Vec<u8> {
+        let input = self.input.bytes();
+
+        let mut replacements: Vec<&(Loc, String)> = self.replacements.iter().collect();
+        // empty replacements (which are insertions) go before non-empty ones starting at the same place
+        replacements.sort_by_key(|(loc, _)| (loc.start, loc.end));
+
+        let mut positions: BTreeSet<usize> = self.insertions.keys().copied().collect();
+        positions.extend(replacements.iter().map(|(loc, _)| loc.start));
+
+        let mut output = vec![];
+        let mut replacements = replacements.into_iter().peekable();
+        let mut cursor = 0;
+
+        for pos in positions {
+            // insertions and replacements never start inside of a replaced range
+            debug_assert!(pos >= cursor);
+            output.extend_from_slice(&input[cursor..pos]);
+            cursor = pos;
+
+            if let Some(insertions) = self.insertions.get(&pos) {
+                for text in insertions.after.iter() {
+                    output.extend_from_slice(text.as_bytes());
+                }
+                for text in insertions.before.iter().rev() {
+                    output.extend_from_slice(text.as_bytes());
+                }
+            }
+
+            while let Some((loc, text)) = replacements.next_if(|(loc, _)| loc.start == pos) {
+                output.extend_from_slice(text.as_bytes());
+                cursor = loc.end;
+            }
+        }
+        output.extend_from_slice(&input[cursor..]);
+
+        output
+    }
+
+    fn check_bounds(&self, loc: Loc) -> Result<(), RewriteError> {
+        if loc.start > loc.end || loc.end > self.input.bytes().len() {
+            return Err(RewriteError {
+                kind: RewriteErrorKind::OutOfBounds,
+                loc,
+                conflicting_loc: None,
+            });
+        }
+        Ok(())
+    }
+
+    fn check_insertion(&self, loc: Loc, pos: usize) -> Result<(), RewriteError> {
+        self.check_bounds(loc)?;
+
+        for (other, _) in self.replacements.iter() {
+            if other.start < pos && pos < other.end {
+                return Err(RewriteError {
+                    kind: RewriteErrorKind::SwallowedInsertion,
+                    loc,
+                    conflicting_loc: Some(*other),
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{RewriteError, RewriteErrorKind, TreeRewriter};
+    use crate::{loc::loc, Buffer, Node, Parser};
+
+    fn rewritten(rewriter: &TreeRewriter) -> String {
+        String::from_utf8(rewriter.process()).unwrap()
+    }
+
+    #[test]
+    fn test_no_edits() {
+        let input = Buffer::new(b"foo(1)");
+        let rewriter = TreeRewriter::new(&input);
+        assert_eq!(rewritten(&rewriter), "foo(1)");
+    }
+
+    #[test]
+    fn test_all_edits() {
+        let input = Buffer::new(b"foo(1, 2, 3)");
+        let mut rewriter = TreeRewriter::new(&input);
+        // edits are queued in random order
+        rewriter.replace(loc!(10, 11), "three").unwrap();
+        rewriter.replace(loc!(0, 3), "bar").unwrap();
+        rewriter.remove(loc!(5, 8)).unwrap();
+        rewriter.wrap(loc!(4, 5), "[", "]").unwrap();
+        rewriter.insert_before(loc!(0, 3), "self.").unwrap();
+        rewriter.insert_after(loc!(0, 12), ";").unwrap();
+        assert_eq!(rewritten(&rewriter), "self.bar([1], three);");
+    }
+
+    #[test]
+    fn test_nested_insertions() {
+        let input = Buffer::new(b"a + b");
+        let mut rewriter = TreeRewriter::new(&input);
+        rewriter.wrap(loc!(0, 5), "(", ")").unwrap();
+        rewriter.wrap(loc!(0, 5), "[", "]").unwrap();
+        rewriter.insert_before(loc!(0, 5), "x = ").unwrap();
+        rewriter.insert_after(loc!(0, 1), " ").unwrap();
+        rewriter.insert_before(loc!(4, 5), "-").unwrap();
+        // `after` of the left range goes before `before` of the right range
+        rewriter.insert_after(loc!(0, 4), "|").unwrap();
+        assert_eq!(rewritten(&rewriter), "x = [(a  + |-b)]");
+    }
+
+    #[test]
+    fn test_insertions_around_replacement() {
+        let input = Buffer::new(b"foo");
+        let mut rewriter = TreeRewriter::new(&input);
+        rewriter.replace(loc!(0, 3), "bar").unwrap();
+        rewriter.wrap(loc!(0, 3), "<", ">").unwrap();
+        assert_eq!(rewritten(&rewriter), "<bar>");
+    }
+
+    #[test]
+    fn test_overlapping_replacements() {
+        let input = Buffer::new(b"foo.bar");
+        let mut rewriter = TreeRewriter::new(&input);
+        rewriter.replace(loc!(0, 4), "baz.").unwrap();
+        // the same replacement twice is fine
+        rewriter.replace(loc!(0, 4), "baz.").unwrap();
+        assert_eq!(
+            rewriter.remove(loc!(3, 7)),
+            Err(RewriteError {
+                kind: RewriteErrorKind::OverlappingReplacements,
+                loc: loc!(3, 7),
+                conflicting_loc: Some(loc!(0, 4)),
+            })
+        );
+        assert_eq!(
+            rewriter.replace(loc!(0, 4), "qux.").map_err(|err| err.kind),
+            Err(RewriteErrorKind::OverlappingReplacements)
+        );
+        // adjacent ranges don't overlap
+        rewriter.replace(loc!(4, 7), "qux").unwrap();
+        assert_eq!(rewritten(&rewriter), "baz.qux");
+    }
+
+    #[test]
+    fn test_swallowed_insertion() {
+        let input = Buffer::new(b"foo.bar");
+        let mut rewriter = TreeRewriter::new(&input);
+        rewriter.remove(loc!(0, 4)).unwrap();
+        assert_eq!(
+            rewriter.insert_after(loc!(0, 3), "!"),
+            Err(RewriteError {
+                kind: RewriteErrorKind::SwallowedInsertion,
+                loc: loc!(0, 3),
+                conflicting_loc: Some(loc!(0, 4)),
+            })
+        );
+        // `wrap` is atomic
+        assert!(rewriter.wrap(loc!(3, 7), "(", ")").is_err());
+
+        let mut rewriter = TreeRewriter::new(&input);
+        rewriter.insert_before(loc!(4, 7), "&").unwrap();
+        assert_eq!(
+            rewriter.replace(loc!(0, 7), "x").map_err(|err| err.kind),
+            Err(RewriteErrorKind::SwallowedInsertion)
+        );
+        assert_eq!(rewritten(&rewriter), "foo.&bar");
+    }
+
+    #[test]
+    fn test_out_of_bounds() {
+        let input = Buffer::new(b"foo");
+        let mut rewriter = TreeRewriter::new(&input);
+        assert_eq!(
+            rewriter.replace(loc!(2, 4), "x").map_err(|err| err.kind),
+            Err(RewriteErrorKind::OutOfBounds)
+        );
+        assert_eq!(
+            rewriter
+                .insert_after(loc!(4, 4), "x")
+                .map_err(|err| err.kind),
+            Err(RewriteErrorKind::OutOfBounds)
+        );
+    }
+
+    #[test]
+    fn test_node_locations() {
+        let result = Parser::new(b"foo.bar(1)\n").parse().unwrap();
+        let Some(Node::Send(send)) = result.ast.as_deref() else {
+            panic!("expected a send")
+        };
+
+        let mut rewriter = TreeRewriter::new(&result.input);
+        rewriter.replace(send.dot_l.unwrap(), "&.").unwrap();
+        rewriter.replace(send.selector_l.unwrap(), "baz").unwrap();
+        rewriter.wrap(send.expression_l, "(", ")").unwrap();
+        assert_eq!(rewritten(&rewriter), "(foo&.baz(1))\n");
+    }
+}